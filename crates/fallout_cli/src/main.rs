@@ -4,21 +4,25 @@ use std::fs;
 use std::io::Cursor;
 use std::path::{Path, PathBuf};
 use std::process;
-use std::time::{SystemTime, UNIX_EPOCH};
 
 use clap::{Args, Parser, Subcommand, ValueEnum};
+use fallout_core::common::atomic_write::write_file_atomically;
 use fallout_core::core_api::{
-    Capabilities, CapabilityIssue, Engine, Game as CoreGame, ItemCatalog, ResolvedInventoryEntry,
-    Session, TraitCatalog, TraitEntry, detect_install_dir_from_save_path,
+    Capabilities, CapabilityIssue, CharacterRule, Engine, EquipmentSlot, Game as CoreGame,
+    GlobalVarCatalog, ItemCatalog, Palette, Preferences, ResolvedInventoryEntry, Session,
+    SlotFileKind, TraitCatalog, TraitEntry, detect_install_dir_from_save_path,
 };
 use fallout_core::fallout1;
 use fallout_core::fallout2;
 use fallout_core::gender::Gender;
 use fallout_core::layout::{FileLayout, SectionId};
+use fallout_core::sfall::FakePerk;
+use fallout_core::slots::{list_slots, list_slots_with_levels};
 use fallout_render::{
-    FieldSelection as RenderFieldSelection, JsonStyle, TextRenderOptions,
-    render_classic_sheet_with_inventory_and_traits, render_json_full_from_export_with_inventory,
-    render_json_selected_from_export_with_inventory,
+    FieldSelection as RenderFieldSelection, JsonStyle, TextRenderOptions, decode_png_rgba,
+    render_automap_png, render_classic_sheet_with_inventory_and_traits,
+    render_json_full_from_export_with_inventory, render_json_selected_from_export_with_inventory,
+    render_thumbnail_png,
 };
 use serde_json::{Map as JsonMap, Value as JsonValue};
 
@@ -34,6 +38,45 @@ enum GenderArg {
     Female,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum EquipmentSlotArg {
+    Armor,
+    LeftHand,
+    RightHand,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct EquipItemArg {
+    slot: EquipmentSlotArg,
+    pid: i32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct WeaponAmmoArg {
+    pid: i32,
+    ammo_pid: i32,
+    rounds: i32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct KillCountAssignmentArg {
+    index: usize,
+    count: i32,
+}
+
+#[derive(Debug, Clone)]
+struct PreferenceArg {
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TeleportArg {
+    map_id: i16,
+    elevation: i16,
+    tile: i32,
+}
+
 #[derive(Debug, Clone, Copy)]
 struct TraitAssignmentArg {
     slot: usize,
@@ -46,6 +89,18 @@ struct PerkAssignmentArg {
     rank: i32,
 }
 
+#[derive(Debug, Clone, Copy)]
+struct GlobalVarAssignmentArg {
+    index: usize,
+    value: i32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct QueueEventTimeArg {
+    index: usize,
+    time: u32,
+}
+
 #[derive(Debug, Clone, Copy)]
 struct ItemQuantityArg {
     pid: i32,
@@ -64,6 +119,19 @@ enum CommandSet {
         #[command(subcommand)]
         command: DebugSubcommand,
     },
+    /// List the save slots in a SAVEGAME directory.
+    Slots(SlotsArgs),
+}
+
+#[derive(Debug, Args)]
+struct SlotsArgs {
+    #[arg(long)]
+    json: bool,
+    /// Also show each character's level, which parses every save in full.
+    #[arg(long)]
+    levels: bool,
+    #[arg(value_name = "SAVEGAME")]
+    path: PathBuf,
 }
 
 #[derive(Debug, Subcommand)]
@@ -73,6 +141,10 @@ enum DebugSubcommand {
     Section(DebugSectionArgs),
     Validate(DebugValidateArgs),
     Compare(DebugCompareArgs),
+    Globals(DebugGlobalsArgs),
+    Thumbnail(DebugThumbnailArgs),
+    Automap(DebugAutomapArgs),
+    Sfall(DebugSfallArgs),
 }
 
 #[derive(Debug, Clone, Args, Default)]
@@ -122,6 +194,9 @@ struct DebugValidateArgs {
     json: bool,
     #[arg(long)]
     strict: bool,
+    /// Also check the character against the game's rules.
+    #[arg(long)]
+    rules: bool,
     #[arg(value_name = "SAVE.DAT")]
     path: PathBuf,
 }
@@ -144,6 +219,56 @@ struct DebugSectionArgs {
     path: PathBuf,
 }
 
+#[derive(Debug, Args)]
+struct DebugGlobalsArgs {
+    #[command(flatten)]
+    hint: DebugHintArgs,
+    #[arg(long)]
+    json: bool,
+    #[arg(long)]
+    nonzero: bool,
+    #[arg(long, value_name = "INSTALL_DIR")]
+    install_dir: Option<PathBuf>,
+    #[arg(value_name = "SAVE.DAT")]
+    path: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct DebugThumbnailArgs {
+    #[command(flatten)]
+    hint: DebugHintArgs,
+    /// Where to write the thumbnail PNG.
+    #[arg(long, value_name = "PNG")]
+    out: PathBuf,
+    #[arg(long, value_name = "INSTALL_DIR")]
+    install_dir: Option<PathBuf>,
+    #[arg(value_name = "SAVE.DAT")]
+    path: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct DebugAutomapArgs {
+    #[arg(long)]
+    json: bool,
+    /// Render one map's explored hexes as a PNG (needs --out).
+    #[arg(long, value_name = "MAP", requires = "out")]
+    map: Option<usize>,
+    #[arg(long, value_name = "ELEVATION", default_value_t = 0, requires = "map")]
+    elevation: usize,
+    #[arg(long, value_name = "PNG", requires = "map")]
+    out: Option<PathBuf>,
+    #[arg(value_name = "SAVE.DAT")]
+    path: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct DebugSfallArgs {
+    #[arg(long)]
+    json: bool,
+    #[arg(value_name = "SAVE.DAT")]
+    path: PathBuf,
+}
+
 #[derive(Debug, Args)]
 struct DebugCompareArgs {
     #[arg(long)]
@@ -235,6 +360,8 @@ struct Cli {
     set_age: Option<i32>,
     #[arg(long = "set-level")]
     set_level: Option<i32>,
+    #[arg(long = "level-up-to", value_name = "LEVEL")]
+    level_up_to: Option<i32>,
     #[arg(long = "set-xp")]
     set_xp: Option<i32>,
     #[arg(long = "set-skill-points")]
@@ -259,8 +386,20 @@ struct Cli {
     set_agility: Option<i32>,
     #[arg(long = "set-luck")]
     set_luck: Option<i32>,
+    #[arg(long = "recalculate-derived-stats")]
+    recalculate_derived_stats: bool,
     #[arg(long = "set-hp")]
     set_hp: Option<i32>,
+    #[arg(long = "set-radiation")]
+    set_radiation: Option<i32>,
+    #[arg(long = "set-poison")]
+    set_poison: Option<i32>,
+    #[arg(
+        long = "set-preference",
+        value_name = "NAME=VALUE",
+        value_parser = parse_preference
+    )]
+    set_preference: Vec<PreferenceArg>,
     #[arg(
         long = "set-trait",
         value_name = "SLOT:INDEX",
@@ -277,6 +416,37 @@ struct Cli {
     set_perk: Vec<PerkAssignmentArg>,
     #[arg(long = "clear-perk", value_name = "INDEX", value_parser = parse_usize_value)]
     clear_perk: Vec<usize>,
+    /// Refuse --set-perk ranks above the perk's maximum.
+    #[arg(long = "enforce-perk-max")]
+    enforce_perk_max: bool,
+    /// Clear an addiction perk and its pending withdrawal and drug-effect
+    /// events (Fallout 1; needs item prototypes).
+    #[arg(long = "cure-addiction", value_name = "INDEX", value_parser = parse_usize_value)]
+    cure_addiction: Vec<usize>,
+    /// Replace the load-menu thumbnail with a PNG image (scaled to 224x133
+    /// and quantized to color.pal from the install dir).
+    #[arg(long = "set-thumbnail", value_name = "PNG")]
+    set_thumbnail: Option<PathBuf>,
+    #[arg(
+        long = "set-tagged-skills",
+        value_name = "INDEX,...",
+        value_delimiter = ',',
+        num_args = 0..,
+        value_parser = parse_usize_value
+    )]
+    set_tagged_skills: Option<Vec<usize>>,
+    #[arg(
+        long = "set-kill-count",
+        value_name = "INDEX:COUNT",
+        value_parser = parse_kill_count_assignment
+    )]
+    set_kill_count: Vec<KillCountAssignmentArg>,
+    #[arg(
+        long = "set-global",
+        value_name = "INDEX:VALUE",
+        value_parser = parse_global_var_assignment
+    )]
+    set_global: Vec<GlobalVarAssignmentArg>,
     #[arg(
         long = "add-item",
         value_name = "PID:QTY",
@@ -295,6 +465,50 @@ struct Cli {
         value_parser = parse_remove_item
     )]
     remove_item: Vec<RemoveItemArg>,
+    #[arg(
+        long = "equip",
+        value_name = "SLOT:PID",
+        value_parser = parse_equip_item
+    )]
+    equip: Vec<EquipItemArg>,
+    #[arg(long = "unequip", value_name = "SLOT")]
+    unequip: Vec<EquipmentSlotArg>,
+    #[arg(
+        long = "set-weapon-ammo",
+        value_name = "PID:AMMO_PID:ROUNDS",
+        value_parser = parse_weapon_ammo
+    )]
+    set_weapon_ammo: Vec<WeaponAmmoArg>,
+    #[arg(
+        long = "teleport",
+        value_name = "MAP_ID:ELEVATION:TILE",
+        value_parser = parse_teleport
+    )]
+    teleport: Option<TeleportArg>,
+    #[arg(long = "set-game-time", value_name = "TICKS")]
+    set_game_time: Option<u32>,
+    #[arg(long = "advance-game-time", value_name = "TICKS")]
+    advance_game_time: Option<u32>,
+    #[arg(
+        long = "set-queue-event-time",
+        value_name = "INDEX:TIME",
+        value_parser = parse_queue_event_time
+    )]
+    set_queue_event_time: Vec<QueueEventTimeArg>,
+    #[arg(
+        long = "remove-queue-event",
+        value_name = "INDEX",
+        value_parser = parse_usize_value
+    )]
+    remove_queue_event: Vec<usize>,
+    /// Reveal a map on the automap; writes AUTOMAP.SAV and the map's save
+    /// next to the output.
+    #[arg(long = "reveal-automap", value_name = "MAP")]
+    reveal_automap: Vec<i32>,
+    /// Forget what was explored on a map; writes AUTOMAP.SAV (and the map's
+    /// save, when the slot has one) next to the output.
+    #[arg(long = "clear-automap", value_name = "MAP")]
+    clear_automap: Vec<i32>,
     #[arg(long)]
     backup: bool,
     #[arg(long)]
@@ -625,6 +839,7 @@ fn main() {
     let fields = FieldSelection::from_cli(&cli);
     let requested_age_edit = cli.set_age;
     let requested_level_edit = cli.set_level;
+    let requested_level_up = cli.level_up_to;
     let requested_xp_edit = cli.set_xp;
     let requested_skill_points_edit = cli.set_skill_points;
     let requested_reputation_edit = cli.set_reputation;
@@ -634,9 +849,24 @@ fn main() {
     let requested_clear_traits = cli.clear_trait.as_slice();
     let requested_set_perks = cli.set_perk.as_slice();
     let requested_clear_perks = cli.clear_perk.as_slice();
+    let requested_cure_addictions = cli.cure_addiction.as_slice();
+    let requested_thumbnail = cli.set_thumbnail.as_deref();
+    let requested_tagged_skills = cli.set_tagged_skills.as_deref();
+    let requested_kill_counts = cli.set_kill_count.as_slice();
+    let requested_set_globals = cli.set_global.as_slice();
     let requested_add_items = cli.add_item.as_slice();
     let requested_set_item_qty = cli.set_item_qty.as_slice();
     let requested_remove_items = cli.remove_item.as_slice();
+    let requested_equips = cli.equip.as_slice();
+    let requested_unequips = cli.unequip.as_slice();
+    let requested_weapon_ammo = cli.set_weapon_ammo.as_slice();
+    let requested_teleport = cli.teleport;
+    let requested_game_time = cli.set_game_time;
+    let requested_game_time_advance = cli.advance_game_time;
+    let requested_queue_event_times = cli.set_queue_event_time.as_slice();
+    let requested_remove_queue_events = cli.remove_queue_event.as_slice();
+    let requested_automap_reveals = cli.reveal_automap.as_slice();
+    let requested_automap_clears = cli.clear_automap.as_slice();
     let special_edits: [(usize, Option<i32>); 7] = [
         (0, cli.set_strength),
         (1, cli.set_perception),
@@ -647,23 +877,47 @@ fn main() {
         (6, cli.set_luck),
     ];
     let has_special_edits = special_edits.iter().any(|(_, v)| v.is_some());
+    let requested_derived_recalculation = cli.recalculate_derived_stats;
     let requested_hp_edit = cli.set_hp;
+    let requested_radiation_edit = cli.set_radiation;
+    let requested_poison_edit = cli.set_poison;
+    let requested_preferences = cli.set_preference.as_slice();
     let has_edits = requested_age_edit.is_some()
         || requested_level_edit.is_some()
+        || requested_level_up.is_some()
         || requested_xp_edit.is_some()
         || requested_skill_points_edit.is_some()
         || requested_reputation_edit.is_some()
         || requested_karma_edit.is_some()
         || requested_gender_edit.is_some()
         || has_special_edits
+        || requested_derived_recalculation
         || requested_hp_edit.is_some()
+        || requested_radiation_edit.is_some()
+        || requested_poison_edit.is_some()
+        || !requested_preferences.is_empty()
         || !requested_set_traits.is_empty()
         || !requested_clear_traits.is_empty()
         || !requested_set_perks.is_empty()
         || !requested_clear_perks.is_empty()
+        || !requested_cure_addictions.is_empty()
+        || requested_thumbnail.is_some()
+        || requested_tagged_skills.is_some()
+        || !requested_kill_counts.is_empty()
+        || !requested_set_globals.is_empty()
         || !requested_add_items.is_empty()
         || !requested_set_item_qty.is_empty()
-        || !requested_remove_items.is_empty();
+        || !requested_remove_items.is_empty()
+        || !requested_equips.is_empty()
+        || !requested_unequips.is_empty()
+        || !requested_weapon_ammo.is_empty()
+        || requested_teleport.is_some()
+        || requested_game_time.is_some()
+        || requested_game_time_advance.is_some()
+        || !requested_queue_event_times.is_empty()
+        || !requested_remove_queue_events.is_empty()
+        || !requested_automap_reveals.is_empty()
+        || !requested_automap_clears.is_empty();
 
     if has_edits && cli.output.is_none() {
         eprintln!("--set-* flags require --output <PATH>");
@@ -744,6 +998,34 @@ fn main() {
             process::exit(1);
         });
     }
+    if let Some(radiation) = requested_radiation_edit {
+        session.set_radiation(radiation).unwrap_or_else(|e| {
+            eprintln!("Error applying radiation edit: {e}");
+            process::exit(1);
+        });
+    }
+    if let Some(poison) = requested_poison_edit {
+        session.set_poison(poison).unwrap_or_else(|e| {
+            eprintln!("Error applying poison edit: {e}");
+            process::exit(1);
+        });
+    }
+    if !requested_preferences.is_empty() {
+        let Some(mut preferences) = session.preferences().cloned() else {
+            eprintln!("Error applying preference edits: Fallout 1 preferences are not decoded");
+            process::exit(1);
+        };
+        for request in requested_preferences {
+            apply_preference(&mut preferences, request).unwrap_or_else(|e| {
+                eprintln!("Error applying preference {}: {e}", request.name);
+                process::exit(1);
+            });
+        }
+        session.set_preferences(&preferences).unwrap_or_else(|e| {
+            eprintln!("Error applying preference edits: {e}");
+            process::exit(1);
+        });
+    }
     for slot in requested_clear_traits {
         session.clear_trait(*slot).unwrap_or_else(|e| {
             eprintln!("Error clearing trait slot {}: {e}", slot);
@@ -768,38 +1050,214 @@ fn main() {
         });
     }
     for assignment in requested_set_perks {
+        if cli.enforce_perk_max {
+            session.set_perk_rank_within_max(assignment.index, assignment.rank)
+        } else {
+            session.set_perk_rank(assignment.index, assignment.rank)
+        }
+        .unwrap_or_else(|e| {
+            eprintln!(
+                "Error setting perk {} rank {}: {e}",
+                assignment.index, assignment.rank
+            );
+            process::exit(1);
+        });
+    }
+    // Matching drug-effect events to the drug needs its prototype.
+    if !requested_cure_addictions.is_empty() {
+        let catalog = load_item_catalog(path, cli.install_dir.as_deref()).unwrap_or_else(|e| {
+            eprintln!("Error loading item prototypes to cure addictions: {e}");
+            process::exit(1);
+        });
+        for perk_index in requested_cure_addictions {
+            session
+                .cure_addiction(*perk_index, &catalog)
+                .unwrap_or_else(|e| {
+                    eprintln!("Error curing addiction {perk_index}: {e}");
+                    process::exit(1);
+                });
+        }
+    }
+    if let Some(image_path) = requested_thumbnail {
+        let image = fs::read(image_path)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| decode_png_rgba(&bytes))
+            .unwrap_or_else(|e| {
+                eprintln!("Error reading thumbnail {}: {e}", image_path.display());
+                process::exit(1);
+            });
+        // The game shows the thumbnail with color.pal, so quantizing to
+        // anything else would scramble its colours.
+        let palette = load_palette(path, cli.install_dir.as_deref()).unwrap_or_else(|e| {
+            eprintln!("Error loading the game palette for the thumbnail: {e}");
+            process::exit(1);
+        });
         session
-            .set_perk_rank(assignment.index, assignment.rank)
+            .set_thumbnail(
+                &image.rgba,
+                image.width as usize,
+                image.height as usize,
+                &palette,
+            )
+            .unwrap_or_else(|e| {
+                eprintln!("Error setting thumbnail: {e}");
+                process::exit(1);
+            });
+    }
+    if let Some(skills) = requested_tagged_skills {
+        session.set_tagged_skills(skills).unwrap_or_else(|e| {
+            eprintln!("Error setting tagged skills: {e}");
+            process::exit(1);
+        });
+    }
+    if requested_derived_recalculation {
+        session.recalculate_derived_stats().unwrap_or_else(|e| {
+            eprintln!("Error recalculating derived stats: {e}");
+            process::exit(1);
+        });
+    }
+    // Level-ups depend on INT, END, Educated, Lifegiver and the traits, so
+    // they run after those edits.
+    let level_up_report = requested_level_up.map(|level| {
+        session.level_up_to(level).unwrap_or_else(|e| {
+            eprintln!("Error leveling up to {level}: {e}");
+            process::exit(1);
+        })
+    });
+    for assignment in requested_kill_counts {
+        session
+            .set_kill_count(assignment.index, assignment.count)
             .unwrap_or_else(|e| {
                 eprintln!(
-                    "Error setting perk {} rank {}: {e}",
-                    assignment.index, assignment.rank
+                    "Error setting kill count {} to {}: {e}",
+                    assignment.index, assignment.count
                 );
                 process::exit(1);
             });
     }
-    for request in requested_set_item_qty {
+    for assignment in requested_set_globals {
         session
-            .set_inventory_quantity(request.pid, request.quantity)
+            .set_global_var(assignment.index, assignment.value)
             .unwrap_or_else(|e| {
                 eprintln!(
-                    "Error setting inventory pid={} quantity={}: {e}",
-                    request.pid, request.quantity
+                    "Error setting global var {} to {}: {e}",
+                    assignment.index, assignment.value
                 );
                 process::exit(1);
             });
     }
-    for request in requested_add_items {
+    for request in requested_set_item_qty {
         session
-            .add_inventory_item(request.pid, request.quantity)
+            .set_inventory_quantity(request.pid, request.quantity)
             .unwrap_or_else(|e| {
                 eprintln!(
-                    "Error adding inventory pid={} quantity={}: {e}",
+                    "Error setting inventory pid={} quantity={}: {e}",
                     request.pid, request.quantity
                 );
                 process::exit(1);
             });
     }
+    let add_item_catalog = if requested_add_items.is_empty() {
+        None
+    } else {
+        match load_item_catalog(path, cli.install_dir.as_deref()) {
+            Ok(catalog) => Some(catalog),
+            Err(e) => {
+                eprintln!("Warning: adding items without item prototypes: {e}");
+                None
+            }
+        }
+    };
+    // New objects take ids above everything on the slot's maps too.
+    let id_floor = if add_item_catalog.is_some() {
+        map_object_id_floor(path).unwrap_or_else(|e| {
+            eprintln!("Warning: new item ids only avoid the player's own items: {e}");
+            0
+        })
+    } else {
+        0
+    };
+    for request in requested_add_items {
+        let result = match add_item_catalog.as_ref() {
+            Some(catalog) => session.add_inventory_item_with_catalog(
+                request.pid,
+                request.quantity,
+                catalog,
+                id_floor,
+            ),
+            None => session.add_inventory_item(request.pid, request.quantity),
+        };
+        result.unwrap_or_else(|e| {
+            eprintln!(
+                "Error adding inventory pid={} quantity={}: {e}",
+                request.pid, request.quantity
+            );
+            process::exit(1);
+        });
+    }
+    // Armor changes need item prototypes to move AC/DT/DR and the armor
+    // perk; without them only hand slots can change.
+    let equip_catalog = if requested_equips.is_empty() && requested_unequips.is_empty() {
+        None
+    } else {
+        match load_item_catalog(path, cli.install_dir.as_deref()) {
+            Ok(catalog) => Some(catalog),
+            Err(e) => {
+                eprintln!("Warning: equipping without item prototypes: {e}");
+                None
+            }
+        }
+    };
+    for &slot in requested_unequips {
+        let slot = to_core_equipment_slot(slot);
+        let result = match equip_catalog.as_ref() {
+            Some(catalog) => session.unequip_with_catalog(slot, catalog),
+            None => session.unequip(slot),
+        };
+        result.unwrap_or_else(|e| {
+            eprintln!("Error unequipping {slot:?} slot: {e}");
+            process::exit(1);
+        });
+    }
+    for request in requested_equips {
+        let slot = to_core_equipment_slot(request.slot);
+        let result = match equip_catalog.as_ref() {
+            Some(catalog) => session.equip_item_with_catalog(slot, request.pid, catalog),
+            None => session.equip_item(slot, request.pid),
+        };
+        result.unwrap_or_else(|e| {
+            eprintln!("Error equipping pid={} in {slot:?} slot: {e}", request.pid);
+            process::exit(1);
+        });
+    }
+    // The magazine size comes from the weapon prototypes; without them the
+    // rounds are only checked to be non-negative.
+    let ammo_catalog = if requested_weapon_ammo.is_empty() {
+        None
+    } else {
+        match load_item_catalog(path, cli.install_dir.as_deref()) {
+            Ok(catalog) => Some(catalog),
+            Err(e) => {
+                eprintln!("Warning: loading ammo without checking magazine sizes: {e}");
+                None
+            }
+        }
+    };
+    for request in requested_weapon_ammo {
+        let result = match ammo_catalog.as_ref() {
+            Some(catalog) => session.set_weapon_ammo_with_catalog(
+                request.pid,
+                request.ammo_pid,
+                request.rounds,
+                catalog,
+            ),
+            None => session.set_weapon_ammo(request.pid, request.ammo_pid, request.rounds),
+        };
+        result.unwrap_or_else(|e| {
+            eprintln!("Error setting ammo for weapon pid={}: {e}", request.pid);
+            process::exit(1);
+        });
+    }
     for request in requested_remove_items {
         session
             .remove_inventory_item(request.pid, request.quantity)
@@ -816,7 +1274,70 @@ fn main() {
             });
     }
 
+    if let Some(request) = requested_teleport {
+        teleport(&mut session, path, request).unwrap_or_else(|e| {
+            eprintln!(
+                "Error teleporting to map {} elevation {} tile {}: {e}",
+                request.map_id, request.elevation, request.tile
+            );
+            process::exit(1);
+        });
+    }
+    // Clock changes shift pending events, so they go before explicit retimes.
+    if let Some(game_time) = requested_game_time {
+        session.set_game_time(game_time).unwrap_or_else(|e| {
+            eprintln!("Error setting game time to {game_time}: {e}");
+            process::exit(1);
+        });
+    }
+    if let Some(duration) = requested_game_time_advance {
+        session.advance_game_time(duration).unwrap_or_else(|e| {
+            eprintln!("Error advancing game time by {duration}: {e}");
+            process::exit(1);
+        });
+    }
+
+    // Removals use the indices shown by `debug summary`; going from the highest
+    // index down keeps the remaining ones valid. Retimes then see the pruned queue.
+    let mut queue_removals = requested_remove_queue_events.to_vec();
+    queue_removals.sort_unstable();
+    queue_removals.dedup();
+    for &index in queue_removals.iter().rev() {
+        session.remove_queue_event(index).unwrap_or_else(|e| {
+            eprintln!("Error removing queue event {index}: {e}");
+            process::exit(1);
+        });
+    }
+    for request in requested_queue_event_times {
+        session
+            .set_queue_event_time(request.index, request.time)
+            .unwrap_or_else(|e| {
+                eprintln!(
+                    "Error setting queue event {} time to {}: {e}",
+                    request.index, request.time
+                );
+                process::exit(1);
+            });
+    }
+
+    let slot_file_edits =
+        if requested_automap_reveals.is_empty() && requested_automap_clears.is_empty() {
+            Vec::new()
+        } else {
+            edit_automap(
+                &mut session,
+                path,
+                requested_automap_reveals,
+                requested_automap_clears,
+            )
+            .unwrap_or_else(|e| {
+                eprintln!("Error editing automap: {e}");
+                process::exit(1);
+            })
+        };
+
     let mut backup_path = None;
+    let mut slot_file_writes = Vec::new();
     if has_edits {
         let out_path = cli.output.as_ref().expect("checked above");
         let edited_bytes = session.to_bytes_modified().unwrap_or_else(|e| {
@@ -837,6 +1358,18 @@ fn main() {
                     eprintln!("Error writing {}: {e}", out_path.display());
                     process::exit(1);
                 });
+
+        let out_dir = out_path.parent().unwrap_or(Path::new(""));
+        for (name, bytes) in &slot_file_edits {
+            let file_path = out_dir.join(name);
+            let backup =
+                write_output_atomically(&file_path, bytes, cli.force_overwrite, cli.backup)
+                    .unwrap_or_else(|e| {
+                        eprintln!("Error writing {}: {e}", file_path.display());
+                        process::exit(1);
+                    });
+            slot_file_writes.push((file_path, backup));
+        }
     }
 
     let output_uses_inventory = if cli.json {
@@ -923,12 +1456,32 @@ fn main() {
         return;
     }
 
-    if cli.output.is_some() {
-        let out_path = cli.output.as_ref().expect("checked above");
+    if let Some(out_path) = cli.output.as_ref() {
         println!("Wrote edited save to {}", out_path.display());
+        if let Some(report) = level_up_report {
+            let perk_levels: Vec<String> =
+                report.perk_levels.iter().map(|l| l.to_string()).collect();
+            println!(
+                "Leveled up from {} to {}: +{} skill points, +{} HP, perk picks at levels [{}]",
+                report.from_level,
+                report.to_level,
+                report.skill_points,
+                report.hit_points,
+                perk_levels.join(", ")
+            );
+            if report.free_perk {
+                println!("A perk pick is waiting in the character editor");
+            }
+        }
         if let Some(path) = backup_path {
             println!("Backup created at {}", path.display());
         }
+        for (file_path, backup) in slot_file_writes {
+            println!("Wrote {}", file_path.display());
+            if let Some(path) = backup {
+                println!("Backup created at {}", path.display());
+            }
+        }
         return;
     }
 
@@ -960,9 +1513,142 @@ fn main() {
 fn run_command(command: CommandSet) -> i32 {
     match command {
         CommandSet::Debug { command } => run_debug(command),
+        CommandSet::Slots(args) => match list_save_slots(args) {
+            Ok(code) => code,
+            Err(message) => {
+                eprintln!("{message}");
+                1
+            }
+        },
     }
 }
 
+fn list_save_slots(args: SlotsArgs) -> Result<i32, String> {
+    let listing = if args.levels {
+        list_slots_with_levels(&args.path)
+    } else {
+        list_slots(&args.path)
+    }
+    .map_err(|e| format!("Error listing slots in {}: {e}", args.path.display()))?;
+
+    if args.json {
+        let slots = listing
+            .slots
+            .iter()
+            .map(|slot| {
+                let mut m = JsonMap::new();
+                m.insert("slot".to_string(), JsonValue::from(slot.slot));
+                m.insert(
+                    "path".to_string(),
+                    JsonValue::String(slot.path.display().to_string()),
+                );
+                m.insert(
+                    "game".to_string(),
+                    JsonValue::String(game_name(slot.game).to_string()),
+                );
+                m.insert(
+                    "character_name".to_string(),
+                    JsonValue::String(slot.character_name.clone()),
+                );
+                m.insert(
+                    "description".to_string(),
+                    JsonValue::String(slot.description.clone()),
+                );
+                m.insert(
+                    "save_date".to_string(),
+                    JsonValue::String(format_date(
+                        slot.file_date.year,
+                        slot.file_date.month,
+                        slot.file_date.day,
+                    )),
+                );
+                m.insert(
+                    "game_date".to_string(),
+                    JsonValue::String(format_date(
+                        slot.game_date.year,
+                        slot.game_date.month,
+                        slot.game_date.day,
+                    )),
+                );
+                m.insert("map_id".to_string(), JsonValue::from(slot.map_id));
+                m.insert(
+                    "map_filename".to_string(),
+                    JsonValue::String(slot.map_filename.clone()),
+                );
+                m.insert("elevation".to_string(), JsonValue::from(slot.elevation));
+                m.insert(
+                    "level".to_string(),
+                    slot.level.map(JsonValue::from).unwrap_or(JsonValue::Null),
+                );
+                JsonValue::Object(m)
+            })
+            .collect();
+        let unreadable = listing
+            .unreadable
+            .iter()
+            .map(|(path, error)| {
+                let mut m = JsonMap::new();
+                m.insert(
+                    "path".to_string(),
+                    JsonValue::String(path.display().to_string()),
+                );
+                m.insert("error".to_string(), JsonValue::String(error.clone()));
+                JsonValue::Object(m)
+            })
+            .collect();
+        let mut out = JsonMap::new();
+        out.insert("slots".to_string(), JsonValue::Array(slots));
+        out.insert("unreadable".to_string(), JsonValue::Array(unreadable));
+        out.insert(
+            "position".to_string(),
+            listing
+                .position
+                .map(|position| {
+                    let mut m = JsonMap::new();
+                    m.insert("list_num".to_string(), JsonValue::from(position.list_num));
+                    m.insert(
+                        "page_offset".to_string(),
+                        JsonValue::from(position.page_offset),
+                    );
+                    JsonValue::Object(m)
+                })
+                .unwrap_or(JsonValue::Null),
+        );
+        print_json(&JsonValue::Object(out))
+            .map_err(|e| format!("Error rendering JSON output: {e}"))?;
+        return Ok(0);
+    }
+
+    println!(
+        "{:<4}  {:<8}  {:>5}  {:<32}  {:<30}  {:<10}  MAP",
+        "SLOT", "GAME", "LEVEL", "NAME", "DESCRIPTION", "SAVED"
+    );
+    for slot in &listing.slots {
+        let game = game_name(slot.game);
+        let level = slot
+            .level
+            .map(|level| level.to_string())
+            .unwrap_or_default();
+        println!(
+            "{:<4}  {game:<8}  {level:>5}  {:<32}  {:<30}  {:<10}  {}",
+            slot.slot,
+            slot.character_name,
+            slot.description,
+            format_date(
+                slot.file_date.year,
+                slot.file_date.month,
+                slot.file_date.day
+            ),
+            slot.map_filename
+        );
+    }
+    for (path, error) in &listing.unreadable {
+        eprintln!("Skipped {}: {error}", path.display());
+    }
+
+    Ok(0)
+}
+
 fn run_debug(command: DebugSubcommand) -> i32 {
     let result = match command {
         DebugSubcommand::Summary(args) => debug_summary(args),
@@ -970,6 +1656,10 @@ fn run_debug(command: DebugSubcommand) -> i32 {
         DebugSubcommand::Section(args) => debug_section(args),
         DebugSubcommand::Validate(args) => debug_validate(args),
         DebugSubcommand::Compare(args) => debug_compare(args),
+        DebugSubcommand::Globals(args) => debug_globals(args),
+        DebugSubcommand::Thumbnail(args) => debug_thumbnail(args),
+        DebugSubcommand::Automap(args) => debug_automap(args),
+        DebugSubcommand::Sfall(args) => debug_sfall(args),
     };
 
     match result {
@@ -1040,6 +1730,11 @@ fn debug_summary(args: DebugSummaryArgs) -> Result<i32, String> {
                 snapshot.file_date.day,
             )),
         );
+        out.insert(
+            "queue_events".to_string(),
+            serde_json::to_value(session.queue_events().ok())
+                .map_err(|e| format!("Error rendering queue events: {e}"))?,
+        );
         out.insert(
             "capabilities".to_string(),
             capabilities_to_json(session.capabilities()),
@@ -1072,6 +1767,20 @@ fn debug_summary(args: DebugSummaryArgs) -> Result<i32, String> {
                 snapshot.file_date.day
             )
         );
+        match session.queue_events() {
+            Ok(events) => {
+                println!("queue_events={}", events.len());
+                for (index, event) in events.iter().enumerate() {
+                    println!(
+                        "  [{index}] time={} type={} object_id={}",
+                        event.time,
+                        event.data.name(),
+                        event.object_id
+                    );
+                }
+            }
+            Err(_) => println!("queue_events=undecoded"),
+        }
 
         let issues = session
             .capabilities()
@@ -1100,6 +1809,274 @@ fn debug_summary(args: DebugSummaryArgs) -> Result<i32, String> {
     Ok(0)
 }
 
+fn debug_globals(args: DebugGlobalsArgs) -> Result<i32, String> {
+    let bytes =
+        fs::read(&args.path).map_err(|e| format!("Error reading {}: {e}", args.path.display()))?;
+    let hint = resolve_hint(args.hint.game, args.hint.fallout1, args.hint.fallout2);
+    let session = Engine::new()
+        .open_bytes(&bytes, hint)
+        .map_err(|e| format!("Error parsing save file {}: {e}", args.path.display()))?;
+    let catalog = load_global_var_catalog(&args.path, args.install_dir.as_deref()).ok();
+    let entries: Vec<_> = session
+        .global_vars_resolved(catalog.as_ref())
+        .into_iter()
+        .filter(|entry| !args.nonzero || entry.value != 0)
+        .collect();
+
+    if args.json {
+        let vars = entries
+            .iter()
+            .map(|entry| {
+                let mut m = JsonMap::new();
+                m.insert("index".to_string(), JsonValue::from(entry.index));
+                m.insert(
+                    "name".to_string(),
+                    entry
+                        .name
+                        .clone()
+                        .map(JsonValue::String)
+                        .unwrap_or(JsonValue::Null),
+                );
+                m.insert("value".to_string(), JsonValue::from(entry.value));
+                JsonValue::Object(m)
+            })
+            .collect();
+        let mut out = JsonMap::new();
+        out.insert(
+            "game".to_string(),
+            JsonValue::String(game_name(session.game()).to_string()),
+        );
+        out.insert(
+            "global_var_count".to_string(),
+            JsonValue::from(session.global_vars().len()),
+        );
+        out.insert("global_vars".to_string(), JsonValue::Array(vars));
+        print_json(&JsonValue::Object(out))
+            .map_err(|e| format!("Error rendering JSON output: {e}"))?;
+    } else {
+        for entry in &entries {
+            match &entry.name {
+                Some(name) => println!("{:>4} {name}={}", entry.index, entry.value),
+                None => println!("{:>4} {}", entry.index, entry.value),
+            }
+        }
+    }
+
+    Ok(0)
+}
+
+fn debug_thumbnail(args: DebugThumbnailArgs) -> Result<i32, String> {
+    let bytes =
+        fs::read(&args.path).map_err(|e| format!("Error reading {}: {e}", args.path.display()))?;
+    let hint = resolve_hint(args.hint.game, args.hint.fallout1, args.hint.fallout2);
+    let session = Engine::new()
+        .open_bytes(&bytes, hint)
+        .map_err(|e| format!("Error parsing save file {}: {e}", args.path.display()))?;
+    let palette = match load_palette(&args.path, args.install_dir.as_deref()) {
+        Ok(palette) => Some(palette),
+        Err(e) => {
+            eprintln!("Warning: writing approximate greys without the game palette: {e}");
+            None
+        }
+    };
+
+    let png = render_thumbnail_png(&session, palette.as_ref());
+    fs::write(&args.out, png).map_err(|e| format!("Error writing {}: {e}", args.out.display()))?;
+    println!("Wrote thumbnail to {}", args.out.display());
+    Ok(0)
+}
+
+fn debug_automap(args: DebugAutomapArgs) -> Result<i32, String> {
+    let slot_dir = args
+        .path
+        .parent()
+        .ok_or_else(|| format!("{} has no slot directory", args.path.display()))?;
+    let slot = Engine::new()
+        .open_slot(slot_dir)
+        .map_err(|e| format!("Error opening slot {}: {e}", slot_dir.display()))?;
+    let automap = slot
+        .automap()
+        .map_err(|e| format!("Error reading automap: {e}"))?;
+
+    if let (Some(map), Some(out)) = (args.map, args.out.as_ref()) {
+        let tiles = automap.tiles(map, args.elevation).ok_or_else(|| {
+            format!(
+                "map {map} elevation {} has nothing explored",
+                args.elevation
+            )
+        })?;
+        fs::write(out, render_automap_png(tiles))
+            .map_err(|e| format!("Error writing {}: {e}", out.display()))?;
+        println!("Wrote automap to {}", out.display());
+        return Ok(0);
+    }
+
+    let mut map_names = BTreeMap::new();
+    for name in slot.map_save_names() {
+        if let Ok(map) = slot.map_save(&name) {
+            map_names.insert(map.header.map_index, name);
+        }
+    }
+    let map_name = |map: usize| {
+        i32::try_from(map)
+            .ok()
+            .and_then(|map| map_names.get(&map))
+            .cloned()
+    };
+
+    if args.json {
+        let entries = automap
+            .explored()
+            .map(|(map, elevation, tiles)| {
+                let mut m = JsonMap::new();
+                m.insert("map".to_string(), JsonValue::from(map));
+                m.insert(
+                    "map_save".to_string(),
+                    map_name(map)
+                        .map(JsonValue::String)
+                        .unwrap_or(JsonValue::Null),
+                );
+                m.insert("elevation".to_string(), JsonValue::from(elevation));
+                m.insert(
+                    "explored_hexes".to_string(),
+                    JsonValue::from(tiles.explored_count()),
+                );
+                JsonValue::Object(m)
+            })
+            .collect();
+        let mut out = JsonMap::new();
+        out.insert(
+            "automap_size".to_string(),
+            JsonValue::from(slot.session().automap_size()),
+        );
+        out.insert("explored".to_string(), JsonValue::Array(entries));
+        print_json(&JsonValue::Object(out))
+            .map_err(|e| format!("Error rendering JSON output: {e}"))?;
+    } else {
+        for (map, elevation, tiles) in automap.explored() {
+            let name = map_name(map).unwrap_or_default();
+            println!(
+                "{map:>4} {name:<14} elevation {elevation}: {} hexes",
+                tiles.explored_count()
+            );
+        }
+    }
+
+    Ok(0)
+}
+
+fn debug_sfall(args: DebugSfallArgs) -> Result<i32, String> {
+    let slot_dir = args
+        .path
+        .parent()
+        .ok_or_else(|| format!("{} has no slot directory", args.path.display()))?;
+    let slot = Engine::new()
+        .open_slot(slot_dir)
+        .map_err(|e| format!("Error opening slot {}: {e}", slot_dir.display()))?;
+    let globals = slot
+        .sfall_globals()
+        .map_err(|e| format!("Error reading sfall globals: {e}"))?;
+    let file_system = slot
+        .sfall_file_system()
+        .map_err(|e| format!("Error reading sfall file system: {e}"))?;
+    let arrays = slot
+        .sfall_arrays()
+        .map_err(|e| format!("Error reading sfall arrays: {e}"))?;
+    if globals.is_none() && file_system.is_none() && arrays.is_none() {
+        return Err(format!("{} has no sfall data", slot_dir.display()));
+    }
+
+    let fake_perk_names = |perks: &[FakePerk]| -> Vec<String> {
+        perks.iter().map(|perk| perk.name.clone()).collect()
+    };
+
+    if args.json {
+        let mut out = JsonMap::new();
+        if let Some(globals) = &globals {
+            let vars = globals
+                .vars
+                .iter()
+                .map(|var| (var.key.to_string(), JsonValue::from(var.value)))
+                .collect();
+            out.insert("globals".to_string(), JsonValue::Object(vars));
+            out.insert(
+                "fake_traits".to_string(),
+                JsonValue::from(fake_perk_names(&globals.fake_traits)),
+            );
+            out.insert(
+                "fake_perks".to_string(),
+                JsonValue::from(fake_perk_names(&globals.fake_perks)),
+            );
+            out.insert(
+                "fake_selectable_perks".to_string(),
+                JsonValue::from(fake_perk_names(&globals.fake_selectable_perks)),
+            );
+        }
+        if let Some(file_system) = &file_system {
+            let files = file_system
+                .files
+                .iter()
+                .map(|file| (file.name.clone(), JsonValue::from(file.data.len())))
+                .collect();
+            out.insert("files".to_string(), JsonValue::Object(files));
+        }
+        if let Some(arrays) = &arrays {
+            let arrays = arrays
+                .arrays
+                .iter()
+                .map(|array| {
+                    let elements = array
+                        .elements
+                        .iter()
+                        .map(|element| element.to_string())
+                        .collect::<Vec<_>>();
+                    (array.key.to_string(), JsonValue::from(elements))
+                })
+                .collect();
+            out.insert("arrays".to_string(), JsonValue::Object(arrays));
+        }
+        print_json(&JsonValue::Object(out))
+            .map_err(|e| format!("Error rendering JSON output: {e}"))?;
+        return Ok(0);
+    }
+
+    if let Some(globals) = &globals {
+        println!("Globals:");
+        for var in &globals.vars {
+            println!("  {:<10} {}", var.key.to_string(), var.value);
+        }
+        for (label, perks) in [
+            ("Fake traits", &globals.fake_traits),
+            ("Fake perks", &globals.fake_perks),
+            ("Fake selectable perks", &globals.fake_selectable_perks),
+        ] {
+            if !perks.is_empty() {
+                println!("{label}:");
+                for perk in perks {
+                    println!("  {} (rank {})", perk.name, perk.level);
+                }
+            }
+        }
+    }
+    if let Some(file_system) = &file_system {
+        for file in &file_system.files {
+            println!("File {} ({} bytes)", file.name, file.data.len());
+        }
+    }
+    if let Some(arrays) = &arrays {
+        for array in &arrays.arrays {
+            let elements = array
+                .elements
+                .iter()
+                .map(|element| element.to_string())
+                .collect::<Vec<_>>();
+            println!("Array {}: [{}]", array.key, elements.join(", "));
+        }
+    }
+
+    Ok(0)
+}
+
 fn debug_layout(args: DebugLayoutArgs) -> Result<i32, String> {
     let bytes =
         fs::read(&args.path).map_err(|e| format!("Error reading {}: {e}", args.path.display()))?;
@@ -1318,6 +2295,21 @@ fn debug_validate(args: DebugValidateArgs) -> Result<i32, String> {
         }
     }
 
+    if args.rules {
+        match Engine::new().open_bytes(&bytes, hint) {
+            Ok(session) => {
+                for violation in session.validate_character() {
+                    errors.push(format!(
+                        "{}: {}",
+                        character_rule_name(violation.rule),
+                        violation.message
+                    ));
+                }
+            }
+            Err(err) => errors.push(format!("cannot check character rules: {err}")),
+        }
+    }
+
     let status = if !errors.is_empty() {
         "error"
     } else if !warnings.is_empty() {
@@ -1799,6 +2791,17 @@ fn game_name(game: CoreGame) -> &'static str {
     }
 }
 
+fn character_rule_name(rule: CharacterRule) -> &'static str {
+    match rule {
+        CharacterRule::Level => "level",
+        CharacterRule::Special => "special",
+        CharacterRule::TaggedSkills => "tagged_skills",
+        CharacterRule::Traits => "traits",
+        CharacterRule::Perks => "perks",
+        CharacterRule::SkillPoints => "skill_points",
+    }
+}
+
 fn push_diff(diffs: &mut Vec<(String, String, String)>, field: &str, a: String, b: String) {
     if a != b {
         diffs.push((field.to_string(), a, b));
@@ -1890,6 +2893,126 @@ fn parse_perk_assignment(value: &str) -> Result<PerkAssignmentArg, String> {
     Ok(PerkAssignmentArg { index, rank })
 }
 
+fn parse_kill_count_assignment(value: &str) -> Result<KillCountAssignmentArg, String> {
+    let (index_raw, count_raw) = value
+        .split_once(':')
+        .ok_or_else(|| format!("invalid kill count assignment '{value}', expected INDEX:COUNT"))?;
+    let index = parse_usize_value(index_raw)?;
+    let count = parse_i32_value(count_raw)?;
+    Ok(KillCountAssignmentArg { index, count })
+}
+
+fn parse_preference(value: &str) -> Result<PreferenceArg, String> {
+    let (name, value_raw) = value
+        .split_once('=')
+        .ok_or_else(|| format!("invalid preference '{value}', expected NAME=VALUE"))?;
+    Ok(PreferenceArg {
+        name: name.trim().to_ascii_lowercase().replace('-', "_"),
+        value: value_raw.trim().to_string(),
+    })
+}
+
+fn apply_preference(preferences: &mut Preferences, request: &PreferenceArg) -> Result<(), String> {
+    let field = match request.name.as_str() {
+        "game_difficulty" => &mut preferences.game_difficulty,
+        "combat_difficulty" => &mut preferences.combat_difficulty,
+        "violence_level" => &mut preferences.violence_level,
+        "target_highlight" => &mut preferences.target_highlight,
+        "combat_looks" => &mut preferences.combat_looks,
+        "combat_messages" => &mut preferences.combat_messages,
+        "combat_taunts" => &mut preferences.combat_taunts,
+        "language_filter" => &mut preferences.language_filter,
+        "running" => &mut preferences.running,
+        "subtitles" => &mut preferences.subtitles,
+        "item_highlight" => &mut preferences.item_highlight,
+        "combat_speed" => &mut preferences.combat_speed,
+        "player_speedup" => &mut preferences.player_speedup,
+        "master_volume" => &mut preferences.master_volume,
+        "music_volume" => &mut preferences.music_volume,
+        "sound_effects_volume" => &mut preferences.sound_effects_volume,
+        "speech_volume" => &mut preferences.speech_volume,
+        _ => {
+            let field = match request.name.as_str() {
+                "text_base_delay" => &mut preferences.text_base_delay,
+                "brightness" => &mut preferences.brightness,
+                "mouse_sensitivity" => &mut preferences.mouse_sensitivity,
+                other => return Err(format!("unknown preference '{other}'")),
+            };
+            *field = request
+                .value
+                .parse::<f32>()
+                .map_err(|_| format!("invalid number '{}'", request.value))?;
+            return Ok(());
+        }
+    };
+    *field = parse_i32_value(&request.value)?;
+    Ok(())
+}
+
+fn parse_global_var_assignment(value: &str) -> Result<GlobalVarAssignmentArg, String> {
+    let (index_raw, value_raw) = value
+        .split_once(':')
+        .ok_or_else(|| format!("invalid global var assignment '{value}', expected INDEX:VALUE"))?;
+    let index = parse_usize_value(index_raw)?;
+    let value = parse_i32_value(value_raw)?;
+    Ok(GlobalVarAssignmentArg { index, value })
+}
+
+fn parse_queue_event_time(value: &str) -> Result<QueueEventTimeArg, String> {
+    let (index_raw, time_raw) = value
+        .split_once(':')
+        .ok_or_else(|| format!("invalid queue event time '{value}', expected INDEX:TIME"))?;
+    let index = parse_usize_value(index_raw)?;
+    let time = time_raw
+        .parse::<u32>()
+        .map_err(|_| format!("invalid queue event time '{time_raw}'"))?;
+    Ok(QueueEventTimeArg { index, time })
+}
+
+fn parse_equip_item(value: &str) -> Result<EquipItemArg, String> {
+    let (slot_raw, pid_raw) = value
+        .split_once(':')
+        .ok_or_else(|| format!("invalid equip assignment '{value}', expected SLOT:PID"))?;
+    let slot = EquipmentSlotArg::from_str(slot_raw, true).map_err(|_| {
+        format!("invalid equipment slot '{slot_raw}', expected armor, left-hand or right-hand")
+    })?;
+    let pid = parse_i32_value(pid_raw)?;
+    Ok(EquipItemArg { slot, pid })
+}
+
+fn parse_weapon_ammo(value: &str) -> Result<WeaponAmmoArg, String> {
+    let parts: Vec<&str> = value.split(':').collect();
+    let [pid_raw, ammo_pid_raw, rounds_raw] = parts.as_slice() else {
+        return Err(format!(
+            "invalid weapon ammo '{value}', expected PID:AMMO_PID:ROUNDS"
+        ));
+    };
+    Ok(WeaponAmmoArg {
+        pid: parse_i32_value(pid_raw)?,
+        ammo_pid: parse_i32_value(ammo_pid_raw)?,
+        rounds: parse_i32_value(rounds_raw)?,
+    })
+}
+
+fn parse_teleport(value: &str) -> Result<TeleportArg, String> {
+    let parts: Vec<&str> = value.split(':').collect();
+    let [map_raw, elevation_raw, tile_raw] = parts.as_slice() else {
+        return Err(format!(
+            "invalid teleport target '{value}', expected MAP_ID:ELEVATION:TILE"
+        ));
+    };
+    let map_id = i16::try_from(parse_i32_value(map_raw)?)
+        .map_err(|_| format!("invalid map id '{map_raw}'"))?;
+    let elevation = i16::try_from(parse_i32_value(elevation_raw)?)
+        .map_err(|_| format!("invalid elevation '{elevation_raw}'"))?;
+    let tile = parse_i32_value(tile_raw)?;
+    Ok(TeleportArg {
+        map_id,
+        elevation,
+        tile,
+    })
+}
+
 fn parse_item_quantity(value: &str) -> Result<ItemQuantityArg, String> {
     let (pid_raw, qty_raw) = value
         .split_once(':')
@@ -1970,6 +3093,92 @@ fn resolve_hint(game: Option<GameKind>, fallout1: bool, fallout2: bool) -> Optio
     .map(to_core_game)
 }
 
+/// Moves within the current map only need `SAVE.DAT`; other maps are looked
+/// up in the map saves next to it.
+fn teleport(session: &mut Session, save_path: &Path, request: TeleportArg) -> Result<(), String> {
+    if request.map_id == session.snapshot().map_id {
+        return session
+            .teleport(request.elevation, request.tile)
+            .map_err(|e| e.to_string());
+    }
+    let slot_dir = save_path
+        .parent()
+        .ok_or_else(|| format!("{} has no slot directory", save_path.display()))?;
+    let slot = Engine::new()
+        .open_slot(slot_dir)
+        .map_err(|e| e.to_string())?;
+    let map_file = slot
+        .map_save_name_for_id(i32::from(request.map_id))
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| {
+            format!(
+                "no map save for map {} in {}",
+                request.map_id,
+                slot_dir.display()
+            )
+        })?;
+    let map = slot.map_save(&map_file).map_err(|e| e.to_string())?;
+    session
+        .teleport_to_map_save(&map_file, &map.header, request.elevation, request.tile)
+        .map_err(|e| e.to_string())
+}
+
+/// Highest object id on the map saves beside `SAVE.DAT`.
+fn map_object_id_floor(save_path: &Path) -> Result<i32, String> {
+    let slot_dir = save_path
+        .parent()
+        .ok_or_else(|| format!("{} has no slot directory", save_path.display()))?;
+    Engine::new()
+        .open_slot(slot_dir)
+        .and_then(|slot| slot.max_map_object_id())
+        .map_err(|e| e.to_string())
+}
+
+/// Automap edits live in `AUTOMAP.SAV` and the map saves beside `SAVE.DAT`.
+/// Returns the edited slot files by name, and records the new automap size
+/// in `session`.
+fn edit_automap(
+    session: &mut Session,
+    save_path: &Path,
+    reveal: &[i32],
+    clear: &[i32],
+) -> Result<Vec<(PathBuf, Vec<u8>)>, String> {
+    let slot_dir = save_path
+        .parent()
+        .ok_or_else(|| format!("{} has no slot directory", save_path.display()))?;
+    let mut slot = Engine::new()
+        .open_slot(slot_dir)
+        .map_err(|e| e.to_string())?;
+
+    let mut map_names = BTreeSet::new();
+    for &map in clear {
+        slot.clear_automap(map).map_err(|e| e.to_string())?;
+        map_names.extend(slot.map_save_name_for_id(map).map_err(|e| e.to_string())?);
+    }
+    for &map in reveal {
+        slot.reveal_automap(map).map_err(|e| e.to_string())?;
+        map_names.extend(slot.map_save_name_for_id(map).map_err(|e| e.to_string())?);
+    }
+    session
+        .set_automap_size(slot.session().automap_size())
+        .map_err(|e| e.to_string())?;
+
+    let automap_name = slot
+        .files()
+        .find(|(_, kind)| *kind == SlotFileKind::Automap)
+        .map(|(relative, _)| relative.to_path_buf())
+        .ok_or_else(|| format!("no AUTOMAP.SAV in {}", slot_dir.display()))?;
+    let mut files = vec![(
+        automap_name.clone(),
+        slot.file_bytes(&automap_name).unwrap_or_default().to_vec(),
+    )];
+    for name in map_names {
+        let bytes = slot.map_save_bytes(&name).unwrap_or_default().to_vec();
+        files.push((PathBuf::from(name), bytes));
+    }
+    Ok(files)
+}
+
 fn load_item_catalog(
     save_path: &Path,
     install_dir_override: Option<&Path>,
@@ -1986,6 +3195,35 @@ fn load_item_catalog(
     ItemCatalog::load_from_install_dir(&install_dir).map_err(|e| e.to_string())
 }
 
+fn load_global_var_catalog(
+    save_path: &Path,
+    install_dir_override: Option<&Path>,
+) -> Result<GlobalVarCatalog, String> {
+    if let Some(install_dir) = install_dir_override {
+        return GlobalVarCatalog::load_from_install_dir(install_dir).map_err(|e| e.to_string());
+    }
+    let install_dir = detect_install_dir_from_save_path(save_path).ok_or_else(|| {
+        format!(
+            "failed to auto-detect install dir from {}",
+            save_path.display()
+        )
+    })?;
+    GlobalVarCatalog::load_from_install_dir(&install_dir).map_err(|e| e.to_string())
+}
+
+fn load_palette(save_path: &Path, install_dir_override: Option<&Path>) -> Result<Palette, String> {
+    if let Some(install_dir) = install_dir_override {
+        return Palette::load_from_install_dir(install_dir).map_err(|e| e.to_string());
+    }
+    let install_dir = detect_install_dir_from_save_path(save_path).ok_or_else(|| {
+        format!(
+            "failed to auto-detect install dir from {}",
+            save_path.display()
+        )
+    })?;
+    Palette::load_from_install_dir(&install_dir).map_err(|e| e.to_string())
+}
+
 fn load_trait_catalog(
     save_path: &Path,
     install_dir_override: Option<&Path>,
@@ -2002,6 +3240,14 @@ fn load_trait_catalog(
     TraitCatalog::load_from_install_dir(&install_dir).map_err(|e| e.to_string())
 }
 
+fn to_core_equipment_slot(slot: EquipmentSlotArg) -> EquipmentSlot {
+    match slot {
+        EquipmentSlotArg::Armor => EquipmentSlot::Armor,
+        EquipmentSlotArg::LeftHand => EquipmentSlot::LeftHand,
+        EquipmentSlotArg::RightHand => EquipmentSlot::RightHand,
+    }
+}
+
 fn to_core_gender(gender: GenderArg) -> Gender {
     match gender {
         GenderArg::Male => Gender::Male,
@@ -2050,49 +3296,8 @@ fn write_output_atomically(
         None
     };
 
-    if let Some(parent) = out_path.parent()
-        && !parent.as_os_str().is_empty()
-    {
-        fs::create_dir_all(parent).map_err(|e| {
-            format!(
-                "failed to create parent directory {}: {e}",
-                parent.display()
-            )
-        })?;
-    }
-
-    let temp_path = temporary_output_path(out_path);
-    fs::write(&temp_path, bytes)
-        .map_err(|e| format!("failed to write temp file {}: {e}", temp_path.display()))?;
-
-    match fs::rename(&temp_path, out_path) {
-        Ok(()) => Ok(backup_path),
-        Err(rename_err) => {
-            if out_exists && force_overwrite {
-                fs::remove_file(out_path).map_err(|e| {
-                    format!(
-                        "failed to replace existing output {} after rename error ({rename_err}): {e}",
-                        out_path.display()
-                    )
-                })?;
-                fs::rename(&temp_path, out_path).map_err(|e| {
-                    format!(
-                        "failed to rename temp file {} to {}: {e}",
-                        temp_path.display(),
-                        out_path.display()
-                    )
-                })?;
-                Ok(backup_path)
-            } else {
-                let _ = fs::remove_file(&temp_path);
-                Err(format!(
-                    "failed to rename temp file {} to {}: {rename_err}",
-                    temp_path.display(),
-                    out_path.display()
-                ))
-            }
-        }
-    }
+    write_file_atomically(out_path, bytes).map_err(|e| e.to_string())?;
+    Ok(backup_path)
 }
 
 fn create_backup(out_path: &Path) -> Result<PathBuf, String> {
@@ -2113,16 +3318,3 @@ fn create_backup(out_path: &Path) -> Result<PathBuf, String> {
     })?;
     Ok(backup_path)
 }
-
-fn temporary_output_path(out_path: &Path) -> PathBuf {
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_nanos())
-        .unwrap_or(0);
-
-    let base_name = out_path
-        .file_name()
-        .map(|s| s.to_string_lossy().to_string())
-        .unwrap_or_else(|| "save.dat".to_string());
-    out_path.with_file_name(format!(".{base_name}.tmp.{}.{}", process::id(), timestamp))
-}
@@ -49,6 +49,12 @@ fn debug_summary_json_includes_expected_fields() {
     assert_eq!(json["game"], "Fallout1");
     assert_eq!(json["name"], "Clairey");
     assert!(json["layout"]["section_count"].as_u64().is_some());
+    let events = json["queue_events"]
+        .as_array()
+        .expect("queue_events should be an array");
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0]["type"], "map_update");
+    assert_eq!(events[0]["object_id"], -2);
 }
 
 #[test]
@@ -106,16 +112,54 @@ fn debug_validate_reports_error_for_truncated_save() {
 
     assert_eq!(json["status"], "error");
     assert!(
-        json["errors"]
+        !json["errors"]
             .as_array()
             .expect("errors should be array")
-            .len()
-            > 0
+            .is_empty()
     );
 
     let _ = fs::remove_file(&truncated_path);
 }
 
+#[test]
+fn debug_validate_rules_reports_character_violations() {
+    let path = fallout1_save_path(1);
+    let path = path.to_string_lossy().to_string();
+
+    let output = run_cli(&["debug", "validate", "--json", &path]);
+    assert!(output.status.success());
+
+    let output = run_cli(&["debug", "validate", "--rules", "--json", &path]);
+    assert!(!output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: Value = serde_json::from_str(&stdout).expect("stdout should be valid JSON");
+
+    assert_eq!(json["status"], "error");
+    let errors = json["errors"].as_array().expect("errors should be array");
+    assert!(
+        errors
+            .iter()
+            .any(|e| e.as_str().is_some_and(|e| e.starts_with("tagged_skills: ")))
+    );
+}
+
+#[test]
+fn debug_thumbnail_writes_png() {
+    let path = fallout2_save_path(1);
+    let path = path.to_string_lossy().to_string();
+    let out_path = temp_output_path("fallout_se_debug_thumbnail");
+    let out = out_path.to_string_lossy().to_string();
+
+    let output = run_cli(&["debug", "thumbnail", "--out", &out, &path]);
+    assert!(output.status.success());
+
+    let png = fs::read(&out_path).expect("thumbnail should be written");
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+    let _ = fs::remove_file(&out_path);
+}
+
 #[test]
 fn debug_compare_json_detects_field_differences() {
     let path = fallout2_save_path(1);
@@ -146,3 +190,193 @@ fn debug_compare_json_detects_field_differences() {
 
     let _ = fs::remove_file(&edited_path);
 }
+
+#[test]
+fn debug_globals_json_lists_values_and_set_global_round_trips() {
+    let path = fallout2_save_path(1);
+    let path = path.to_string_lossy().to_string();
+    let output_path = temp_output_path("fallout_se_set_global");
+    let output_str = output_path.to_string_lossy().to_string();
+
+    let output = run_cli(&["debug", "globals", "--json", &path]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: Value = serde_json::from_str(&stdout).expect("stdout should be valid JSON");
+    assert_eq!(json["game"], "Fallout2");
+    assert_eq!(json["global_var_count"], 791);
+    assert_eq!(
+        json["global_vars"]
+            .as_array()
+            .expect("global_vars should be array")
+            .len(),
+        791
+    );
+
+    let edit = run_cli(&[&path, "--set-global", "5:4242", "--output", &output_str]);
+    assert!(
+        edit.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&edit.stderr)
+    );
+
+    let output = run_cli(&["debug", "globals", "--json", "--nonzero", &output_str]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: Value = serde_json::from_str(&stdout).expect("stdout should be valid JSON");
+    let edited = json["global_vars"]
+        .as_array()
+        .expect("global_vars should be array")
+        .iter()
+        .find(|entry| entry["index"] == 5)
+        .expect("edited global should be listed as nonzero");
+    assert_eq!(edited["value"], 4242);
+
+    let _ = fs::remove_file(&output_path);
+}
+
+#[test]
+fn debug_section_json_targets_fallout2_world_map_handler() {
+    let path = fallout2_save_path(1);
+    let path = path.to_string_lossy().to_string();
+
+    let output = run_cli(&["debug", "layout", "--json", &path]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: Value = serde_json::from_str(&stdout).expect("stdout should be valid JSON");
+    let ids: Vec<&str> = json["sections"]
+        .as_array()
+        .expect("sections should be an array")
+        .iter()
+        .filter_map(|section| section["id"].as_str())
+        .collect();
+    for handler in 18..=23 {
+        assert!(ids.contains(&format!("handler:{handler}").as_str()));
+    }
+
+    let output = run_cli(&["debug", "section", "--id", "handler:21", "--json", &path]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: Value = serde_json::from_str(&stdout).expect("stdout should be valid JSON");
+    assert_eq!(json["section"]["id"], "handler:21");
+    assert_eq!(json["section"]["len"], 17);
+}
+
+#[test]
+fn automap_edits_write_slot_files_next_to_the_output() {
+    let path = fallout2_save_path(1);
+    let path_s = path.to_string_lossy().to_string();
+    let out_dir = temp_output_path("fallout_se_automap_slot");
+    let out_save = out_dir.join("SAVE.DAT");
+    let out_save_s = out_save.to_string_lossy().to_string();
+
+    let explored = |save: &str| -> Value {
+        let output = run_cli(&["debug", "automap", "--json", save]);
+        assert!(output.status.success());
+        serde_json::from_slice(&output.stdout).expect("debug automap should print JSON")
+    };
+    let hexes = |json: &Value, map: u64| {
+        json["explored"]
+            .as_array()
+            .expect("explored should be an array")
+            .iter()
+            .find(|entry| entry["map"] == map)
+            .map(|entry| entry["explored_hexes"].as_u64().expect("hex count"))
+    };
+    let before = explored(&path_s);
+    assert_eq!(before["automap_size"], 4658);
+    assert_eq!(hexes(&before, 3), Some(706));
+    assert_eq!(hexes(&before, 126), Some(247));
+
+    let output = run_cli(&[
+        "--reveal-automap",
+        "126",
+        "--clear-automap",
+        "3",
+        "--output",
+        &out_save_s,
+        &path_s,
+    ]);
+    assert!(output.status.success());
+    assert!(out_dir.join("AUTOMAP.SAV").is_file());
+    assert!(out_dir.join("ARTEMPLE.SAV").is_file());
+
+    let after = explored(&out_save_s);
+    assert_eq!(hexes(&after, 3), None);
+    assert!(hexes(&after, 126).expect("ARTEMPLE should stay explored") > 247);
+    assert_ne!(after["automap_size"], before["automap_size"]);
+
+    let png_path = out_dir.join("artemple.png");
+    let png_s = png_path.to_string_lossy().to_string();
+    let output = run_cli(&[
+        "debug",
+        "automap",
+        "--map",
+        "126",
+        "--out",
+        &png_s,
+        &out_save_s,
+    ]);
+    assert!(output.status.success());
+    let png = fs::read(&png_path).expect("automap PNG should be written");
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+    let _ = fs::remove_dir_all(&out_dir);
+}
+
+#[test]
+fn debug_sfall_json_lists_globals() {
+    let path = fallout2_save_path(1);
+    let output = run_cli(&["debug", "sfall", "--json", &path.to_string_lossy()]);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: Value = serde_json::from_str(&stdout).expect("stdout should be valid JSON");
+    assert_eq!(json["globals"]["p_looter"], 0);
+    assert_eq!(json["globals"]["p_gitem0"], 0);
+    assert_eq!(json["fake_perks"], serde_json::json!([]));
+    assert_eq!(json["files"], serde_json::json!({}));
+    assert_eq!(json["arrays"], serde_json::json!({}));
+
+    let fallout1 = run_cli(&["debug", "sfall", &fallout1_save_path(1).to_string_lossy()]);
+    assert!(!fallout1.status.success());
+}
+
+#[test]
+fn slots_json_lists_each_slot() {
+    let root = workspace_root().join("tests/fallout2_examples");
+    let output = run_cli(&["slots", "--json", "--levels", &root.to_string_lossy()]);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: Value = serde_json::from_str(&stdout).expect("stdout should be valid JSON");
+    let slots = json["slots"].as_array().expect("slots should be array");
+    assert_eq!(slots.len(), 2);
+    assert_eq!(slots[0]["slot"], 1);
+    assert_eq!(slots[0]["game"], "Fallout2");
+    assert_eq!(slots[0]["character_name"], "Narg");
+    assert_eq!(slots[0]["level"], 1);
+    assert_eq!(json["position"]["list_num"], 1);
+
+    let output = run_cli(&["slots", "--json", &root.to_string_lossy()]);
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).expect("stdout should be valid JSON");
+    assert_eq!(json["slots"][0]["level"], Value::Null);
+
+    let root = workspace_root().join("tests/fallout1_examples/SAVEGAME");
+    let table = run_cli(&["slots", &root.to_string_lossy()]);
+    assert!(table.status.success());
+    let stdout = String::from_utf8_lossy(&table.stdout);
+    assert_eq!(stdout.lines().count(), 8);
+    assert!(
+        stdout
+            .lines()
+            .nth(1)
+            .is_some_and(|line| line.contains("Clairey"))
+    );
+}
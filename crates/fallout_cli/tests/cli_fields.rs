@@ -7,6 +7,8 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use fallout_core::fallout1::SaveGame as Fallout1SaveGame;
 use fallout_core::fallout1::types as f1_types;
 use fallout_core::fallout2::SaveGame as Fallout2SaveGame;
+use fallout_core::object::{OBJECT_FLAG_LEFT_HAND, OBJECT_FLAGS_EQUIPPED};
+use fallout_render::encode_png_rgba;
 use serde_json::Value;
 
 const GAME_TIME_TICKS_PER_YEAR: u32 = 315_360_000;
@@ -69,7 +71,7 @@ fn cli_prints_multiple_requested_fields_in_fixed_order() {
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let lines: Vec<&str> = stdout.lines().collect();
-    assert_eq!(lines, vec!["gender=Male", "level=1", "xp=1"]);
+    assert_eq!(lines, vec!["gender=Male", "level=1", "xp=170"]);
 }
 
 #[test]
@@ -306,7 +308,7 @@ fn cli_outputs_selected_fields_as_json() {
     let json: Value = serde_json::from_str(&stdout).expect("stdout should be valid JSON");
     assert_eq!(json["gender"], "Male");
     assert_eq!(json["level"], 1);
-    assert_eq!(json["xp"], 1);
+    assert_eq!(json["xp"], 170);
     assert!(json.get("name").is_none());
 }
 
@@ -409,16 +411,20 @@ fn cli_outputs_default_json_in_expected_order() {
             "elevation",
             "global_var_count",
             "hp",
+            "radiation",
+            "poison",
             "karma",
             "reputation",
             "special",
             "stats",
             "traits",
             "perks",
+            "addictions",
             "skills",
             "tagged_skills",
             "kill_counts",
             "inventory",
+            "queue_events",
         ]
     );
 }
@@ -629,6 +635,130 @@ fn cli_can_edit_traits_perks_and_inventory() {
     let _ = std::fs::remove_file(&out_path);
 }
 
+#[test]
+fn cli_can_equip_and_unequip_items() {
+    let path = fallout2_save_path(1);
+    let path_s = path.to_string_lossy().to_string();
+    let out_path = temp_output_path("fallout_se_equip");
+    let out_path_s = out_path.to_string_lossy().to_string();
+
+    let output = run_cli(&[
+        "--unequip",
+        "right-hand",
+        "--equip",
+        "left-hand:591",
+        "--output",
+        &out_path_s,
+        &path_s,
+    ]);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let file = File::open(&out_path).expect("expected output file to be created");
+    let save = Fallout2SaveGame::parse(BufReader::new(file))
+        .expect("output file should parse as Fallout 2 save");
+    let flags_of = |pid: i32| -> Vec<i32> {
+        save.player_object
+            .inventory
+            .iter()
+            .filter(|item| item.object.pid == pid)
+            .map(|item| item.object.flags & OBJECT_FLAGS_EQUIPPED)
+            .collect()
+    };
+    assert_eq!(flags_of(7), [0]);
+    assert_eq!(flags_of(591), [OBJECT_FLAG_LEFT_HAND, 0]);
+
+    let bad = run_cli(&["--equip", "belt:591", "--output", &out_path_s, &path_s]);
+    assert!(!bad.status.success());
+
+    let _ = std::fs::remove_file(&out_path);
+}
+
+#[test]
+fn cli_can_set_tagged_skills() {
+    let path = fallout2_save_path(1);
+    let path_s = path.to_string_lossy().to_string();
+    let out_path = temp_output_path("fallout_se_tagged_skills");
+    let out_path_s = out_path.to_string_lossy().to_string();
+
+    let output = run_cli(&[
+        "--set-tagged-skills",
+        "8,0,5",
+        "--output",
+        &out_path_s,
+        &path_s,
+    ]);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let file = File::open(&out_path).expect("expected output file to be created");
+    let save = Fallout2SaveGame::parse(BufReader::new(file))
+        .expect("output file should parse as Fallout 2 save");
+    assert_eq!(save.tagged_skills, [8, 0, 5, -1]);
+
+    let too_many = run_cli(&[
+        "--set-tagged-skills",
+        "0,1,2,3",
+        "--output",
+        &out_path_s,
+        "--force-overwrite",
+        &path_s,
+    ]);
+    assert!(!too_many.status.success());
+
+    let _ = std::fs::remove_file(&out_path);
+}
+
+#[test]
+fn cli_can_set_preferences() {
+    let path = fallout2_save_path(1);
+    let path_s = path.to_string_lossy().to_string();
+    let out_path = temp_output_path("fallout_se_preferences");
+    let out_path_s = out_path.to_string_lossy().to_string();
+
+    let output = run_cli(&[
+        "--set-preference",
+        "game_difficulty=2",
+        "--set-preference",
+        "violence-level=1",
+        "--set-preference",
+        "mouse_sensitivity=2.5",
+        "--output",
+        &out_path_s,
+        &path_s,
+    ]);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let file = File::open(&out_path).expect("expected output file to be created");
+    let save = Fallout2SaveGame::parse(BufReader::new(file))
+        .expect("output file should parse as Fallout 2 save");
+    assert_eq!(save.preferences.game_difficulty, 2);
+    assert_eq!(save.preferences.violence_level, 1);
+    assert_eq!(save.preferences.mouse_sensitivity, 2.5);
+
+    let unknown = run_cli(&[
+        "--set-preference",
+        "gore=1",
+        "--output",
+        &out_path_s,
+        "--force-overwrite",
+        &path_s,
+    ]);
+    assert!(!unknown.status.success());
+
+    let _ = std::fs::remove_file(&out_path);
+}
+
 #[test]
 fn cli_refuses_to_overwrite_output_without_force_flag() {
     let path = fallout2_save_path(1);
@@ -684,3 +814,56 @@ fn cli_can_force_overwrite_and_create_backup() {
     let _ = std::fs::remove_file(&out_path);
     let _ = std::fs::remove_file(&backup_path);
 }
+
+#[test]
+fn cli_replaces_thumbnail_from_png() {
+    let path = fallout2_save_path(1);
+    let path_s = path.to_string_lossy().to_string();
+    let image_path = temp_output_path("fallout_se_thumbnail_png");
+    let image_s = image_path.to_string_lossy().to_string();
+    let out_path = temp_output_path("fallout_se_thumbnail_save");
+    let out_path_s = out_path.to_string_lossy().to_string();
+
+    let image = [100u8, 100, 100, 255].repeat(8 * 8);
+    std::fs::write(&image_path, encode_png_rgba(&image, 8, 8)).expect("failed to write PNG");
+
+    // Without color.pal the thumbnail is refused.
+    let output = run_cli(&[
+        "--set-thumbnail",
+        &image_s,
+        "--output",
+        &out_path_s,
+        &path_s,
+    ]);
+    assert!(!output.status.success());
+    assert!(!out_path.exists());
+
+    // A grey-ramp color.pal: index i is grey level i rounded down to 6 bits.
+    let install_dir = temp_install_dir("fallout_se_thumbnail_palette");
+    std::fs::create_dir_all(&install_dir).expect("failed to create install dir");
+    let palette: Vec<u8> = (0..=255u8).flat_map(|index| [index >> 2; 3]).collect();
+    std::fs::write(install_dir.join("color.pal"), palette).expect("failed to write color.pal");
+    let install_dir_s = install_dir.to_string_lossy().to_string();
+
+    let output = run_cli(&[
+        "--install-dir",
+        &install_dir_s,
+        "--set-thumbnail",
+        &image_s,
+        "--output",
+        &out_path_s,
+        "--level",
+        &path_s,
+    ]);
+    assert!(output.status.success());
+
+    let file = File::open(&out_path).expect("expected output file to be created");
+    let save = Fallout2SaveGame::parse(BufReader::new(file))
+        .expect("output file should parse as Fallout 2 save");
+    assert_eq!(save.header.preview.len(), 224 * 133);
+    assert!(save.header.preview.iter().all(|&index| index == 100));
+
+    let _ = std::fs::remove_file(&image_path);
+    let _ = std::fs::remove_file(&out_path);
+    let _ = std::fs::remove_dir_all(&install_dir);
+}
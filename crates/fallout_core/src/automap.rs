@@ -0,0 +1,460 @@
+//! The automap database: `AUTOMAP.SAV` in a save slot (`automap.db` while the
+//! game runs).
+//!
+//! A version byte and the file size are followed by an index holding one
+//! offset per map and elevation, then one entry for every elevation the
+//! player has explored. An entry records two bits per hex: whether a seen
+//! wall or seen scenery sits there. Entries are usually LZSS-compressed.
+//! Fallout 2 also gzips the whole file; Fallout 1 stores it as-is.
+
+use std::collections::BTreeMap;
+use std::io::{self, Cursor, Read, Write};
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+use crate::map_save::{ELEVATION_COUNT, HEX_GRID_SIZE, MapSave};
+use crate::object::{GameObject, OBJ_TYPE_SCENERY, OBJ_TYPE_WALL, obj_type_from_fid};
+use crate::reader::BigEndianReader;
+
+pub const AUTOMAP_VERSION: u8 = 1;
+pub const AUTOMAP_MAP_COUNT_FALLOUT1: usize = 66;
+pub const AUTOMAP_MAP_COUNT_FALLOUT2: usize = 160;
+/// Decoded size of one elevation: two bits for each of the 200x200 hexes.
+pub const AUTOMAP_DATA_SIZE: usize = HEX_GRID_SIZE as usize / 4;
+
+pub const AUTOMAP_TILE_EMPTY: u8 = 0;
+pub const AUTOMAP_TILE_WALL: u8 = 1;
+pub const AUTOMAP_TILE_SCENERY: u8 = 2;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const HEADER_LEN: usize = 5;
+const OFFSET_DISABLED: i32 = -1;
+const HEX_GRID_WIDTH: usize = 200;
+const BYTES_PER_ROW: usize = HEX_GRID_WIDTH / 4;
+/// Invisible blocker scenery; the game leaves it off the automap.
+const BLOCKER_SCENERY_FID: i32 = 0x200_0015;
+
+const LZSS_RING_SIZE: usize = 4096;
+const LZSS_MAX_MATCH: usize = 18;
+const LZSS_THRESHOLD: usize = 2;
+
+/// What the index records for one map elevation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AutomapEntry {
+    /// The map has no automap on this elevation (`-1` in the index).
+    Disabled,
+    /// Nothing explored yet.
+    Empty,
+    Explored(AutomapTiles),
+}
+
+/// The explored hexes of one map elevation.
+#[derive(Debug, Clone)]
+pub struct AutomapTiles {
+    data: Vec<u8>,
+    /// The entry as read from disk, kept so unedited entries are written
+    /// back unchanged.
+    stored: Option<StoredEntry>,
+}
+
+#[derive(Debug, Clone)]
+struct StoredEntry {
+    compressed: bool,
+    payload: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Automap {
+    /// Whether the file was gzip-compressed (Fallout 2) when read.
+    pub compressed: bool,
+    entries: Vec<[AutomapEntry; ELEVATION_COUNT]>,
+    /// Explored entries in file order, so a rewrite keeps the game's layout.
+    order: Vec<(usize, usize)>,
+    /// The decompressed file as read. The game leaves stale gaps between
+    /// entries, so an unedited automap is written back from this.
+    source: Option<Vec<u8>>,
+}
+
+impl Automap {
+    /// Parse an automap database as read from disk, gzipped or not.
+    /// `map_count` is [`AUTOMAP_MAP_COUNT_FALLOUT1`] or
+    /// [`AUTOMAP_MAP_COUNT_FALLOUT2`].
+    pub fn parse(bytes: &[u8], map_count: usize) -> io::Result<Self> {
+        if bytes.starts_with(&GZIP_MAGIC) {
+            let mut payload = Vec::new();
+            GzDecoder::new(bytes).read_to_end(&mut payload)?;
+            let mut automap = Self::parse_uncompressed(&payload, map_count)?;
+            automap.compressed = true;
+            Ok(automap)
+        } else {
+            Self::parse_uncompressed(bytes, map_count)
+        }
+    }
+
+    pub fn parse_uncompressed(data: &[u8], map_count: usize) -> io::Result<Self> {
+        let mut r = BigEndianReader::new(Cursor::new(data));
+        let version = r.read_u8()?;
+        if version != AUTOMAP_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported automap version {version}"),
+            ));
+        }
+        let data_size = r.read_i32()?;
+        if usize::try_from(data_size).ok() != Some(data.len()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "automap records size {data_size}, but the file is {} bytes",
+                    data.len()
+                ),
+            ));
+        }
+        let offsets = r.read_i32_vec(map_count * ELEVATION_COUNT)?;
+
+        let mut entries = vec![[const { AutomapEntry::Empty }; ELEVATION_COUNT]; map_count];
+        let mut by_offset = BTreeMap::new();
+        for (index, &offset) in offsets.iter().enumerate() {
+            let (map, elevation) = (index / ELEVATION_COUNT, index % ELEVATION_COUNT);
+            entries[map][elevation] = match offset {
+                OFFSET_DISABLED => AutomapEntry::Disabled,
+                0 => AutomapEntry::Empty,
+                offset if offset > 0 => {
+                    by_offset.insert(offset, (map, elevation));
+                    r.seek_to(offset as u64)?;
+                    AutomapEntry::Explored(parse_entry(&mut r, map, elevation)?)
+                }
+                offset => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "invalid automap offset {offset} for map {map} elevation {elevation}"
+                        ),
+                    ));
+                }
+            };
+        }
+
+        Ok(Self {
+            compressed: false,
+            entries,
+            order: by_offset.into_values().collect(),
+            source: Some(data.to_vec()),
+        })
+    }
+
+    /// Encode the automap the way it was read: gzipped when the source was.
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let payload = self.to_uncompressed_bytes();
+        if !self.compressed {
+            return Ok(payload);
+        }
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&payload)?;
+        encoder.finish()
+    }
+
+    /// The database as the game keeps it in `automap.db`. Its length is the
+    /// automap size `SAVE.DAT` records.
+    pub fn to_uncompressed_bytes(&self) -> Vec<u8> {
+        if let Some(source) = &self.source {
+            return source.clone();
+        }
+
+        let mut offsets: Vec<i32> = self
+            .entries
+            .iter()
+            .flatten()
+            .map(|entry| match entry {
+                AutomapEntry::Disabled => OFFSET_DISABLED,
+                _ => 0,
+            })
+            .collect();
+
+        let mut body = Vec::new();
+        let index_end = HEADER_LEN + offsets.len() * 4;
+        for (map, elevation) in self.explored_in_file_order() {
+            let AutomapEntry::Explored(tiles) = &self.entries[map][elevation] else {
+                continue;
+            };
+            offsets[map * ELEVATION_COUNT + elevation] = (index_end + body.len()) as i32;
+            let (compressed, payload) = match &tiles.stored {
+                Some(stored) => (stored.compressed, stored.payload.as_slice()),
+                None => (false, tiles.data.as_slice()),
+            };
+            body.extend_from_slice(&(payload.len() as i32).to_be_bytes());
+            body.push(u8::from(compressed));
+            body.extend_from_slice(payload);
+        }
+
+        let mut out = Vec::with_capacity(index_end + body.len());
+        out.push(AUTOMAP_VERSION);
+        out.extend_from_slice(&((index_end + body.len()) as i32).to_be_bytes());
+        for offset in offsets {
+            out.extend_from_slice(&offset.to_be_bytes());
+        }
+        out.extend_from_slice(&body);
+        out
+    }
+
+    pub fn map_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn entry(&self, map: usize, elevation: usize) -> Option<&AutomapEntry> {
+        self.entries.get(map)?.get(elevation)
+    }
+
+    pub fn tiles(&self, map: usize, elevation: usize) -> Option<&AutomapTiles> {
+        match self.entry(map, elevation)? {
+            AutomapEntry::Explored(tiles) => Some(tiles),
+            _ => None,
+        }
+    }
+
+    /// Every explored map elevation, by map then elevation.
+    pub fn explored(&self) -> impl Iterator<Item = (usize, usize, &AutomapTiles)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .flat_map(|(map, elevations)| {
+                elevations
+                    .iter()
+                    .enumerate()
+                    .filter_map(move |(elevation, entry)| match entry {
+                        AutomapEntry::Explored(tiles) => Some((map, elevation, tiles)),
+                        _ => None,
+                    })
+            })
+    }
+
+    /// Forget everything explored on `map`.
+    pub fn clear(&mut self, map: usize) -> io::Result<()> {
+        for entry in self.map_entries_mut(map)? {
+            if let AutomapEntry::Explored(_) = entry {
+                *entry = AutomapEntry::Empty;
+            }
+        }
+        self.source = None;
+        Ok(())
+    }
+
+    /// Mark every wall and piece of scenery on `map` as seen, using the
+    /// objects of the map's save. Elevations without an automap stay as they
+    /// are.
+    pub fn reveal_all(&mut self, map: usize, map_save: &MapSave) -> io::Result<()> {
+        if usize::try_from(map_save.header.map_index).ok() != Some(map) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "map save {} is map {}, not {map}",
+                    map_save.name(),
+                    map_save.header.map_index
+                ),
+            ));
+        }
+
+        let entries = self.map_entries_mut(map)?;
+        for (elevation, entry) in entries.iter_mut().enumerate() {
+            if *entry == AutomapEntry::Disabled || !map_save.header.has_elevation(elevation) {
+                continue;
+            }
+            let mut tiles = AutomapTiles::default();
+            for object in &map_save.objects[elevation] {
+                if let Some(value) = automap_tile_for(object) {
+                    tiles.set_tile(object.tile, value);
+                }
+            }
+            *entry = AutomapEntry::Explored(tiles);
+        }
+        self.source = None;
+        Ok(())
+    }
+
+    fn map_entries_mut(&mut self, map: usize) -> io::Result<&mut [AutomapEntry; ELEVATION_COUNT]> {
+        let count = self.entries.len();
+        self.entries.get_mut(map).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid automap map index {map}, expected 0..{count}"),
+            )
+        })
+    }
+
+    fn explored_in_file_order(&self) -> Vec<(usize, usize)> {
+        let mut order = self.order.clone();
+        for (map, elevation, _) in self.explored() {
+            if !order.contains(&(map, elevation)) {
+                order.push((map, elevation));
+            }
+        }
+        order
+    }
+}
+
+impl AutomapTiles {
+    /// What the automap shows on `tile`: [`AUTOMAP_TILE_EMPTY`],
+    /// [`AUTOMAP_TILE_WALL`] or [`AUTOMAP_TILE_SCENERY`].
+    pub fn tile(&self, tile: i32) -> u8 {
+        match bit_position(tile) {
+            Some((byte, shift)) => (self.data[byte] >> shift) & 0x03,
+            None => AUTOMAP_TILE_EMPTY,
+        }
+    }
+
+    /// Set what the automap shows on `tile`. Tiles off the grid are ignored.
+    pub fn set_tile(&mut self, tile: i32, value: u8) {
+        if let Some((byte, shift)) = bit_position(tile) {
+            self.data[byte] = (self.data[byte] & !(0x03 << shift)) | ((value & 0x03) << shift);
+            self.stored = None;
+        }
+    }
+
+    /// Number of hexes with something recorded on them.
+    pub fn explored_count(&self) -> usize {
+        (0..HEX_GRID_SIZE)
+            .filter(|&tile| self.tile(tile) != AUTOMAP_TILE_EMPTY)
+            .count()
+    }
+
+    /// The decoded entry, two bits per hex in the game's packing.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+/// Tiles compare by what they record, not by how they were stored.
+impl PartialEq for AutomapTiles {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+impl Eq for AutomapTiles {}
+
+impl Default for AutomapTiles {
+    fn default() -> Self {
+        Self {
+            data: vec![0; AUTOMAP_DATA_SIZE],
+            stored: None,
+        }
+    }
+}
+
+fn parse_entry<R: Read + std::io::Seek>(
+    r: &mut BigEndianReader<R>,
+    map: usize,
+    elevation: usize,
+) -> io::Result<AutomapTiles> {
+    let size = r.read_i32()?;
+    let compressed = match r.read_u8()? {
+        0 => false,
+        1 => true,
+        flag => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid compression flag {flag} for map {map} elevation {elevation}"),
+            ));
+        }
+    };
+    let size = usize::try_from(size)
+        .ok()
+        .filter(|&size| size <= AUTOMAP_DATA_SIZE * 2)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid entry size {size} for map {map} elevation {elevation}"),
+            )
+        })?;
+    let payload = r.read_bytes(size)?;
+
+    let data = if compressed {
+        lzss_decompress(&payload, AUTOMAP_DATA_SIZE)
+    } else {
+        payload.clone()
+    };
+    if data.len() != AUTOMAP_DATA_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "map {map} elevation {elevation} decodes to {} bytes, expected {AUTOMAP_DATA_SIZE}",
+                data.len()
+            ),
+        ));
+    }
+
+    Ok(AutomapTiles {
+        data,
+        stored: Some(StoredEntry {
+            compressed,
+            payload,
+        }),
+    })
+}
+
+/// What `object` leaves on the automap once seen. The game records walls
+/// and scenery; everything else, and objects off the grid, leave no trace.
+pub fn automap_tile_for(object: &GameObject) -> Option<u8> {
+    if object.tile < 0 {
+        return None;
+    }
+    match obj_type_from_fid(object.fid) {
+        OBJ_TYPE_WALL => Some(AUTOMAP_TILE_WALL),
+        OBJ_TYPE_SCENERY if object.fid != BLOCKER_SCENERY_FID => Some(AUTOMAP_TILE_SCENERY),
+        _ => None,
+    }
+}
+
+/// Byte and bit shift of a hex in the packed entry. The game counts columns
+/// from the east edge starting at one, so column 0 lands in the first byte of
+/// the next row; the very last hex has no room and is never recorded.
+fn bit_position(tile: i32) -> Option<(usize, u32)> {
+    let tile = usize::try_from(tile)
+        .ok()
+        .filter(|&t| t < HEX_GRID_SIZE as usize)?;
+    let (row, column) = (tile / HEX_GRID_WIDTH, tile % HEX_GRID_WIDTH);
+    let from_east = HEX_GRID_WIDTH - column;
+    let byte = from_east / 4 + BYTES_PER_ROW * row;
+    let shift = 2 * (3 - (from_east % 4)) as u32;
+    (byte < AUTOMAP_DATA_SIZE).then_some((byte, shift))
+}
+
+/// Decode the LZSS variant the game uses for automap entries: a 4 KiB ring
+/// buffer primed with spaces, and flag bytes whose set bits mark literals.
+fn lzss_decompress(src: &[u8], expected_len: usize) -> Vec<u8> {
+    let mut ring = [b' '; LZSS_RING_SIZE];
+    let mut ring_pos = LZSS_RING_SIZE - LZSS_MAX_MATCH;
+    let mut out = Vec::with_capacity(expected_len);
+    let mut input = src.iter().copied();
+    let mut flags = 0u32;
+
+    while out.len() < expected_len {
+        flags >>= 1;
+        if flags & 0x100 == 0 {
+            let Some(byte) = input.next() else { break };
+            flags = u32::from(byte) | 0xff00;
+        }
+        let (start, len) = if flags & 1 != 0 {
+            let Some(byte) = input.next() else { break };
+            // A literal is a one-byte match on the slot about to be written.
+            ring[ring_pos] = byte;
+            (ring_pos, 1)
+        } else {
+            let (Some(low), Some(high)) = (input.next(), input.next()) else {
+                break;
+            };
+            let start = usize::from(low) | (usize::from(high & 0xf0) << 4);
+            (start, usize::from(high & 0x0f) + LZSS_THRESHOLD + 1)
+        };
+        for k in 0..len {
+            let byte = ring[(start + k) % LZSS_RING_SIZE];
+            out.push(byte);
+            ring[ring_pos] = byte;
+            ring_pos = (ring_pos + 1) % LZSS_RING_SIZE;
+        }
+    }
+    out.truncate(expected_len);
+    out
+}
@@ -0,0 +1,125 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Write `bytes` to a temporary file next to `out_path`, then rename it into
+/// place so an interrupted write never leaves a partial file behind. An
+/// existing file is replaced.
+pub fn write_file_atomically(out_path: &Path, bytes: &[u8]) -> io::Result<()> {
+    create_parent_dir(out_path)?;
+
+    let temp_path = temporary_sibling(out_path, "save.dat");
+    fs::write(&temp_path, bytes).map_err(|e| {
+        with_context(
+            e,
+            format!("failed to write temp file {}", temp_path.display()),
+        )
+    })?;
+
+    match fs::rename(&temp_path, out_path) {
+        Ok(()) => Ok(()),
+        // Some platforms refuse to rename over an existing file.
+        Err(rename_err) if out_path.exists() => {
+            fs::remove_file(out_path).map_err(|e| {
+                let _ = fs::remove_file(&temp_path);
+                with_context(
+                    e,
+                    format!(
+                        "failed to replace existing output {} after rename error ({rename_err})",
+                        out_path.display()
+                    ),
+                )
+            })?;
+            fs::rename(&temp_path, out_path).map_err(|e| {
+                with_context(
+                    e,
+                    format!(
+                        "failed to rename temp file {} to {}",
+                        temp_path.display(),
+                        out_path.display()
+                    ),
+                )
+            })
+        }
+        Err(rename_err) => {
+            let _ = fs::remove_file(&temp_path);
+            Err(with_context(
+                rename_err,
+                format!(
+                    "failed to rename temp file {} to {}",
+                    temp_path.display(),
+                    out_path.display()
+                ),
+            ))
+        }
+    }
+}
+
+/// Build a directory through `fill`, which receives a temporary directory
+/// next to `out_dir` to populate, then rename it to `out_dir`. The temporary
+/// directory is removed if anything fails.
+pub fn write_dir_atomically<F>(out_dir: &Path, fill: F) -> io::Result<()>
+where
+    F: FnOnce(&Path) -> io::Result<()>,
+{
+    create_parent_dir(out_dir)?;
+
+    let temp_dir = temporary_sibling(out_dir, "slot");
+    let result = fs::create_dir(&temp_dir)
+        .map_err(|e| {
+            with_context(
+                e,
+                format!("failed to create directory {}", temp_dir.display()),
+            )
+        })
+        .and_then(|()| fill(&temp_dir))
+        .and_then(|()| {
+            fs::rename(&temp_dir, out_dir).map_err(|e| {
+                with_context(
+                    e,
+                    format!(
+                        "failed to rename temp directory {} to {}",
+                        temp_dir.display(),
+                        out_dir.display()
+                    ),
+                )
+            })
+        });
+    if result.is_err() {
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+    result
+}
+
+fn create_parent_dir(path: &Path) -> io::Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).map_err(|e| {
+            with_context(
+                e,
+                format!("failed to create parent directory {}", parent.display()),
+            )
+        })?;
+    }
+    Ok(())
+}
+
+fn temporary_sibling(path: &Path, default_name: &str) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+
+    let base_name = path
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| default_name.to_string());
+    path.with_file_name(format!(".{base_name}.tmp.{}.{}", process::id(), timestamp))
+}
+
+fn with_context(e: io::Error, context: String) -> io::Error {
+    io::Error::new(e.kind(), format!("{context}: {e}"))
+}
@@ -1,5 +1,6 @@
 use std::io;
 
+const I16_WIDTH: usize = 2;
 const I32_WIDTH: usize = 4;
 
 #[derive(Debug, Clone)]
@@ -29,6 +30,28 @@ pub fn patch_i32_in_blob(
     Ok(())
 }
 
+pub fn patch_i16_in_blob(
+    blob: &mut SectionBlob,
+    offset: usize,
+    raw: i16,
+    section_label: &str,
+    field_label: &str,
+) -> io::Result<()> {
+    if blob.bytes.len() < offset + I16_WIDTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "{section_label} too short for {field_label} patch: len={}, need at least {}",
+                blob.bytes.len(),
+                offset + I16_WIDTH
+            ),
+        ));
+    }
+
+    blob.bytes[offset..offset + I16_WIDTH].copy_from_slice(&raw.to_be_bytes());
+    Ok(())
+}
+
 pub fn patch_fixed_string_in_blob(
     blob: &mut SectionBlob,
     offset: usize,
@@ -71,3 +94,25 @@ pub fn patch_fixed_string_in_blob(
     field[..raw.len()].copy_from_slice(raw);
     Ok(())
 }
+
+pub fn patch_bytes_in_blob(
+    blob: &mut SectionBlob,
+    offset: usize,
+    raw: &[u8],
+    section_label: &str,
+    field_label: &str,
+) -> io::Result<()> {
+    if blob.bytes.len() < offset + raw.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "{section_label} too short for {field_label} patch: len={}, need at least {}",
+                blob.bytes.len(),
+                offset + raw.len()
+            ),
+        ));
+    }
+
+    blob.bytes[offset..offset + raw.len()].copy_from_slice(raw);
+    Ok(())
+}
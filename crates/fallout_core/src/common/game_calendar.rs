@@ -0,0 +1,61 @@
+/// Game time advances ten ticks per second.
+pub const GAME_TIME_TICKS_PER_DAY: u32 = 10 * 60 * 60 * 24;
+
+const DAYS_PER_MONTH: [u32; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+/// The date a game's clock starts at when `game_time` is 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalendarEpoch {
+    pub year: i16,
+    /// Zero-based month.
+    pub month: usize,
+    /// Zero-based day of `month`.
+    pub day: u32,
+}
+
+/// Month, day and year (all one-based except the year) shown for
+/// `game_time`, computed the way the engine does when it writes the save
+/// header.
+pub fn game_date(epoch: CalendarEpoch, game_time: u32) -> (i16, i16, i16) {
+    let days = game_time / GAME_TIME_TICKS_PER_DAY + epoch.day;
+    let mut year = i32::from(epoch.year) + (days / 365) as i32;
+    let mut month = epoch.month;
+    let mut day = days % 365;
+    while day >= DAYS_PER_MONTH[month] {
+        day -= DAYS_PER_MONTH[month];
+        month += 1;
+        if month == DAYS_PER_MONTH.len() {
+            month = 0;
+            year += 1;
+        }
+    }
+    (
+        month as i16 + 1,
+        day as i16 + 1,
+        i16::try_from(year).unwrap_or(i16::MAX),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CalendarEpoch, GAME_TIME_TICKS_PER_DAY, game_date};
+
+    #[test]
+    fn game_date_rolls_months_and_years() {
+        let epoch = CalendarEpoch {
+            year: 2241,
+            month: 6,
+            day: 24,
+        };
+        assert_eq!(game_date(epoch, 0), (7, 25, 2241));
+        assert_eq!(game_date(epoch, 7 * GAME_TIME_TICKS_PER_DAY), (8, 1, 2241));
+        assert_eq!(
+            game_date(epoch, 160 * GAME_TIME_TICKS_PER_DAY),
+            (1, 1, 2242)
+        );
+        assert_eq!(
+            game_date(epoch, 365 * GAME_TIME_TICKS_PER_DAY),
+            (7, 25, 2242)
+        );
+    }
+}
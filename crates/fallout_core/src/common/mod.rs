@@ -1,3 +1,5 @@
+pub mod atomic_write;
 pub mod blob_emission;
 pub mod blob_patching;
+pub mod game_calendar;
 pub mod layout_management;
@@ -1,24 +1,50 @@
 use std::collections::{BTreeMap, BTreeSet};
 use std::io::Cursor;
 
+use crate::event_queue::{QueueEvent, QueueEventData};
 use crate::fallout1;
 use crate::fallout1::types as f1_types;
 use crate::fallout2;
+use crate::fallout2::sections::Preferences;
 use crate::fallout2::types as f2_types;
 use crate::gender::Gender;
+use crate::map_save::MapHeader;
+use crate::object::{
+    self, ArmorPrototype, DAMAGE_TYPE_COUNT, DrugPrototype, GameObject, ITEM_TYPE_ARMOR,
+    ITEM_TYPE_WEAPON, InventoryItem, ItemExtraData, OBJECT_FLAG_LEFT_HAND, OBJECT_FLAG_RIGHT_HAND,
+    OBJECT_FLAG_WORN, ObjectData,
+};
+use crate::perk::{PerkEffect, PerkInfo, PerkKind};
 
 use super::error::{CoreError, CoreErrorCode};
 use super::types::{
-    Capabilities, CapabilityIssue, CharacterExport, DateParts, Game, InventoryEntry,
-    KillCountEntry, PerkEntry, ResolvedInventoryEntry, SkillEntry, Snapshot, StatEntry, TraitEntry,
+    AddictionEntry, Capabilities, CapabilityIssue, CharacterExport, CharacterRule, DateParts,
+    EquipmentSlot, EquippedItems, Game, GlobalVarEntry, InventoryEntry, KillCountEntry,
+    LevelUpReport, PerkEntry, ResolvedInventoryEntry, RuleViolation, SkillEntry, Snapshot,
+    StatEntry, TraitEntry,
+};
+use super::{
+    GlobalVarCatalog, ItemCatalog, Palette, THUMBNAIL_HEIGHT, THUMBNAIL_WIDTH, TraitCatalog,
+    resize_rgba,
 };
-use super::{ItemCatalog, TraitCatalog};
 
+const STAT_ENDURANCE: usize = 2;
+const STAT_INTELLIGENCE: usize = 4;
+const STAT_MAXIMUM_HIT_POINTS: usize = 7;
+const STAT_ARMOR_CLASS: usize = 9;
+const STAT_DAMAGE_THRESHOLD_FIRST: usize = 17;
+const STAT_DAMAGE_RESISTANCE_FIRST: usize = 24;
+const ARMOR_STAT_COUNT: usize = 1 + 2 * DAMAGE_TYPE_COUNT;
 const STAT_AGE_INDEX: usize = 33;
 const STAT_GENDER_INDEX: usize = 34;
 const GAME_TIME_TICKS_PER_YEAR: u32 = 315_360_000;
 const INVENTORY_CAPS_PID: i32 = -1;
 const TRAIT_SLOT_COUNT: usize = 2;
+const UNSPENT_SKILL_POINTS_MAX: i32 = 99;
+const SPECIAL_STAT_COUNT: usize = 7;
+const SPECIAL_MIN: i32 = 1;
+const SPECIAL_MAX: i32 = 10;
+const TAGGED_SKILL_LIMIT: usize = 3;
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Engine;
@@ -119,6 +145,8 @@ impl Session {
             elevation: snapshot.elevation,
             global_var_count: snapshot.global_var_count,
             hp: self.current_hp(),
+            radiation: self.radiation(),
+            poison: self.poison(),
             karma: snapshot.karma,
             reputation: snapshot.reputation,
             special: self.special_stats(),
@@ -129,6 +157,8 @@ impl Session {
             tagged_skills: self.tagged_skill_indices(),
             kill_counts: self.nonzero_kill_counts(),
             inventory: self.inventory(),
+            queue_events: self.queue_events().ok().map(<[QueueEvent]>::to_vec),
+            addictions: self.addictions(),
         }
     }
 
@@ -144,6 +174,17 @@ impl Session {
         }
 
         let current = self.export_character();
+        // Older exports carry an empty list for a queue that was not decoded,
+        // so only a non-empty list that differs counts as an edit.
+        if let Some(events) = &character.queue_events
+            && !events.is_empty()
+            && Some(events) != current.queue_events.as_ref()
+        {
+            return Err(CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                "cannot edit queue events via character export",
+            ));
+        }
 
         for stat in &character.special {
             let current_base = current
@@ -200,6 +241,16 @@ impl Session {
             };
             self.set_hp(hp)?;
         }
+        if character.radiation != current.radiation
+            && let Some(radiation) = character.radiation
+        {
+            self.set_radiation(radiation)?;
+        }
+        if character.poison != current.poison
+            && let Some(poison) = character.poison
+        {
+            self.set_poison(poison)?;
+        }
 
         if let Some(effective_age) = export_age_total(&character.stats)
             && Some(effective_age) != export_age_total(&current.stats)
@@ -217,6 +268,13 @@ impl Session {
         if character.perks != current.perks {
             self.apply_perks_from_export(&character.perks)?;
         }
+        if character.kill_counts != current.kill_counts {
+            self.apply_kill_counts_from_export(&character.kill_counts)?;
+        }
+        // After perks, since a newly granted Tag! perk raises the tag limit.
+        if character.tagged_skills != current.tagged_skills {
+            self.set_tagged_skills(&character.tagged_skills)?;
+        }
         if character.inventory != current.inventory {
             self.apply_inventory_from_export(&character.inventory)?;
         }
@@ -338,6 +396,68 @@ impl Session {
         }
     }
 
+    /// Selection rules for a perk: ranks, level, SPECIAL and skill
+    /// requirements, and whether it is hidden or an addiction.
+    pub fn perk_info(&self, perk_index: usize) -> Option<PerkInfo> {
+        self.character_rules().perk_info.get(perk_index).copied()
+    }
+
+    /// Perks the character could take on the perk screen right now: selectable,
+    /// below their maximum rank, and with the level, SPECIAL and skill
+    /// requirements met. `rank` is the current rank. Whether a perk pick is
+    /// pending is not checked.
+    pub fn eligible_perks(&self) -> Vec<PerkEntry> {
+        let rules = self.character_rules();
+        let level = self.snapshot.level;
+        let skill_totals: Vec<i32> = self.skills().iter().map(|skill| skill.total).collect();
+        rules
+            .perk_info
+            .iter()
+            .enumerate()
+            .filter(|(index, info)| {
+                info.kind == PerkKind::Selectable
+                    && rules.perks[*index].max(0) < info.max_rank
+                    && level >= info.min_level
+                    && rules
+                        .special
+                        .iter()
+                        .enumerate()
+                        .all(|(stat, &value)| info.special_allows(stat, value))
+                    && info.skills_allow(&skill_totals)
+            })
+            .map(|(index, _)| PerkEntry {
+                index,
+                name: rules.perk_names[index].to_string(),
+                rank: rules.perks[index].max(0),
+            })
+            .collect()
+    }
+
+    /// Addiction perks the character has, plus those only pending as a
+    /// queued withdrawal event. Without a decoded event queue the addictions
+    /// come from the perks alone and their withdrawal counts are unknown.
+    pub fn addictions(&self) -> Vec<AddictionEntry> {
+        let rules = self.character_rules();
+        let queue_events = self.queue_events().ok();
+        rules
+            .perk_info
+            .iter()
+            .enumerate()
+            .filter(|(_, info)| info.kind == PerkKind::Addiction)
+            .filter_map(|(index, _)| {
+                let active = rules.perks[index] > 0;
+                let withdrawal_events =
+                    queue_events.map(|events| withdrawal_event_count(events, index));
+                (active || withdrawal_events > Some(0)).then(|| AddictionEntry {
+                    index,
+                    name: rules.perk_names[index].to_string(),
+                    active,
+                    withdrawal_events,
+                })
+            })
+            .collect()
+    }
+
     pub fn selected_traits_resolved(&self, catalog: Option<&TraitCatalog>) -> Vec<TraitEntry> {
         let traits = match &self.document {
             LoadedDocument::Fallout1(doc) => doc.save.selected_traits,
@@ -365,6 +485,67 @@ impl Session {
         self.selected_traits_resolved(None)
     }
 
+    /// Palette indices of the 224x133 load-menu thumbnail, row by row.
+    pub fn thumbnail_indices(&self) -> &[u8] {
+        match &self.document {
+            LoadedDocument::Fallout1(doc) => &doc.save.header.preview,
+            LoadedDocument::Fallout2(doc) => &doc.save.header.preview,
+        }
+    }
+
+    /// The thumbnail as RGBA pixels, coloured with `palette` or, without one,
+    /// [`Palette::builtin`].
+    pub fn thumbnail_rgba_resolved(&self, palette: Option<&Palette>) -> Vec<u8> {
+        match palette {
+            Some(palette) => palette.to_rgba(self.thumbnail_indices()),
+            None => Palette::builtin().to_rgba(self.thumbnail_indices()),
+        }
+    }
+
+    pub fn thumbnail_rgba(&self) -> Vec<u8> {
+        self.thumbnail_rgba_resolved(None)
+    }
+
+    pub fn global_vars(&self) -> &[i32] {
+        match &self.document {
+            LoadedDocument::Fallout1(doc) => &doc.save.global_vars,
+            LoadedDocument::Fallout2(doc) => &doc.save.global_vars,
+        }
+    }
+
+    pub fn global_var(&self, index: usize) -> Option<i32> {
+        self.global_vars().get(index).copied()
+    }
+
+    pub fn global_vars_resolved(&self, catalog: Option<&GlobalVarCatalog>) -> Vec<GlobalVarEntry> {
+        self.global_vars()
+            .iter()
+            .enumerate()
+            .map(|(index, &value)| GlobalVarEntry {
+                index,
+                name: catalog
+                    .and_then(|catalog| catalog.get(index))
+                    .map(str::to_string),
+                value,
+            })
+            .collect()
+    }
+
+    /// Pending event queue entries, in fire-time order. Errors when the
+    /// queue was not decoded because the handlers around it did not parse.
+    pub fn queue_events(&self) -> Result<&[QueueEvent], CoreError> {
+        let queue_events = match &self.document {
+            LoadedDocument::Fallout1(doc) => doc.save.queue_events.as_deref(),
+            LoadedDocument::Fallout2(doc) => doc.save.queue_events.as_deref(),
+        };
+        queue_events.ok_or_else(|| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                "the event queue was not decoded",
+            )
+        })
+    }
+
     pub fn all_kill_counts(&self) -> Vec<KillCountEntry> {
         match &self.document {
             LoadedDocument::Fallout1(doc) => doc
@@ -436,17 +617,105 @@ impl Session {
         }
     }
 
+    /// Uncompressed size of the slot's `AUTOMAP.SAV`, as `SAVE.DAT` records it.
+    pub fn automap_size(&self) -> i32 {
+        match &self.document {
+            LoadedDocument::Fallout1(doc) => doc.save.automap_size,
+            LoadedDocument::Fallout2(doc) => doc.save.automap_size,
+        }
+    }
+
+    /// Hex tile the player stands on.
+    pub fn player_tile(&self) -> i32 {
+        match &self.document {
+            LoadedDocument::Fallout1(doc) => doc.save.player_object.tile,
+            LoadedDocument::Fallout2(doc) => doc.save.player_object.tile,
+        }
+    }
+
+    /// Move the player to `tile` on `elevation` of the current map.
+    ///
+    /// `SAVE.DAT` only lists map saves by file name, so moving to another map
+    /// needs that map's save: see [`Session::teleport_to_map_save`], or
+    /// [`SlotSession::teleport`](super::SlotSession::teleport) which looks the
+    /// map id up in the slot's map saves.
+    pub fn teleport(&mut self, elevation: i16, tile: i32) -> Result<(), CoreError> {
+        let map_file = self.snapshot.map_filename.clone();
+        let map_id = self.snapshot.map_id;
+        self.set_player_location(&map_file, map_id, elevation, tile)
+    }
+
+    /// Move the player to `tile` on `elevation` of the map saved in the slot
+    /// as `map_file` (one of [`Session::map_files`]), whose save header is
+    /// `map`. The map id comes from the header, and `map_file` must name the
+    /// same map.
+    pub fn teleport_to_map_save(
+        &mut self,
+        map_file: &str,
+        map: &MapHeader,
+        elevation: i16,
+        tile: i32,
+    ) -> Result<(), CoreError> {
+        let map_name = map.name();
+        if !file_stem(&map_name).eq_ignore_ascii_case(file_stem(map_file)) {
+            return Err(CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("map save {map_file} is not the save of {map_name}"),
+            ));
+        }
+        let map_id = i16::try_from(map.map_index).map_err(|_| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("{map_file} has invalid map id {}", map.map_index),
+            )
+        })?;
+        if !usize::try_from(elevation).is_ok_and(|elevation| map.has_elevation(elevation)) {
+            return Err(CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("{map_file} has no elevation {elevation}"),
+            ));
+        }
+        self.set_player_location(map_file, map_id, elevation, tile)
+    }
+
+    fn set_player_location(
+        &mut self,
+        map_file: &str,
+        map_id: i16,
+        elevation: i16,
+        tile: i32,
+    ) -> Result<(), CoreError> {
+        let map_filename = match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc
+                .set_player_location(map_id, map_file, elevation, tile)
+                .map(|()| doc.save.header.map_filename.clone()),
+            LoadedDocument::Fallout2(doc) => doc
+                .set_player_location(map_id, map_file, elevation, tile)
+                .map(|()| doc.save.header.map_filename.clone()),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to teleport to {map_file} elevation {elevation} tile {tile}: {e}"),
+            )
+        })?;
+
+        self.snapshot.map_filename = map_filename;
+        self.snapshot.map_id = map_id;
+        self.snapshot.elevation = elevation;
+        Ok(())
+    }
+
     pub fn age(&self) -> i32 {
         self.stat(STAT_AGE_INDEX).total
     }
 
     pub fn max_hp(&self) -> i32 {
-        self.stat(7).total
+        self.stat(STAT_MAXIMUM_HIT_POINTS).total
     }
 
     pub fn next_level_xp(&self) -> i32 {
-        let l = self.snapshot.level;
-        (l + 1) * l / 2 * 1000
+        experience_for_level(self.snapshot.level + 1)
     }
 
     pub fn stat(&self, index: usize) -> StatEntry {
@@ -487,11 +756,7 @@ impl Session {
     }
 
     pub fn inventory(&self) -> Vec<InventoryEntry> {
-        let items = match &self.document {
-            LoadedDocument::Fallout1(doc) => &doc.save.player_object.inventory,
-            LoadedDocument::Fallout2(doc) => &doc.save.player_object.inventory,
-        };
-        items
+        self.player_inventory()
             .iter()
             .map(|item| InventoryEntry {
                 quantity: item.quantity,
@@ -500,37 +765,62 @@ impl Session {
             .collect()
     }
 
+    fn player_inventory(&self) -> &[InventoryItem] {
+        match &self.document {
+            LoadedDocument::Fallout1(doc) => &doc.save.player_object.inventory,
+            LoadedDocument::Fallout2(doc) => &doc.save.player_object.inventory,
+        }
+    }
+
     pub fn inventory_resolved(&self, catalog: &ItemCatalog) -> Vec<ResolvedInventoryEntry> {
-        self.inventory()
-            .into_iter()
+        self.player_inventory()
+            .iter()
             .map(|item| {
-                let meta = catalog.get(item.pid);
-                ResolvedInventoryEntry {
+                let meta = catalog.get(item.object.pid);
+                let item_type = meta.map(|entry| entry.item_type);
+                let mut entry = ResolvedInventoryEntry {
                     quantity: item.quantity,
-                    pid: item.pid,
+                    pid: item.object.pid,
                     name: meta.map(|entry| entry.name.clone()),
                     base_weight: meta.map(|entry| entry.base_weight),
-                    item_type: meta.map(|entry| entry.item_type),
+                    item_type,
+                    ammo_pid: None,
+                    ammo_loaded: None,
+                    charges: None,
+                    key_code: None,
+                };
+                if let Some(item_type) = item_type {
+                    apply_item_extra_data(&mut entry, item_type, &item.object);
                 }
+                entry
             })
             .collect()
     }
 
     /// Resolve inventory using the built-in well-known item table.
     /// Falls back to pid-only entries for items not in the table.
+    ///
+    /// Without item types only weapon ammo can be decoded, since weapons are
+    /// the one item type with 8 bytes of extra data.
     pub fn inventory_resolved_builtin(&self) -> Vec<ResolvedInventoryEntry> {
         let game = self.game();
-        self.inventory()
-            .into_iter()
+        self.player_inventory()
+            .iter()
             .map(|item| {
-                let known = super::well_known_items::lookup(game, item.pid);
-                ResolvedInventoryEntry {
+                let known = super::well_known_items::lookup(game, item.object.pid);
+                let mut entry = ResolvedInventoryEntry {
                     quantity: item.quantity,
-                    pid: item.pid,
+                    pid: item.object.pid,
                     name: known.map(|(name, _)| name.to_string()),
                     base_weight: known.map(|(_, w)| w),
                     item_type: None,
-                }
+                    ammo_pid: None,
+                    ammo_loaded: None,
+                    charges: None,
+                    key_code: None,
+                };
+                apply_item_extra_data(&mut entry, ITEM_TYPE_WEAPON, &item.object);
+                entry
             })
             .collect()
     }
@@ -596,6 +886,76 @@ impl Session {
         Ok(())
     }
 
+    pub fn radiation(&self) -> Option<i32> {
+        player_critter_data(&self.document).map(|data| data.radiation)
+    }
+
+    pub fn poison(&self) -> Option<i32> {
+        player_critter_data(&self.document).map(|data| data.poison)
+    }
+
+    /// Options-screen preferences (handler 17). Only decoded for Fallout 2.
+    pub fn preferences(&self) -> Option<&Preferences> {
+        match &self.document {
+            LoadedDocument::Fallout1(_) => None,
+            LoadedDocument::Fallout2(doc) => Some(&doc.save.preferences),
+        }
+    }
+
+    /// Replace the preferences. The game difficulty feeds into skill totals.
+    pub fn set_preferences(&mut self, preferences: &Preferences) -> Result<(), CoreError> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(_) => Err(fallout1_preferences_unsupported()),
+            LoadedDocument::Fallout2(doc) => doc.set_preferences(preferences),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to set preferences: {e}"),
+            )
+        })
+    }
+
+    pub fn set_game_difficulty(&mut self, difficulty: i32) -> Result<(), CoreError> {
+        let mut preferences = self.preferences().cloned().ok_or_else(|| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!(
+                    "failed to set game difficulty: {}",
+                    fallout1_preferences_unsupported()
+                ),
+            )
+        })?;
+        preferences.game_difficulty = difficulty;
+        self.set_preferences(&preferences)
+    }
+
+    pub fn set_radiation(&mut self, radiation: i32) -> Result<(), CoreError> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.set_radiation(radiation),
+            LoadedDocument::Fallout2(doc) => doc.set_radiation(radiation),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to set radiation: {e}"),
+            )
+        })
+    }
+
+    pub fn set_poison(&mut self, poison: i32) -> Result<(), CoreError> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.set_poison(poison),
+            LoadedDocument::Fallout2(doc) => doc.set_poison(poison),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to set poison: {e}"),
+            )
+        })
+    }
+
     pub fn set_base_stat(&mut self, stat_index: usize, value: i32) -> Result<(), CoreError> {
         if stat_index > 6 {
             return Err(CoreError::new(
@@ -616,6 +976,33 @@ impl Session {
         })
     }
 
+    /// Like [`Session::set_base_stat`], then recompute the derived stats that
+    /// depend on SPECIAL, as the character editor does.
+    pub fn set_base_stat_with_recalc(
+        &mut self,
+        stat_index: usize,
+        value: i32,
+    ) -> Result<(), CoreError> {
+        self.set_base_stat(stat_index, value)?;
+        self.recalculate_derived_stats()
+    }
+
+    /// Recompute Max HP, AP, AC, melee damage, carry weight, sequence,
+    /// healing rate, critical chance and the radiation/poison resistances
+    /// from SPECIAL and traits, as the engine does after a stat change.
+    pub fn recalculate_derived_stats(&mut self) -> Result<(), CoreError> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.recalculate_derived_stats(),
+            LoadedDocument::Fallout2(doc) => doc.recalculate_derived_stats(),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to recalculate derived stats: {e}"),
+            )
+        })
+    }
+
     pub fn set_gender(&mut self, gender: Gender) -> Result<(), CoreError> {
         match &mut self.document {
             LoadedDocument::Fallout1(doc) => doc.set_gender(gender),
@@ -677,6 +1064,56 @@ impl Session {
         Ok(())
     }
 
+    /// Replace the load-menu thumbnail with an RGBA image of any size. The
+    /// image is scaled to 224x133 and quantized to `palette`, which should
+    /// be the game's `color.pal`; the game shows the indices with it.
+    pub fn set_thumbnail(
+        &mut self,
+        rgba: &[u8],
+        width: usize,
+        height: usize,
+        palette: &Palette,
+    ) -> Result<(), CoreError> {
+        if width == 0
+            || height == 0
+            || width.checked_mul(height).and_then(|n| n.checked_mul(4)) != Some(rgba.len())
+        {
+            return Err(CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!(
+                    "thumbnail image is {} bytes, expected {width}x{height} RGBA pixels",
+                    rgba.len()
+                ),
+            ));
+        }
+
+        let scaled = resize_rgba(rgba, width, height, THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT);
+        let indices = palette.quantize(&scaled);
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.set_preview(&indices),
+            LoadedDocument::Fallout2(doc) => doc.set_preview(&indices),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to set thumbnail: {e}"),
+            )
+        })
+    }
+
+    pub fn set_automap_size(&mut self, size: i32) -> Result<(), CoreError> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.set_automap_size(size),
+            LoadedDocument::Fallout2(doc) => doc.set_automap_size(size),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to set automap size: {e}"),
+            )
+        })
+    }
+
     pub fn set_level(&mut self, level: i32) -> Result<(), CoreError> {
         match &mut self.document {
             LoadedDocument::Fallout1(doc) => doc.set_level(level),
@@ -709,59 +1146,388 @@ impl Session {
         Ok(())
     }
 
-    pub fn set_skill_points(&mut self, skill_points: i32) -> Result<(), CoreError> {
-        match &mut self.document {
-            LoadedDocument::Fallout1(doc) => doc.set_skill_points(skill_points),
-            LoadedDocument::Fallout2(doc) => doc.set_skill_points(skill_points),
-        }
-        .map_err(|e| {
-            CoreError::new(
+    /// Raise the character to `level` the way the game's level-up does. Each
+    /// level grants `5 + 2 * INT` skill points (+2 per Educated rank, +5 with
+    /// Skilled, -5 with Gifted; unspent points cap at 99) and `2 + END / 2`
+    /// max HP (+4 per Lifegiver rank), healing by the same amount. A perk pick
+    /// comes every third level, or every fourth with Skilled; on Fallout 2 it
+    /// is left pending in the character editor, as the game does. Experience
+    /// is raised to the new level's minimum when short of it.
+    pub fn level_up_to(&mut self, level: i32) -> Result<LevelUpReport, CoreError> {
+        let (level_max, educated, lifegiver, skilled, gifted) = match &self.document {
+            LoadedDocument::Fallout1(doc) => (
+                f1_types::PC_LEVEL_MAX,
+                doc.save.perks[f1_types::PERK_EDUCATED],
+                doc.save.perks[f1_types::PERK_LIFEGIVER],
+                doc.save.selected_traits.contains(&f1_types::TRAIT_SKILLED),
+                doc.save.selected_traits.contains(&f1_types::TRAIT_GIFTED),
+            ),
+            LoadedDocument::Fallout2(doc) => (
+                f2_types::PC_LEVEL_MAX,
+                doc.save.perks[f2_types::PERK_EDUCATED],
+                doc.save.perks[f2_types::PERK_LIFEGIVER],
+                doc.save.selected_traits.contains(&f2_types::TRAIT_SKILLED),
+                doc.save.selected_traits.contains(&f2_types::TRAIT_GIFTED),
+            ),
+        };
+        let from_level = self.snapshot.level;
+        let editor = match &self.document {
+            LoadedDocument::Fallout1(_) => None,
+            LoadedDocument::Fallout2(doc) => {
+                Some(doc.save.character_editor.clone().ok_or_else(|| {
+                    CoreError::new(
+                        CoreErrorCode::UnsupportedOperation,
+                        "cannot level up: the character editor state was not decoded",
+                    )
+                })?)
+            }
+        };
+        if level <= from_level || level > level_max {
+            return Err(CoreError::new(
                 CoreErrorCode::UnsupportedOperation,
-                format!("failed to set skill points: {e}"),
-            )
-        })?;
+                format!(
+                    "cannot level up from {from_level} to {level}, expected {}..={level_max}",
+                    from_level + 1
+                ),
+            ));
+        }
 
-        self.snapshot.unspent_skill_points = skill_points;
-        Ok(())
-    }
+        let skill_points_per_level = self.skill_points_per_level(educated, skilled);
+        let hit_points_per_level = 2 + self.stat(STAT_ENDURANCE).total / 2 + 4 * lifegiver;
+        let perk_rate = if skilled { 4 } else { 3 };
 
-    pub fn set_skill_base_value(&mut self, skill_index: usize, raw: i32) -> Result<(), CoreError> {
-        match &mut self.document {
-            LoadedDocument::Fallout1(doc) => doc.set_skill_base_value(skill_index, raw),
-            LoadedDocument::Fallout2(doc) => doc.set_skill_base_value(skill_index, raw),
+        let mut unspent = self.snapshot.unspent_skill_points;
+        let mut perk_levels = Vec::new();
+        for next in from_level + 1..=level {
+            unspent += skill_points_per_level;
+            if gifted {
+                unspent = (unspent - 5).max(0);
+            }
+            unspent = unspent.min(UNSPENT_SKILL_POINTS_MAX);
+            if next % perk_rate == 0 {
+                perk_levels.push(next);
+            }
         }
-        .map_err(|e| {
-            CoreError::new(
-                CoreErrorCode::UnsupportedOperation,
-                format!("failed to set skill {skill_index} raw value: {e}"),
-            )
-        })
-    }
+        let report = LevelUpReport {
+            from_level,
+            to_level: level,
+            experience: self.snapshot.experience.max(experience_for_level(level)),
+            skill_points: unspent - self.snapshot.unspent_skill_points,
+            hit_points: hit_points_per_level * (level - from_level),
+            free_perk: editor
+                .as_ref()
+                .is_some_and(|editor| editor.free_perk != 0 || !perk_levels.is_empty()),
+            perk_levels,
+        };
 
-    pub fn set_reputation(&mut self, reputation: i32) -> Result<(), CoreError> {
+        self.set_level(level)?;
+        self.set_experience(report.experience)?;
+        self.set_skill_points(unspent)?;
+        let max_hp_bonus = self.stat(STAT_MAXIMUM_HIT_POINTS).bonus + report.hit_points;
         match &mut self.document {
-            LoadedDocument::Fallout1(doc) => doc.set_reputation(reputation),
-            LoadedDocument::Fallout2(doc) => doc.set_reputation(reputation),
+            LoadedDocument::Fallout1(doc) => {
+                doc.set_bonus_stat(STAT_MAXIMUM_HIT_POINTS, max_hp_bonus)
+            }
+            LoadedDocument::Fallout2(doc) => {
+                doc.set_bonus_stat(STAT_MAXIMUM_HIT_POINTS, max_hp_bonus)
+            }
         }
         .map_err(|e| {
             CoreError::new(
                 CoreErrorCode::UnsupportedOperation,
-                format!("failed to set reputation: {e}"),
+                format!("failed to raise max HP: {e}"),
             )
         })?;
+        if let Some(hp) = self.current_hp() {
+            self.set_hp(hp + report.hit_points)?;
+        }
+        if let (LoadedDocument::Fallout2(doc), Some(mut editor)) = (&mut self.document, editor) {
+            editor.last_level = level;
+            editor.free_perk = u8::from(report.free_perk);
+            doc.set_character_editor_state(&editor).map_err(|e| {
+                CoreError::new(
+                    CoreErrorCode::UnsupportedOperation,
+                    format!("failed to grant the perk pick: {e}"),
+                )
+            })?;
+        }
 
-        self.snapshot.reputation = reputation;
-        Ok(())
+        Ok(report)
     }
 
-    pub fn set_karma(&mut self, karma: i32) -> Result<(), CoreError> {
-        match &mut self.document {
-            LoadedDocument::Fallout1(doc) => doc.set_karma(karma),
-            LoadedDocument::Fallout2(doc) => doc.set_karma(karma),
-        }
-        .map_err(|e| {
-            CoreError::new(
-                CoreErrorCode::UnsupportedOperation,
+    /// Check the character against the rules the game enforces while playing:
+    /// level and experience, SPECIAL limits after traits, the number of tagged
+    /// skills, known traits, perk ranks and requirements, how many perk picks
+    /// and unspent skill points the level could have earned. Perk requirements
+    /// are checked against SPECIAL with the bonuses from armor, drugs and
+    /// items, as the game does. An empty list means nothing was found.
+    pub fn validate_character(&self) -> Vec<RuleViolation> {
+        let rules = self.character_rules();
+        let level = self.snapshot.level;
+        let mut out = Vec::new();
+        let mut violation = |rule, index, message: String| {
+            out.push(RuleViolation {
+                rule,
+                index,
+                message,
+            })
+        };
+
+        if !(1..=rules.level_max).contains(&level) {
+            violation(
+                CharacterRule::Level,
+                None,
+                format!("level {level} is outside 1..={}", rules.level_max),
+            );
+        } else if self.snapshot.experience < experience_for_level(level) {
+            violation(
+                CharacterRule::Level,
+                None,
+                format!(
+                    "{} experience is short of the {} level {level} needs",
+                    self.snapshot.experience,
+                    experience_for_level(level)
+                ),
+            );
+        }
+
+        for (index, &value) in rules.special.iter().enumerate() {
+            if !(SPECIAL_MIN..=SPECIAL_MAX).contains(&value) {
+                violation(
+                    CharacterRule::Special,
+                    Some(index),
+                    format!(
+                        "{} is {value} after traits, expected {SPECIAL_MIN}..={SPECIAL_MAX}",
+                        rules.stat_names[index]
+                    ),
+                );
+            }
+        }
+
+        let mut tagged = Vec::new();
+        for &raw in rules.tagged_skills {
+            let Ok(index) = usize::try_from(raw) else {
+                continue;
+            };
+            if index >= rules.skill_names.len() {
+                violation(
+                    CharacterRule::TaggedSkills,
+                    Some(index),
+                    format!("tagged skill {index} does not exist"),
+                );
+            } else if tagged.contains(&index) {
+                violation(
+                    CharacterRule::TaggedSkills,
+                    Some(index),
+                    format!("{} is tagged twice", rules.skill_names[index]),
+                );
+            } else {
+                tagged.push(index);
+            }
+        }
+        let tag_limit = TAGGED_SKILL_LIMIT + usize::from(rules.perks[rules.perk_tag] > 0);
+        if tagged.len() > tag_limit {
+            violation(
+                CharacterRule::TaggedSkills,
+                None,
+                format!(
+                    "{} skills are tagged, at most {tag_limit} allowed",
+                    tagged.len()
+                ),
+            );
+        }
+
+        for (slot, &value) in rules.traits.iter().enumerate() {
+            if value == -1 {
+                continue;
+            }
+            match usize::try_from(value) {
+                Ok(index) if index < rules.trait_names.len() => {
+                    if rules.traits[..slot].contains(&value) {
+                        violation(
+                            CharacterRule::Traits,
+                            Some(index),
+                            format!("{} is selected twice", rules.trait_names[index]),
+                        );
+                    }
+                }
+                _ => violation(
+                    CharacterRule::Traits,
+                    None,
+                    format!("trait slot {slot} holds unknown trait {value}"),
+                ),
+            }
+        }
+
+        let skill_totals: Vec<i32> = self.skills().iter().map(|skill| skill.total).collect();
+        let mut selected_ranks = 0;
+        for (index, &rank) in rules.perks.iter().enumerate() {
+            let info = &rules.perk_info[index];
+            if rank <= 0 || info.kind != PerkKind::Selectable {
+                continue;
+            }
+            selected_ranks += rank;
+            let name = rules.perk_names[index];
+            if rank > info.max_rank {
+                violation(
+                    CharacterRule::Perks,
+                    Some(index),
+                    format!("{name} has rank {rank}, at most {} allowed", info.max_rank),
+                );
+            }
+            if level < info.min_level {
+                violation(
+                    CharacterRule::Perks,
+                    Some(index),
+                    format!("{name} needs level {}", info.min_level),
+                );
+            }
+            for (stat, &value) in rules.special_totals.iter().enumerate() {
+                if !info.special_allows(stat, value) {
+                    violation(
+                        CharacterRule::Perks,
+                        Some(index),
+                        format!("{name} does not allow {} {value}", rules.stat_names[stat]),
+                    );
+                }
+            }
+            if !info.skills_allow(&skill_totals) {
+                violation(
+                    CharacterRule::Perks,
+                    Some(index),
+                    format!("{name} skill requirements are not met"),
+                );
+            }
+        }
+
+        let perk_picks = level.max(0) / if rules.skilled { 4 } else { 3 };
+        if selected_ranks > perk_picks {
+            violation(
+                CharacterRule::Perks,
+                None,
+                format!("{selected_ranks} perk ranks taken, level {level} earns {perk_picks}"),
+            );
+        }
+
+        let per_level = (self.skill_points_per_level(rules.educated, rules.skilled)
+            - if rules.gifted { 5 } else { 0 })
+        .max(0);
+        let earnable = ((level - 1).max(0) * per_level).min(UNSPENT_SKILL_POINTS_MAX);
+        let unspent = self.snapshot.unspent_skill_points;
+        if unspent < 0 || unspent > earnable {
+            violation(
+                CharacterRule::SkillPoints,
+                None,
+                format!("{unspent} unspent skill points, level {level} allows 0..={earnable}"),
+            );
+        }
+
+        out
+    }
+
+    fn character_rules(&self) -> CharacterRules<'_> {
+        match &self.document {
+            LoadedDocument::Fallout1(doc) => {
+                let save = &doc.save;
+                CharacterRules {
+                    level_max: f1_types::PC_LEVEL_MAX,
+                    special: std::array::from_fn(|stat| save.base_stat_with_traits(stat)),
+                    special_totals: std::array::from_fn(|stat| save.special_total(stat)),
+                    stat_names: &f1_types::STAT_NAMES,
+                    skill_names: &f1_types::SKILL_NAMES,
+                    tagged_skills: &save.tagged_skills,
+                    traits: save.selected_traits,
+                    trait_names: &f1_types::TRAIT_NAMES,
+                    perks: &save.perks,
+                    perk_names: &f1_types::PERK_NAMES,
+                    perk_info: &f1_types::PERK_INFO,
+                    perk_tag: f1_types::PERK_TAG,
+                    educated: save.perks[f1_types::PERK_EDUCATED],
+                    skilled: save.selected_traits.contains(&f1_types::TRAIT_SKILLED),
+                    gifted: save.selected_traits.contains(&f1_types::TRAIT_GIFTED),
+                }
+            }
+            LoadedDocument::Fallout2(doc) => {
+                let save = &doc.save;
+                CharacterRules {
+                    level_max: f2_types::PC_LEVEL_MAX,
+                    special: std::array::from_fn(|stat| save.base_stat_with_traits(stat)),
+                    special_totals: std::array::from_fn(|stat| save.special_total(stat)),
+                    stat_names: &f2_types::STAT_NAMES,
+                    skill_names: &f2_types::SKILL_NAMES,
+                    tagged_skills: &save.tagged_skills,
+                    traits: save.selected_traits,
+                    trait_names: &f2_types::TRAIT_NAMES,
+                    perks: &save.perks,
+                    perk_names: &f2_types::PERK_NAMES,
+                    perk_info: &f2_types::PERK_INFO,
+                    perk_tag: f2_types::PERK_TAG,
+                    educated: save.perks[f2_types::PERK_EDUCATED],
+                    skilled: save.selected_traits.contains(&f2_types::TRAIT_SKILLED),
+                    gifted: save.selected_traits.contains(&f2_types::TRAIT_GIFTED),
+                }
+            }
+        }
+    }
+
+    /// Skill points a level-up grants before the Gifted penalty.
+    fn skill_points_per_level(&self, educated: i32, skilled: bool) -> i32 {
+        5 + 2 * self.stat(STAT_INTELLIGENCE).total + 2 * educated + if skilled { 5 } else { 0 }
+    }
+
+    pub fn set_skill_points(&mut self, skill_points: i32) -> Result<(), CoreError> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.set_skill_points(skill_points),
+            LoadedDocument::Fallout2(doc) => doc.set_skill_points(skill_points),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to set skill points: {e}"),
+            )
+        })?;
+
+        self.snapshot.unspent_skill_points = skill_points;
+        Ok(())
+    }
+
+    pub fn set_skill_base_value(&mut self, skill_index: usize, raw: i32) -> Result<(), CoreError> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.set_skill_base_value(skill_index, raw),
+            LoadedDocument::Fallout2(doc) => doc.set_skill_base_value(skill_index, raw),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to set skill {skill_index} raw value: {e}"),
+            )
+        })
+    }
+
+    pub fn set_reputation(&mut self, reputation: i32) -> Result<(), CoreError> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.set_reputation(reputation),
+            LoadedDocument::Fallout2(doc) => doc.set_reputation(reputation),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to set reputation: {e}"),
+            )
+        })?;
+
+        self.snapshot.reputation = reputation;
+        Ok(())
+    }
+
+    pub fn set_karma(&mut self, karma: i32) -> Result<(), CoreError> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.set_karma(karma),
+            LoadedDocument::Fallout2(doc) => doc.set_karma(karma),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
                 format!("failed to set karma: {e}"),
             )
         })?;
@@ -809,6 +1575,120 @@ impl Session {
         Ok(())
     }
 
+    pub fn set_global_var(&mut self, index: usize, value: i32) -> Result<(), CoreError> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.set_global_var(index, value),
+            LoadedDocument::Fallout2(doc) => doc.set_global_var(index, value),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to set global var {index}: {e}"),
+            )
+        })
+    }
+
+    pub fn remove_queue_event(&mut self, index: usize) -> Result<QueueEvent, CoreError> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.remove_queue_event(index),
+            LoadedDocument::Fallout2(doc) => doc.remove_queue_event(index),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to remove queue event {index}: {e}"),
+            )
+        })
+    }
+
+    pub fn set_queue_event_time(&mut self, index: usize, time: u32) -> Result<(), CoreError> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.set_queue_event_time(index, time),
+            LoadedDocument::Fallout2(doc) => doc.set_queue_event_time(index, time),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to set queue event {index} time: {e}"),
+            )
+        })
+    }
+
+    /// Set the game clock, in ticks (tenths of a second) since the game
+    /// started. The header's game date follows, and pending queue events
+    /// move by the same amount as the clock; a save whose queue was not
+    /// decoded refuses the edit.
+    pub fn set_game_time(&mut self, game_time: u32) -> Result<(), CoreError> {
+        let game_date = match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.set_game_time(game_time).map(|()| {
+                let header = &doc.save.header;
+                (header.game_month, header.game_day, header.game_year)
+            }),
+            LoadedDocument::Fallout2(doc) => doc.set_game_time(game_time).map(|()| {
+                let header = &doc.save.header;
+                (header.game_month, header.game_day, header.game_year)
+            }),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to set game time: {e}"),
+            )
+        })?;
+
+        let (month, day, year) = game_date;
+        self.snapshot.game_date = DateParts { day, month, year };
+        self.snapshot.game_time = game_time;
+        Ok(())
+    }
+
+    /// Move the game clock forward by `duration` ticks; see
+    /// [`Session::set_game_time`].
+    pub fn advance_game_time(&mut self, duration: u32) -> Result<(), CoreError> {
+        let game_time = self
+            .snapshot
+            .game_time
+            .checked_add(duration)
+            .ok_or_else(|| {
+                CoreError::new(
+                    CoreErrorCode::UnsupportedOperation,
+                    format!(
+                        "cannot advance game time {} by {duration} ticks without overflow",
+                        self.snapshot.game_time
+                    ),
+                )
+            })?;
+        self.set_game_time(game_time)
+    }
+
+    pub fn set_kill_count(&mut self, kill_type: usize, count: i32) -> Result<(), CoreError> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.set_kill_count(kill_type, count),
+            LoadedDocument::Fallout2(doc) => doc.set_kill_count(kill_type, count),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to set kill count {kill_type}: {e}"),
+            )
+        })
+    }
+
+    /// Replace the tagged skills, in tag order. Skill totals follow the new
+    /// tags immediately.
+    pub fn set_tagged_skills(&mut self, skills: &[usize]) -> Result<(), CoreError> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.set_tagged_skills(skills),
+            LoadedDocument::Fallout2(doc) => doc.set_tagged_skills(skills),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to set tagged skills: {e}"),
+            )
+        })
+    }
+
     pub fn set_perk_rank(&mut self, perk_index: usize, rank: i32) -> Result<(), CoreError> {
         match &mut self.document {
             LoadedDocument::Fallout1(doc) => doc.set_perk_rank(perk_index, rank),
@@ -822,6 +1702,131 @@ impl Session {
         })
     }
 
+    /// Like [`Session::set_perk_rank`], but refuses ranks above the perk's
+    /// maximum.
+    pub fn set_perk_rank_within_max(
+        &mut self,
+        perk_index: usize,
+        rank: i32,
+    ) -> Result<(), CoreError> {
+        if let Some(info) = self.perk_info(perk_index)
+            && rank > info.max_rank
+        {
+            return Err(CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!(
+                    "perk {perk_index} rank {rank} is above its maximum of {}",
+                    info.max_rank
+                ),
+            ));
+        }
+        self.set_perk_rank(perk_index, rank)
+    }
+
+    /// Clear an addiction perk and drop the pending withdrawal events for it
+    /// and the player's pending drug-effect events for its drug, returning
+    /// how many events were removed. The drug comes from the withdrawal
+    /// events and its effect stages from its prototype in `catalog`. The stat
+    /// changes the dropped effects were still due to make are applied now, so
+    /// the drug wears off instead of staying in effect. Errors without a
+    /// decoded event queue.
+    pub fn cure_addiction(
+        &mut self,
+        perk_index: usize,
+        catalog: &ItemCatalog,
+    ) -> Result<usize, CoreError> {
+        if self.perk_info(perk_index).map(|info| info.kind) != Some(PerkKind::Addiction) {
+            return Err(CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("perk {perk_index} is not an addiction"),
+            ));
+        }
+        let is_withdrawal = |event: &QueueEvent| {
+            matches!(event.data, QueueEventData::Withdrawal { perk, .. }
+                if usize::try_from(perk) == Ok(perk_index))
+        };
+        let mut drugs: Vec<&DrugPrototype> = Vec::new();
+        for event in self
+            .queue_events()?
+            .iter()
+            .filter(|event| is_withdrawal(event))
+        {
+            let QueueEventData::Withdrawal { item_pid, .. } = event.data else {
+                continue;
+            };
+            if drugs.iter().any(|drug| drug.pid == item_pid) {
+                continue;
+            }
+            drugs.push(catalog.drug(item_pid).ok_or_else(|| {
+                CoreError::new(
+                    CoreErrorCode::UnsupportedOperation,
+                    format!("no drug prototype for pid={item_pid}; cannot find its effects"),
+                )
+            })?);
+        }
+
+        let player_id = match &self.document {
+            LoadedDocument::Fallout1(doc) => doc.save.player_object.id,
+            LoadedDocument::Fallout2(doc) => doc.save.player_object.id,
+        };
+        let cured = |event: &QueueEvent| match &event.data {
+            QueueEventData::Drug { stats, modifiers } => {
+                event.object_id == player_id
+                    && drugs
+                        .iter()
+                        .any(|drug| drug.queued_effect(stats, modifiers))
+            }
+            _ => is_withdrawal(event),
+        };
+        let map_err = |e: std::io::Error| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to cure addiction {perk_index}: {e}"),
+            )
+        };
+        let removed = match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc
+                .clear_perk(perk_index)
+                .and_then(|()| doc.remove_queue_events_where(cured)),
+            LoadedDocument::Fallout2(doc) => doc
+                .clear_perk(perk_index)
+                .and_then(|()| doc.remove_queue_events_where(cured)),
+        }
+        .map_err(map_err)?;
+
+        for event in &removed {
+            let QueueEventData::Drug { stats, modifiers } = event.data else {
+                continue;
+            };
+            for (stat, modifier) in stats.into_iter().zip(modifiers) {
+                if let Ok(stat_index) = usize::try_from(stat) {
+                    self.add_to_bonus_stat(stat_index, modifier)
+                        .map_err(map_err)?;
+                }
+            }
+        }
+        Ok(removed.len())
+    }
+
+    /// Add `delta` to the bonus of `stat_index`. Stats without a bonus slot,
+    /// such as current hit points, are left as they are.
+    fn add_to_bonus_stat(&mut self, stat_index: usize, delta: i32) -> std::io::Result<()> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => {
+                match doc.save.critter_data.bonus_stats.get(stat_index) {
+                    Some(&bonus) => doc.set_bonus_stat(stat_index, bonus + delta),
+                    None => Ok(()),
+                }
+            }
+            LoadedDocument::Fallout2(doc) => {
+                match doc.save.critter_data.bonus_stats.get(stat_index) {
+                    Some(&bonus) => doc.set_bonus_stat(stat_index, bonus + delta),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+
     pub fn clear_perk(&mut self, perk_index: usize) -> Result<(), CoreError> {
         match &mut self.document {
             LoadedDocument::Fallout1(doc) => doc.clear_perk(perk_index),
@@ -848,6 +1853,10 @@ impl Session {
         })
     }
 
+    /// Add `quantity` of an item the player already carries. A PID the
+    /// player has no stack of fails: a new object needs the fid, flags and
+    /// subtype data from its prototype, which only
+    /// [`Session::add_inventory_item_with_catalog`] has.
     pub fn add_inventory_item(&mut self, pid: i32, quantity: i32) -> Result<(), CoreError> {
         match &mut self.document {
             LoadedDocument::Fallout1(doc) => doc.add_inventory_item(pid, quantity),
@@ -861,6 +1870,40 @@ impl Session {
         })
     }
 
+    /// Like [`Session::add_inventory_item`], but items the player does not
+    /// carry yet are created from their prototype in `catalog`.
+    ///
+    /// Object ids are shared with every map save in the slot, which this
+    /// session cannot see, so the new object's id is placed above `id_floor`
+    /// as well as the player's own ids. Pass the highest id used on the maps
+    /// ([`SlotSession::max_map_object_id`](super::SlotSession::max_map_object_id)),
+    /// or use [`SlotSession::add_inventory_item_with_catalog`](super::SlotSession::add_inventory_item_with_catalog).
+    pub fn add_inventory_item_with_catalog(
+        &mut self,
+        pid: i32,
+        quantity: i32,
+        catalog: &ItemCatalog,
+        id_floor: i32,
+    ) -> Result<(), CoreError> {
+        let Some(proto) = catalog.prototype(pid) else {
+            return self.add_inventory_item(pid, quantity);
+        };
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => {
+                doc.add_inventory_item_from_prototype(proto, quantity, id_floor)
+            }
+            LoadedDocument::Fallout2(doc) => {
+                doc.add_inventory_item_from_prototype(proto, quantity, id_floor)
+            }
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to add inventory item pid={pid}: {e}"),
+            )
+        })
+    }
+
     pub fn remove_inventory_item(
         &mut self,
         pid: i32,
@@ -878,6 +1921,253 @@ impl Session {
         })
     }
 
+    pub fn equipped(&self) -> EquippedItems {
+        let items = match &self.document {
+            LoadedDocument::Fallout1(doc) => &doc.save.player_object.inventory,
+            LoadedDocument::Fallout2(doc) => &doc.save.player_object.inventory,
+        };
+        let slot_pid = |slot| {
+            object::equipped_inventory_item(items, slot_flag(slot)).map(|item| item.object.pid)
+        };
+        EquippedItems {
+            armor: slot_pid(EquipmentSlot::Armor),
+            left_hand: slot_pid(EquipmentSlot::LeftHand),
+            right_hand: slot_pid(EquipmentSlot::RightHand),
+        }
+    }
+
+    /// Equip one carried `pid` in a hand slot, replacing what was there.
+    ///
+    /// Armor changes the player's stats, so the armor slot needs item
+    /// prototypes; use [`Session::equip_item_with_catalog`] for it.
+    pub fn equip_item(&mut self, slot: EquipmentSlot, pid: i32) -> Result<(), CoreError> {
+        require_hand_slot(slot)?;
+        self.set_equipped_item(slot, pid)
+    }
+
+    fn set_equipped_item(&mut self, slot: EquipmentSlot, pid: i32) -> Result<(), CoreError> {
+        let flag = slot_flag(slot);
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.equip_inventory_item(flag, pid),
+            LoadedDocument::Fallout2(doc) => doc.equip_inventory_item(flag, pid),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to equip pid={pid} in {slot:?} slot: {e}"),
+            )
+        })
+    }
+
+    /// Empty a hand slot, returning the PID of the item that was in it. The
+    /// armor slot needs [`Session::unequip_with_catalog`].
+    pub fn unequip(&mut self, slot: EquipmentSlot) -> Result<Option<i32>, CoreError> {
+        require_hand_slot(slot)?;
+        self.clear_equipped_slot(slot)
+    }
+
+    fn clear_equipped_slot(&mut self, slot: EquipmentSlot) -> Result<Option<i32>, CoreError> {
+        let flag = slot_flag(slot);
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.unequip_inventory_item(flag),
+            LoadedDocument::Fallout2(doc) => doc.unequip_inventory_item(flag),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to unequip {slot:?} slot: {e}"),
+            )
+        })
+    }
+
+    /// Load `rounds` of `ammo_pid` into the player's stacks of weapon `pid`.
+    /// An `ammo_pid` of -1 with zero rounds empties the weapon. The magazine
+    /// size needs the item prototypes; use
+    /// [`Session::set_weapon_ammo_with_catalog`] to check it.
+    pub fn set_weapon_ammo(
+        &mut self,
+        pid: i32,
+        ammo_pid: i32,
+        rounds: i32,
+    ) -> Result<(), CoreError> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.set_weapon_ammo(pid, ammo_pid, rounds),
+            LoadedDocument::Fallout2(doc) => doc.set_weapon_ammo(pid, ammo_pid, rounds),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to set ammo for weapon pid={pid}: {e}"),
+            )
+        })
+    }
+
+    /// Like [`Session::set_weapon_ammo`], but also holds `rounds` to the
+    /// weapon's magazine size from its prototype in `catalog`.
+    pub fn set_weapon_ammo_with_catalog(
+        &mut self,
+        pid: i32,
+        ammo_pid: i32,
+        rounds: i32,
+        catalog: &ItemCatalog,
+    ) -> Result<(), CoreError> {
+        let capacity = catalog.weapon_ammo_capacity(pid).ok_or_else(|| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("no weapon prototype for pid={pid}; cannot check its magazine size"),
+            )
+        })?;
+        if rounds > capacity {
+            return Err(CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("weapon pid={pid} holds {capacity} rounds, cannot load {rounds}"),
+            ));
+        }
+        self.set_weapon_ammo(pid, ammo_pid, rounds)
+    }
+
+    /// Like [`Session::equip_item`], but also takes the armor slot. Armor
+    /// changes move armor class, damage threshold/resistance and the armor
+    /// perk's effect from the old armor to the new, as the engine does when
+    /// armor is put on.
+    pub fn equip_item_with_catalog(
+        &mut self,
+        slot: EquipmentSlot,
+        pid: i32,
+        catalog: &ItemCatalog,
+    ) -> Result<(), CoreError> {
+        if slot != EquipmentSlot::Armor {
+            return self.equip_item(slot, pid);
+        }
+        let new_armor = catalog_armor(catalog, pid)?;
+        let old_armor = self
+            .equipped()
+            .armor
+            .map(|old| catalog_armor(catalog, old))
+            .transpose()?;
+        self.set_equipped_item(slot, pid)?;
+        self.adjust_armor_bonus_stats(old_armor, Some(new_armor))
+    }
+
+    pub fn unequip_with_catalog(
+        &mut self,
+        slot: EquipmentSlot,
+        catalog: &ItemCatalog,
+    ) -> Result<Option<i32>, CoreError> {
+        if slot != EquipmentSlot::Armor {
+            return self.unequip(slot);
+        }
+        let old_armor = self
+            .equipped()
+            .armor
+            .map(|old| catalog_armor(catalog, old))
+            .transpose()?;
+        let removed = self.clear_equipped_slot(slot)?;
+        self.adjust_armor_bonus_stats(old_armor, None)?;
+        Ok(removed)
+    }
+
+    fn adjust_armor_bonus_stats(
+        &mut self,
+        old: Option<&ArmorPrototype>,
+        new: Option<&ArmorPrototype>,
+    ) -> Result<(), CoreError> {
+        let protection = |armor: Option<&ArmorPrototype>| {
+            let mut values = [0; ARMOR_STAT_COUNT];
+            if let Some(armor) = armor {
+                values[0] = armor.armor_class;
+                values[1..=DAMAGE_TYPE_COUNT].copy_from_slice(&armor.damage_threshold);
+                values[DAMAGE_TYPE_COUNT + 1..].copy_from_slice(&armor.damage_resistance);
+            }
+            values
+        };
+        let (old_values, new_values) = (protection(old), protection(new));
+        let stat_indices = std::iter::once(STAT_ARMOR_CLASS)
+            .chain(STAT_DAMAGE_THRESHOLD_FIRST..STAT_DAMAGE_THRESHOLD_FIRST + DAMAGE_TYPE_COUNT)
+            .chain(STAT_DAMAGE_RESISTANCE_FIRST..STAT_DAMAGE_RESISTANCE_FIRST + DAMAGE_TYPE_COUNT);
+        let mut deltas: Vec<(usize, i32)> = stat_indices
+            .enumerate()
+            .map(|(i, stat_index)| (stat_index, new_values[i] - old_values[i]))
+            .collect();
+        // The engine removes the old armor's perk effect and adds the new one.
+        for (armor, sign) in [(old, -1), (new, 1)] {
+            let Some(effect) = armor.and_then(|armor| self.armor_perk_effect(armor.perk)) else {
+                continue;
+            };
+            deltas.push((effect.stat, sign * effect.modifier));
+            deltas.extend(
+                effect
+                    .special
+                    .iter()
+                    .enumerate()
+                    .map(|(stat_index, &value)| (stat_index, sign * value)),
+            );
+        }
+
+        for (stat_index, delta) in deltas {
+            if delta == 0 {
+                continue;
+            }
+            match &mut self.document {
+                LoadedDocument::Fallout1(doc) => {
+                    let value = doc.save.critter_data.bonus_stats[stat_index] + delta;
+                    doc.set_bonus_stat(stat_index, value)
+                }
+                LoadedDocument::Fallout2(doc) => {
+                    let value = doc.save.critter_data.bonus_stats[stat_index] + delta;
+                    doc.set_bonus_stat(stat_index, value)
+                }
+            }
+            .map_err(|e| {
+                CoreError::new(
+                    CoreErrorCode::UnsupportedOperation,
+                    format!("failed to update armor bonus for stat {stat_index}: {e}"),
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    fn armor_perk_effect(&self, perk: i32) -> Option<&'static PerkEffect> {
+        let effects: &'static [PerkEffect] = match self.game {
+            Game::Fallout1 => &f1_types::ARMOR_PERK_EFFECTS,
+            Game::Fallout2 => &f2_types::ARMOR_PERK_EFFECTS,
+        };
+        let perk = usize::try_from(perk).ok()?;
+        effects.iter().find(|effect| effect.perk == perk)
+    }
+
+    pub(crate) fn take_inventory_object(
+        &mut self,
+        pid: i32,
+        quantity: Option<i32>,
+    ) -> Result<InventoryItem, CoreError> {
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.take_inventory_object(pid, quantity),
+            LoadedDocument::Fallout2(doc) => doc.take_inventory_object(pid, quantity),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to take inventory item pid={pid}: {e}"),
+            )
+        })
+    }
+
+    pub(crate) fn insert_inventory_object(&mut self, item: InventoryItem) -> Result<(), CoreError> {
+        let pid = item.object.pid;
+        match &mut self.document {
+            LoadedDocument::Fallout1(doc) => doc.insert_inventory_object(item),
+            LoadedDocument::Fallout2(doc) => doc.insert_inventory_object(item),
+        }
+        .map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("failed to add inventory item pid={pid}: {e}"),
+            )
+        })
+    }
+
     fn sync_snapshot_selected_traits(&mut self) {
         self.snapshot.selected_traits = match &self.document {
             LoadedDocument::Fallout1(doc) => doc.save.selected_traits,
@@ -896,6 +2186,35 @@ impl Session {
         Ok(())
     }
 
+    /// Exports list only non-zero kill counts, so kill types missing from
+    /// `kill_counts` are reset to zero.
+    fn apply_kill_counts_from_export(
+        &mut self,
+        kill_counts: &[KillCountEntry],
+    ) -> Result<(), CoreError> {
+        let current = self.all_kill_counts();
+        if let Some(entry) = kill_counts
+            .iter()
+            .find(|entry| entry.index >= current.len())
+        {
+            return Err(CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("invalid kill type {} in character export", entry.index),
+            ));
+        }
+
+        for entry in current {
+            let desired = kill_counts
+                .iter()
+                .find(|desired| desired.index == entry.index)
+                .map_or(0, |desired| desired.count);
+            if desired != entry.count {
+                self.set_kill_count(entry.index, desired)?;
+            }
+        }
+        Ok(())
+    }
+
     fn apply_perks_from_export(&mut self, perks: &[PerkEntry]) -> Result<(), CoreError> {
         for perk_index in 0..perk_count_for_game(self.game()) {
             self.clear_perk(perk_index)?;
@@ -1036,6 +2355,116 @@ fn session_from_fallout2(doc: fallout2::Document) -> Session {
     }
 }
 
+/// The per-game tables and save fields [`Session::validate_character`] reads.
+struct CharacterRules<'a> {
+    level_max: i32,
+    special: [i32; SPECIAL_STAT_COUNT],
+    /// SPECIAL with bonuses, as the game checks perk requirements.
+    special_totals: [i32; SPECIAL_STAT_COUNT],
+    stat_names: &'a [&'a str],
+    skill_names: &'a [&'a str],
+    tagged_skills: &'a [i32],
+    traits: [i32; TRAIT_SLOT_COUNT],
+    trait_names: &'a [&'a str],
+    perks: &'a [i32],
+    perk_names: &'a [&'a str],
+    perk_info: &'a [PerkInfo],
+    perk_tag: usize,
+    educated: i32,
+    skilled: bool,
+    gifted: bool,
+}
+
+/// Experience needed to reach `level`: 1000 per level gained, growing
+/// linearly (0, 1000, 3000, 6000, ...).
+fn experience_for_level(level: i32) -> i32 {
+    level * (level - 1) / 2 * 1000
+}
+
+fn fallout1_preferences_unsupported() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "Fallout 1 preferences are not decoded",
+    )
+}
+
+fn withdrawal_event_count(queue_events: &[QueueEvent], perk_index: usize) -> usize {
+    queue_events
+        .iter()
+        .filter(|event| {
+            matches!(event.data, QueueEventData::Withdrawal { perk, .. }
+                if usize::try_from(perk) == Ok(perk_index))
+        })
+        .count()
+}
+
+fn apply_item_extra_data(entry: &mut ResolvedInventoryEntry, item_type: i32, item: &GameObject) {
+    let ObjectData::Item(data) = &item.object_data else {
+        return;
+    };
+    match ItemExtraData::decode(item_type, &data.extra_data) {
+        Some(ItemExtraData::Weapon {
+            ammo_loaded,
+            ammo_pid,
+        }) => {
+            entry.ammo_loaded = Some(ammo_loaded);
+            entry.ammo_pid = Some(ammo_pid);
+        }
+        Some(ItemExtraData::Ammo { quantity: charges } | ItemExtraData::Misc { charges }) => {
+            entry.charges = Some(charges);
+        }
+        Some(ItemExtraData::Key { key_code }) => entry.key_code = Some(key_code),
+        None => {}
+    }
+}
+
+fn slot_flag(slot: EquipmentSlot) -> i32 {
+    match slot {
+        EquipmentSlot::Armor => OBJECT_FLAG_WORN,
+        EquipmentSlot::LeftHand => OBJECT_FLAG_LEFT_HAND,
+        EquipmentSlot::RightHand => OBJECT_FLAG_RIGHT_HAND,
+    }
+}
+
+fn file_stem(name: &str) -> &str {
+    name.rsplit_once('.').map_or(name, |(stem, _)| stem)
+}
+
+fn require_hand_slot(slot: EquipmentSlot) -> Result<(), CoreError> {
+    if slot == EquipmentSlot::Armor {
+        return Err(CoreError::new(
+            CoreErrorCode::UnsupportedOperation,
+            "armor changes need item prototypes to update armor stats",
+        ));
+    }
+    Ok(())
+}
+
+fn catalog_armor(catalog: &ItemCatalog, pid: i32) -> Result<&ArmorPrototype, CoreError> {
+    match (catalog.get(pid), catalog.armor(pid)) {
+        (Some(entry), Some(armor)) if entry.item_type == ITEM_TYPE_ARMOR => Ok(armor),
+        (Some(_), _) => Err(CoreError::new(
+            CoreErrorCode::UnsupportedOperation,
+            format!("pid={pid} is not armor"),
+        )),
+        (None, _) => Err(CoreError::new(
+            CoreErrorCode::UnsupportedOperation,
+            format!("no item prototype for pid={pid}; cannot compute armor stats"),
+        )),
+    }
+}
+
+fn player_critter_data(document: &LoadedDocument) -> Option<&crate::object::CritterObjectData> {
+    let player = match document {
+        LoadedDocument::Fallout1(doc) => &doc.save.player_object,
+        LoadedDocument::Fallout2(doc) => &doc.save.player_object,
+    };
+    match &player.object_data {
+        crate::object::ObjectData::Critter(data) => Some(data),
+        _ => None,
+    }
+}
+
 fn extract_hp(obj: &crate::object::GameObject) -> Option<i32> {
     match &obj.object_data {
         crate::object::ObjectData::Critter(data) => Some(data.hp),
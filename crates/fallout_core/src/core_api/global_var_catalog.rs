@@ -0,0 +1,170 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::error::{CoreError, CoreErrorCode};
+use super::item_catalog::{DatArchive, find_master_dat_path, resolve_case_insensitive_path};
+
+const VAULT13_GAM_ARCHIVE_PATH: &str = "data/vault13.gam";
+const GAME_GLOBAL_VARS_MARKER: &str = "GAME_GLOBAL_VARS:";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobalVarCatalog {
+    install_dir: PathBuf,
+    names: Vec<String>,
+}
+
+impl GlobalVarCatalog {
+    pub fn load_from_install_dir(install_dir: &Path) -> Result<Self, CoreError> {
+        let bytes = match find_vault13_gam_path(install_dir) {
+            Some(path) => fs::read(&path).map_err(|e| {
+                CoreError::new(
+                    CoreErrorCode::Io,
+                    format!("failed to read {}: {e}", path.display()),
+                )
+            })?,
+            None => {
+                let dat = find_master_dat_path(install_dir)
+                    .and_then(|path| DatArchive::open(&path).ok())
+                    .ok_or_else(|| {
+                        CoreError::new(
+                            CoreErrorCode::Io,
+                            format!(
+                                "could not find data/vault13.gam or a readable master.dat under {}",
+                                install_dir.display()
+                            ),
+                        )
+                    })?;
+                dat.read_file(VAULT13_GAM_ARCHIVE_PATH).map_err(|e| {
+                    CoreError::new(
+                        CoreErrorCode::Io,
+                        format!(
+                            "failed to load {VAULT13_GAM_ARCHIVE_PATH} from {}: {e}",
+                            dat.path().display()
+                        ),
+                    )
+                })?
+            }
+        };
+
+        let names = parse_gam_global_names(&String::from_utf8_lossy(&bytes));
+        if names.is_empty() {
+            return Err(CoreError::new(
+                CoreErrorCode::Parse,
+                format!(
+                    "no global variable names could be parsed from install dir {}",
+                    install_dir.display()
+                ),
+            ));
+        }
+
+        Ok(Self {
+            install_dir: install_dir.to_path_buf(),
+            names,
+        })
+    }
+
+    pub fn install_dir(&self) -> &Path {
+        &self.install_dir
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.names.get(index).map(String::as_str)
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.names
+            .iter()
+            .position(|entry| entry.eq_ignore_ascii_case(name))
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+fn find_vault13_gam_path(install_dir: &Path) -> Option<PathBuf> {
+    [
+        ["data", "vault13.gam"].as_slice(),
+        ["vault13.gam"].as_slice(),
+    ]
+    .iter()
+    .find_map(|parts| resolve_case_insensitive_path(install_dir, parts))
+    .filter(|path| path.is_file())
+}
+
+/// Collect variable names in declaration order; the save stores values by that order.
+///
+/// Lines look like `GVAR_PLAYER_REPUTATION :=0; // (0)`. When the file has a
+/// `GAME_GLOBAL_VARS:` marker, only declarations after it are counted.
+fn parse_gam_global_names(contents: &str) -> Vec<String> {
+    let has_marker = contents
+        .lines()
+        .any(|line| strip_gam_comment(line).trim() == GAME_GLOBAL_VARS_MARKER);
+    let mut in_globals = !has_marker;
+    let mut names = Vec::new();
+
+    for line in contents.lines() {
+        let line = strip_gam_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.ends_with(':') && !line.contains(":=") {
+            in_globals = line == GAME_GLOBAL_VARS_MARKER;
+            continue;
+        }
+        if !in_globals {
+            continue;
+        }
+        let Some((name, _value)) = line.split_once(":=") else {
+            continue;
+        };
+        let name = name.trim();
+        if !name.is_empty() {
+            names.push(name.to_string());
+        }
+    }
+
+    names
+}
+
+fn strip_gam_comment(line: &str) -> &str {
+    line.split_once("//").map_or(line, |(code, _)| code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_gam_global_names;
+
+    #[test]
+    fn parses_names_after_game_global_vars_marker() {
+        let contents = "\
+// Fallout global variables
+MAP_GLOBAL_VARS:
+MVAR_IGNORED :=1;
+
+GAME_GLOBAL_VARS:
+GVAR_PLAYER_REPUTATION      :=0;    //  (0)
+// GVAR_COMMENTED_OUT := 5;
+GVAR_CHILDREN_KILLED :=0; // (1)
+GVAR_BAD_MONSTER:=0;
+";
+        assert_eq!(
+            parse_gam_global_names(contents),
+            [
+                "GVAR_PLAYER_REPUTATION",
+                "GVAR_CHILDREN_KILLED",
+                "GVAR_BAD_MONSTER"
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_all_declarations_without_marker() {
+        let contents = "GVAR_A :=0;\r\nGVAR_B :=3;\r\n";
+        assert_eq!(parse_gam_global_names(contents), ["GVAR_A", "GVAR_B"]);
+    }
+}
@@ -6,21 +6,44 @@ use std::path::{Path, PathBuf};
 
 use flate2::read::ZlibDecoder;
 
-use crate::object::{OBJ_TYPE_ITEM, obj_type_from_pid};
+use crate::object::{
+    ArmorPrototype, DAMAGE_TYPE_COUNT, DrugPrototype, ITEM_TYPE_AMMO, ITEM_TYPE_ARMOR,
+    ITEM_TYPE_DRUG, ITEM_TYPE_KEY, ITEM_TYPE_MISC, ITEM_TYPE_WEAPON, ItemExtraData, ItemPrototype,
+    OBJ_TYPE_ITEM, obj_type_from_pid,
+};
 
 use super::error::{CoreError, CoreErrorCode};
 use super::types::ItemCatalogEntry;
 
 const PRO_ITEM_PID_OFFSET: usize = 0x00;
 const PRO_ITEM_MESSAGE_ID_OFFSET: usize = 0x04;
+const PRO_ITEM_FID_OFFSET: usize = 0x08;
+const PRO_ITEM_LIGHT_DISTANCE_OFFSET: usize = 0x0C;
+const PRO_ITEM_LIGHT_INTENSITY_OFFSET: usize = 0x10;
+const PRO_ITEM_FLAGS_OFFSET: usize = 0x14;
 const PRO_ITEM_TYPE_OFFSET: usize = 0x20;
 const PRO_ITEM_WEIGHT_OFFSET: usize = 0x2C;
+const PRO_WEAPON_AMMO_PID_OFFSET: usize = 0x71;
+const PRO_WEAPON_AMMO_CAPACITY_OFFSET: usize = 0x75;
+const PRO_AMMO_QUANTITY_OFFSET: usize = 0x3D;
+const PRO_MISC_CHARGES_OFFSET: usize = 0x41;
+const PRO_KEY_CODE_OFFSET: usize = 0x39;
+const PRO_ARMOR_CLASS_OFFSET: usize = 0x39;
+const PRO_ARMOR_DAMAGE_RESISTANCE_OFFSET: usize = 0x3D;
+const PRO_ARMOR_DAMAGE_THRESHOLD_OFFSET: usize = 0x59;
+const PRO_ARMOR_PERK_OFFSET: usize = 0x75;
+const PRO_DRUG_STAT_OFFSET: usize = 0x39;
+/// Immediate amounts; the delayed ones follow, each after its 4-byte delay.
+const PRO_DRUG_AMOUNT_OFFSETS: [usize; 3] = [0x45, 0x55, 0x65];
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ItemCatalog {
     install_dir: PathBuf,
     language: String,
     entries: BTreeMap<i32, ItemCatalogEntry>,
+    prototypes: BTreeMap<i32, ItemPrototype>,
+    armor: BTreeMap<i32, ArmorPrototype>,
+    drugs: BTreeMap<i32, DrugPrototype>,
 }
 
 impl ItemCatalog {
@@ -92,6 +115,9 @@ impl ItemCatalog {
         };
 
         let mut entries = BTreeMap::new();
+        let mut prototypes = BTreeMap::new();
+        let mut armor = BTreeMap::new();
+        let mut drugs = BTreeMap::new();
         for (index, relative_path) in item_paths.iter().enumerate() {
             let normalized = relative_path.replace('\\', "/");
             let bytes = if let Some(items_dir) = items_fs_base.as_ref() {
@@ -124,6 +150,19 @@ impl ItemCatalog {
                 .cloned()
                 .unwrap_or_else(|| format!("pid={pid:08X}"));
 
+            if let Some(prototype) = parse_item_prototype(index, &bytes) {
+                prototypes.insert(pid, prototype);
+            }
+            if item_type == ITEM_TYPE_ARMOR
+                && let Some(prototype) = parse_armor_prototype(index, &bytes)
+            {
+                armor.insert(pid, prototype);
+            }
+            if item_type == ITEM_TYPE_DRUG
+                && let Some(prototype) = parse_drug_prototype(index, &bytes)
+            {
+                drugs.insert(pid, prototype);
+            }
             entries.insert(
                 pid,
                 ItemCatalogEntry {
@@ -149,6 +188,9 @@ impl ItemCatalog {
             install_dir: install_dir.to_path_buf(),
             language,
             entries,
+            prototypes,
+            armor,
+            drugs,
         })
     }
 
@@ -164,6 +206,31 @@ impl ItemCatalog {
         self.entries.get(&pid)
     }
 
+    /// Prototype data for creating a new instance of `pid`, if its .PRO was readable.
+    pub fn prototype(&self, pid: i32) -> Option<&ItemPrototype> {
+        self.prototypes.get(&pid)
+    }
+
+    /// Armor class and damage protection of an armor `pid`.
+    pub fn armor(&self, pid: i32) -> Option<&ArmorPrototype> {
+        self.armor.get(&pid)
+    }
+
+    /// Stats and stage amounts of a drug `pid`.
+    pub fn drug(&self, pid: i32) -> Option<&DrugPrototype> {
+        self.drugs.get(&pid)
+    }
+
+    /// Magazine size of a weapon `pid`. New weapons start full, so this is
+    /// the loaded-rounds default of its prototype.
+    pub fn weapon_ammo_capacity(&self, pid: i32) -> Option<i32> {
+        let proto = self.prototype(pid)?;
+        match ItemExtraData::decode(proto.item_type, &proto.extra_data)? {
+            ItemExtraData::Weapon { ammo_loaded, .. } => Some(ammo_loaded),
+            _ => None,
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.entries.len()
     }
@@ -858,23 +925,105 @@ enum Endian {
 }
 
 fn parse_item_proto_record(index: usize, bytes: &[u8]) -> Option<(i32, i32, i32, i32)> {
+    let endian = detect_item_proto_endian(index, bytes)?;
+    let pid = read_i32_at(bytes, PRO_ITEM_PID_OFFSET, endian)?;
+    let message_id = read_i32_at(bytes, PRO_ITEM_MESSAGE_ID_OFFSET, endian)?;
+    let item_type = read_i32_at(bytes, PRO_ITEM_TYPE_OFFSET, endian)?;
+    let base_weight = read_i32_at(bytes, PRO_ITEM_WEIGHT_OFFSET, endian)?;
+    Some((pid, message_id, item_type, base_weight))
+}
+
+fn parse_item_prototype(index: usize, bytes: &[u8]) -> Option<ItemPrototype> {
+    let endian = detect_item_proto_endian(index, bytes)?;
+    let item_type = read_i32_at(bytes, PRO_ITEM_TYPE_OFFSET, endian)?;
+
+    // New objects start with the proto defaults for their subtype data.
+    let mut extra_data = Vec::new();
+    match item_type {
+        ITEM_TYPE_WEAPON => {
+            let capacity = read_i32_at(bytes, PRO_WEAPON_AMMO_CAPACITY_OFFSET, endian)?;
+            let ammo_pid = read_i32_at(bytes, PRO_WEAPON_AMMO_PID_OFFSET, endian)?;
+            extra_data.extend_from_slice(&capacity.to_be_bytes());
+            extra_data.extend_from_slice(&ammo_pid.to_be_bytes());
+        }
+        ITEM_TYPE_AMMO => {
+            let quantity = read_i32_at(bytes, PRO_AMMO_QUANTITY_OFFSET, endian)?;
+            extra_data.extend_from_slice(&quantity.to_be_bytes());
+        }
+        ITEM_TYPE_MISC => {
+            let charges = read_i32_at(bytes, PRO_MISC_CHARGES_OFFSET, endian)?;
+            extra_data.extend_from_slice(&charges.to_be_bytes());
+        }
+        ITEM_TYPE_KEY => {
+            let key_code = read_i32_at(bytes, PRO_KEY_CODE_OFFSET, endian)?;
+            extra_data.extend_from_slice(&key_code.to_be_bytes());
+        }
+        _ => {}
+    }
+
+    Some(ItemPrototype {
+        pid: read_i32_at(bytes, PRO_ITEM_PID_OFFSET, endian)?,
+        item_type,
+        fid: read_i32_at(bytes, PRO_ITEM_FID_OFFSET, endian)?,
+        flags: read_i32_at(bytes, PRO_ITEM_FLAGS_OFFSET, endian)?,
+        light_distance: read_i32_at(bytes, PRO_ITEM_LIGHT_DISTANCE_OFFSET, endian)?,
+        light_intensity: read_i32_at(bytes, PRO_ITEM_LIGHT_INTENSITY_OFFSET, endian)?,
+        extra_data,
+    })
+}
+
+fn parse_armor_prototype(index: usize, bytes: &[u8]) -> Option<ArmorPrototype> {
+    let endian = detect_item_proto_endian(index, bytes)?;
+    let read_array = |offset: usize| -> Option<[i32; DAMAGE_TYPE_COUNT]> {
+        let mut values = [0; DAMAGE_TYPE_COUNT];
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = read_i32_at(bytes, offset + i * 4, endian)?;
+        }
+        Some(values)
+    };
+
+    Some(ArmorPrototype {
+        pid: read_i32_at(bytes, PRO_ITEM_PID_OFFSET, endian)?,
+        armor_class: read_i32_at(bytes, PRO_ARMOR_CLASS_OFFSET, endian)?,
+        damage_resistance: read_array(PRO_ARMOR_DAMAGE_RESISTANCE_OFFSET)?,
+        damage_threshold: read_array(PRO_ARMOR_DAMAGE_THRESHOLD_OFFSET)?,
+        perk: read_i32_at(bytes, PRO_ARMOR_PERK_OFFSET, endian)?,
+    })
+}
+
+fn parse_drug_prototype(index: usize, bytes: &[u8]) -> Option<DrugPrototype> {
+    let endian = detect_item_proto_endian(index, bytes)?;
+    let read_triple = |offset: usize| -> Option<[i32; 3]> {
+        let mut values = [0; 3];
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = read_i32_at(bytes, offset + i * 4, endian)?;
+        }
+        Some(values)
+    };
+
+    Some(DrugPrototype {
+        pid: read_i32_at(bytes, PRO_ITEM_PID_OFFSET, endian)?,
+        stats: read_triple(PRO_DRUG_STAT_OFFSET)?,
+        amounts: [
+            read_triple(PRO_DRUG_AMOUNT_OFFSETS[0])?,
+            read_triple(PRO_DRUG_AMOUNT_OFFSETS[1])?,
+            read_triple(PRO_DRUG_AMOUNT_OFFSETS[2])?,
+        ],
+    })
+}
+
+fn detect_item_proto_endian(index: usize, bytes: &[u8]) -> Option<Endian> {
     for endian in [Endian::Big, Endian::Little] {
         let pid = read_i32_at(bytes, PRO_ITEM_PID_OFFSET, endian)?;
-        let message_id = read_i32_at(bytes, PRO_ITEM_MESSAGE_ID_OFFSET, endian)?;
-        let item_type = read_i32_at(bytes, PRO_ITEM_TYPE_OFFSET, endian)?;
-        let base_weight = read_i32_at(bytes, PRO_ITEM_WEIGHT_OFFSET, endian)?;
         if obj_type_from_pid(pid) == OBJ_TYPE_ITEM && pid_to_index(pid) == index as i32 {
-            return Some((pid, message_id, item_type, base_weight));
+            return Some(endian);
         }
     }
 
     for endian in [Endian::Big, Endian::Little] {
         let pid = read_i32_at(bytes, PRO_ITEM_PID_OFFSET, endian)?;
-        let message_id = read_i32_at(bytes, PRO_ITEM_MESSAGE_ID_OFFSET, endian)?;
-        let item_type = read_i32_at(bytes, PRO_ITEM_TYPE_OFFSET, endian)?;
-        let base_weight = read_i32_at(bytes, PRO_ITEM_WEIGHT_OFFSET, endian)?;
         if obj_type_from_pid(pid) == OBJ_TYPE_ITEM {
-            return Some((pid, message_id, item_type, base_weight));
+            return Some(endian);
         }
     }
 
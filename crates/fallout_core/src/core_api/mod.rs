@@ -1,16 +1,25 @@
 mod engine;
 mod error;
+mod global_var_catalog;
 mod item_catalog;
+mod slot;
+mod thumbnail;
 mod trait_catalog;
 mod types;
 pub mod well_known_items;
 
+pub use crate::event_queue::{QueueEvent, QueueEventData};
+pub use crate::fallout2::sections::Preferences;
 pub use engine::{Engine, Session};
 pub use error::{CoreError, CoreErrorCode};
+pub use global_var_catalog::GlobalVarCatalog;
 pub use item_catalog::{ItemCatalog, detect_install_dir_from_save_path};
+pub use slot::{SlotFileKind, SlotSession};
+pub use thumbnail::{Palette, THUMBNAIL_HEIGHT, THUMBNAIL_WIDTH, resize_rgba};
 pub use trait_catalog::TraitCatalog;
 pub use types::{
-    Capabilities, CapabilityIssue, CharacterExport, DateParts, Game, InventoryEntry,
-    ItemCatalogEntry, KillCountEntry, PerkEntry, ResolvedInventoryEntry, SkillEntry, Snapshot,
-    StatEntry, TraitEntry,
+    AddictionEntry, Capabilities, CapabilityIssue, CharacterExport, CharacterRule, DateParts,
+    EquipmentSlot, EquippedItems, Game, GlobalVarEntry, InventoryEntry, ItemCatalogEntry,
+    KillCountEntry, LevelUpReport, MapInventoryHolder, MapInventoryHolderKind, PerkEntry,
+    ResolvedInventoryEntry, RuleViolation, SkillEntry, Snapshot, StatEntry, TraitEntry,
 };
@@ -0,0 +1,690 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::automap::{
+    AUTOMAP_MAP_COUNT_FALLOUT1, AUTOMAP_MAP_COUNT_FALLOUT2, Automap, automap_tile_for,
+};
+use crate::common::atomic_write::write_dir_atomically;
+use crate::map_save::{MapSave, is_inventory_holder};
+use crate::object::{OBJ_TYPE_CRITTER, OBJECT_FLAG_SEEN, obj_type_from_pid};
+use crate::sfall::{
+    SFALL_ARRAYS_FILE_NAME, SFALL_FILE_SYSTEM_FILE_NAME, SFALL_GLOBALS_FILE_NAME, SfallArrays,
+    SfallFileSystem, SfallGlobalKey, SfallGlobals,
+};
+
+use super::engine::{Engine, Session};
+use super::error::{CoreError, CoreErrorCode};
+use super::item_catalog::ItemCatalog;
+use super::types::{Game, InventoryEntry, MapInventoryHolder, MapInventoryHolderKind};
+
+const SAVE_DAT_FILE_NAME: &str = "SAVE.DAT";
+const AUTOMAP_FILE_NAME: &str = "AUTOMAP.SAV";
+const PROTO_DIR_NAME: &str = "proto";
+const SFALL_FILE_PREFIX: &str = "sfall";
+const MAP_SAVE_EXTENSION: &str = "sav";
+
+/// What a file inside a save slot directory holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotFileKind {
+    /// Per-map state (`*.SAV`), named after the map it belongs to.
+    MapSave,
+    Automap,
+    /// Modified item/critter prototypes under `proto/`.
+    Proto,
+    /// sfall extension data (`sfallgv.sav`, `sfallfs.sav`, `sfalldb.sav`).
+    Sfall,
+    Other,
+}
+
+/// A whole save slot: `SAVE.DAT` plus every file the game keeps beside it.
+///
+/// Files other than `SAVE.DAT` are held as raw bytes keyed by their path
+/// relative to the slot, using the on-disk spelling so a written slot keeps
+/// the original names.
+#[derive(Debug)]
+pub struct SlotSession {
+    path: PathBuf,
+    save_dat_name: String,
+    session: Session,
+    files: BTreeMap<PathBuf, Vec<u8>>,
+}
+
+impl Engine {
+    pub fn open_slot<P: AsRef<Path>>(&self, path: P) -> Result<SlotSession, CoreError> {
+        let path = path.as_ref();
+        let mut files = BTreeMap::new();
+        collect_slot_files(path, Path::new(""), &mut files)?;
+
+        let save_dat_path = files
+            .keys()
+            .find(|relative| is_save_dat(relative))
+            .cloned()
+            .ok_or_else(|| {
+                CoreError::new(
+                    CoreErrorCode::Io,
+                    format!("no {SAVE_DAT_FILE_NAME} found in {}", path.display()),
+                )
+            })?;
+        let save_dat = files.remove(&save_dat_path).unwrap_or_default();
+        let session = self.open_bytes(&save_dat, None)?;
+
+        Ok(SlotSession {
+            path: path.to_path_buf(),
+            save_dat_name: save_dat_path.to_string_lossy().to_string(),
+            session,
+            files,
+        })
+    }
+}
+
+impl SlotSession {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    pub fn session_mut(&mut self) -> &mut Session {
+        &mut self.session
+    }
+
+    /// Maps the player has entered, as listed in `SAVE.DAT`. The game writes a
+    /// `.SAV` file for each of these when it saves.
+    pub fn visited_maps(&self) -> Vec<String> {
+        self.session.map_files()
+    }
+
+    /// File names of the map saves present in the slot directory.
+    pub fn map_save_names(&self) -> Vec<String> {
+        self.files_of_kind(SlotFileKind::MapSave)
+            .map(|(relative, _)| relative.to_string_lossy().to_string())
+            .collect()
+    }
+
+    pub fn map_save_bytes(&self, name: &str) -> Option<&[u8]> {
+        self.files_of_kind(SlotFileKind::MapSave)
+            .find(|(relative, _)| relative.as_os_str().eq_ignore_ascii_case(name))
+            .map(|(_, bytes)| bytes)
+    }
+
+    /// Parse a map save, including any edits made through this session.
+    pub fn map_save(&self, name: &str) -> Result<MapSave, CoreError> {
+        let key = self.map_save_key(name)?;
+        MapSave::parse(&self.files[&key]).map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::Parse,
+                format!("failed to parse map save {name}: {e}"),
+            )
+        })
+    }
+
+    /// Containers and critters on a saved map, with their inventories.
+    pub fn map_inventories(&self, name: &str) -> Result<Vec<MapInventoryHolder>, CoreError> {
+        let map = self.map_save(name)?;
+        Ok(map
+            .all_objects()
+            .filter(|(_, object)| is_inventory_holder(object))
+            .map(|(elevation, object)| MapInventoryHolder {
+                kind: if obj_type_from_pid(object.pid) == OBJ_TYPE_CRITTER {
+                    MapInventoryHolderKind::Critter
+                } else {
+                    MapInventoryHolderKind::Container
+                },
+                object_id: object.id,
+                pid: object.pid,
+                elevation,
+                tile: object.tile,
+                inventory: object
+                    .inventory
+                    .iter()
+                    .map(|item| InventoryEntry {
+                        quantity: item.quantity,
+                        pid: item.object.pid,
+                    })
+                    .collect(),
+            })
+            .collect())
+    }
+
+    /// Name of the map save whose header carries map id `map_id`.
+    pub fn map_save_name_for_id(&self, map_id: i32) -> Result<Option<String>, CoreError> {
+        Ok(self.map_save_for_id(map_id)?.map(|(name, _)| name))
+    }
+
+    /// Move the player to `tile` on `elevation` of map `map_id`. The map must
+    /// have a save in this slot, and the elevation must exist on it.
+    pub fn teleport(&mut self, map_id: i16, elevation: i16, tile: i32) -> Result<(), CoreError> {
+        let Some((name, map)) = self.map_save_for_id(i32::from(map_id))? else {
+            return Err(CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("slot has no map save for map {map_id}"),
+            ));
+        };
+        self.session
+            .teleport_to_map_save(&name, &map.header, elevation, tile)
+    }
+
+    /// Highest object id used on any of the slot's map saves, 0 when there
+    /// are none. Object ids are unique across the whole slot.
+    pub fn max_map_object_id(&self) -> Result<i32, CoreError> {
+        let mut used_ids = BTreeSet::new();
+        for name in self.map_save_names() {
+            for (_, object) in self.map_save(&name)?.all_objects() {
+                object.collect_object_ids(&mut used_ids);
+            }
+        }
+        Ok(used_ids.last().copied().unwrap_or(0))
+    }
+
+    /// [`Session::add_inventory_item_with_catalog`], with new object ids kept
+    /// clear of every object on the slot's maps.
+    pub fn add_inventory_item_with_catalog(
+        &mut self,
+        pid: i32,
+        quantity: i32,
+        catalog: &ItemCatalog,
+    ) -> Result<(), CoreError> {
+        let id_floor = self.max_map_object_id()?;
+        self.session
+            .add_inventory_item_with_catalog(pid, quantity, catalog, id_floor)
+    }
+
+    /// Move an item (the whole stack when `quantity` is `None`) from a
+    /// container or critter on a saved map into the player's inventory.
+    pub fn move_item_to_player(
+        &mut self,
+        map_name: &str,
+        owner_id: i32,
+        pid: i32,
+        quantity: Option<i32>,
+    ) -> Result<(), CoreError> {
+        let key = self.map_save_key(map_name)?;
+        let mut map = self.map_save(map_name)?;
+        let item = map
+            .take_inventory_item(owner_id, pid, quantity)
+            .map_err(|e| map_edit_error(map_name, e))?;
+        let bytes = encode_map_save(map_name, &map)?;
+        self.session.insert_inventory_object(item)?;
+        self.files.insert(key, bytes);
+        Ok(())
+    }
+
+    /// Move an item from the player's inventory into a container or critter
+    /// on a saved map.
+    pub fn move_item_from_player(
+        &mut self,
+        map_name: &str,
+        owner_id: i32,
+        pid: i32,
+        quantity: Option<i32>,
+    ) -> Result<(), CoreError> {
+        let key = self.map_save_key(map_name)?;
+        let mut map = self.map_save(map_name)?;
+        if !map.object_by_id(owner_id).is_some_and(is_inventory_holder) {
+            return Err(CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("{map_name} has no container or critter with object id {owner_id}"),
+            ));
+        }
+        let item = self.session.take_inventory_object(pid, quantity)?;
+        let bytes = map
+            .insert_inventory_item(owner_id, item.clone())
+            .map_err(|e| map_edit_error(map_name, e))
+            .and_then(|()| encode_map_save(map_name, &map));
+        match bytes {
+            Ok(bytes) => {
+                self.files.insert(key, bytes);
+                Ok(())
+            }
+            Err(e) => {
+                self.session.insert_inventory_object(item)?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Move an item between two containers or critters on the same saved map.
+    pub fn move_map_item(
+        &mut self,
+        map_name: &str,
+        from_id: i32,
+        to_id: i32,
+        pid: i32,
+        quantity: Option<i32>,
+    ) -> Result<(), CoreError> {
+        let key = self.map_save_key(map_name)?;
+        let mut map = self.map_save(map_name)?;
+        map.take_inventory_item(from_id, pid, quantity)
+            .and_then(|item| map.insert_inventory_item(to_id, item))
+            .map_err(|e| map_edit_error(map_name, e))?;
+        let bytes = encode_map_save(map_name, &map)?;
+        self.files.insert(key, bytes);
+        Ok(())
+    }
+
+    pub fn automap_bytes(&self) -> Option<&[u8]> {
+        self.files_of_kind(SlotFileKind::Automap)
+            .next()
+            .map(|(_, bytes)| bytes)
+    }
+
+    /// Parse `AUTOMAP.SAV`, including any edits made through this session.
+    pub fn automap(&self) -> Result<Automap, CoreError> {
+        let key = self.automap_key()?;
+        let map_count = match self.session.game() {
+            Game::Fallout1 => AUTOMAP_MAP_COUNT_FALLOUT1,
+            Game::Fallout2 => AUTOMAP_MAP_COUNT_FALLOUT2,
+        };
+        Automap::parse(&self.files[&key], map_count).map_err(|e| {
+            CoreError::new(
+                CoreErrorCode::Parse,
+                format!("failed to parse {AUTOMAP_FILE_NAME}: {e}"),
+            )
+        })
+    }
+
+    /// Reveal all of map `map_id` on the automap. Every wall and piece of
+    /// scenery on the map's save is recorded in `AUTOMAP.SAV` and flagged as
+    /// seen, so the reveal survives the game rewriting the entry on the next
+    /// visit.
+    pub fn reveal_automap(&mut self, map_id: i32) -> Result<(), CoreError> {
+        let Some((name, mut map)) = self.map_save_for_id(map_id)? else {
+            return Err(CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("slot has no map save for map {map_id}"),
+            ));
+        };
+        let mut automap = self.automap()?;
+        automap
+            .reveal_all(automap_map_index(map_id)?, &map)
+            .map_err(automap_edit_error)?;
+        set_automap_objects_seen(&mut map, true);
+        let map_bytes = encode_map_save(&name, &map)?;
+        self.store_automap(&automap)?;
+        let key = self.map_save_key(&name)?;
+        self.files.insert(key, map_bytes);
+        Ok(())
+    }
+
+    /// Forget everything explored on map `map_id`. When the slot has a save
+    /// for the map, its walls and scenery are flagged as unseen too.
+    pub fn clear_automap(&mut self, map_id: i32) -> Result<(), CoreError> {
+        let mut automap = self.automap()?;
+        automap
+            .clear(automap_map_index(map_id)?)
+            .map_err(automap_edit_error)?;
+        let map_save = match self.map_save_for_id(map_id)? {
+            Some((name, mut map)) => {
+                set_automap_objects_seen(&mut map, false);
+                Some((self.map_save_key(&name)?, encode_map_save(&name, &map)?))
+            }
+            None => None,
+        };
+        self.store_automap(&automap)?;
+        if let Some((key, bytes)) = map_save {
+            self.files.insert(key, bytes);
+        }
+        Ok(())
+    }
+
+    /// Paths (relative to the slot) of the prototypes saved under `proto/`.
+    pub fn proto_paths(&self) -> Vec<&Path> {
+        self.files_of_kind(SlotFileKind::Proto)
+            .map(|(relative, _)| relative)
+            .collect()
+    }
+
+    pub fn sfall_file_names(&self) -> Vec<String> {
+        self.files_of_kind(SlotFileKind::Sfall)
+            .map(|(relative, _)| relative.to_string_lossy().to_string())
+            .collect()
+    }
+
+    pub fn sfall_bytes(&self, name: &str) -> Option<&[u8]> {
+        self.files_of_kind(SlotFileKind::Sfall)
+            .find(|(relative, _)| relative.as_os_str().eq_ignore_ascii_case(name))
+            .map(|(_, bytes)| bytes)
+    }
+
+    /// Parse `sfallgv.sav`, or `None` when the slot was not saved with sfall.
+    pub fn sfall_globals(&self) -> Result<Option<SfallGlobals>, CoreError> {
+        self.parse_sfall_file(SFALL_GLOBALS_FILE_NAME, SfallGlobals::parse)
+    }
+
+    pub fn set_sfall_globals(&mut self, globals: &SfallGlobals) -> Result<(), CoreError> {
+        let bytes = globals
+            .to_bytes()
+            .map_err(|e| sfall_edit_error(SFALL_GLOBALS_FILE_NAME, e))?;
+        self.store_sfall_file(SFALL_GLOBALS_FILE_NAME, bytes);
+        Ok(())
+    }
+
+    /// Set one sfall global, adding it when the save does not have it yet.
+    pub fn set_sfall_global(&mut self, key: SfallGlobalKey, value: i32) -> Result<(), CoreError> {
+        let Some(mut globals) = self.sfall_globals()? else {
+            return Err(CoreError::new(
+                CoreErrorCode::UnsupportedOperation,
+                format!("no {SFALL_GLOBALS_FILE_NAME} in {}", self.path.display()),
+            ));
+        };
+        globals.set(key, value);
+        self.set_sfall_globals(&globals)
+    }
+
+    /// Parse `sfallfs.sav`, or `None` when the slot was not saved with sfall.
+    pub fn sfall_file_system(&self) -> Result<Option<SfallFileSystem>, CoreError> {
+        self.parse_sfall_file(SFALL_FILE_SYSTEM_FILE_NAME, SfallFileSystem::parse)
+    }
+
+    pub fn set_sfall_file_system(&mut self, fs: &SfallFileSystem) -> Result<(), CoreError> {
+        let bytes = fs
+            .to_bytes()
+            .map_err(|e| sfall_edit_error(SFALL_FILE_SYSTEM_FILE_NAME, e))?;
+        self.store_sfall_file(SFALL_FILE_SYSTEM_FILE_NAME, bytes);
+        Ok(())
+    }
+
+    /// Parse `sfalldb.sav`, or `None` when the slot was not saved with sfall.
+    pub fn sfall_arrays(&self) -> Result<Option<SfallArrays>, CoreError> {
+        self.parse_sfall_file(SFALL_ARRAYS_FILE_NAME, SfallArrays::parse)
+    }
+
+    pub fn set_sfall_arrays(&mut self, arrays: &SfallArrays) -> Result<(), CoreError> {
+        let bytes = arrays
+            .to_bytes()
+            .map_err(|e| sfall_edit_error(SFALL_ARRAYS_FILE_NAME, e))?;
+        self.store_sfall_file(SFALL_ARRAYS_FILE_NAME, bytes);
+        Ok(())
+    }
+
+    /// Look up any file in the slot by its relative path, ignoring ASCII case.
+    pub fn file_bytes<P: AsRef<Path>>(&self, relative: P) -> Option<&[u8]> {
+        let wanted = relative.as_ref();
+        self.files
+            .iter()
+            .find(|(path, _)| paths_eq_ignore_ascii_case(path, wanted))
+            .map(|(_, bytes)| bytes.as_slice())
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = (&Path, SlotFileKind)> {
+        self.files
+            .keys()
+            .map(|relative| (relative.as_path(), classify_slot_file(relative)))
+    }
+
+    /// Write the slot, including any edits, to a directory that must not exist yet.
+    ///
+    /// Everything is written into a temporary sibling directory first and then
+    /// renamed into place, so a failure never leaves a half-written slot behind.
+    pub fn write_to_dir<P: AsRef<Path>>(&self, out_dir: P) -> Result<(), CoreError> {
+        let out_dir = out_dir.as_ref();
+        if out_dir.exists() {
+            return Err(CoreError::new(
+                CoreErrorCode::Io,
+                format!(
+                    "refusing to overwrite existing slot directory {}",
+                    out_dir.display()
+                ),
+            ));
+        }
+
+        let save_dat = self.session.to_bytes_modified()?;
+        write_dir_atomically(out_dir, |dir| {
+            write_slot_files(dir, &self.save_dat_name, &save_dat, &self.files)
+        })
+        .map_err(|e| io_error(e.to_string()))
+    }
+
+    fn map_save_for_id(&self, map_id: i32) -> Result<Option<(String, MapSave)>, CoreError> {
+        for name in self.map_save_names() {
+            let map = self.map_save(&name)?;
+            if map.header.map_index == map_id {
+                return Ok(Some((name, map)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Replace `AUTOMAP.SAV` and the automap size `SAVE.DAT` records for it.
+    fn store_automap(&mut self, automap: &Automap) -> Result<(), CoreError> {
+        let key = self.automap_key()?;
+        let size = automap.to_uncompressed_bytes().len();
+        let bytes = automap.to_bytes().map_err(automap_edit_error)?;
+        self.session.set_automap_size(size as i32)?;
+        self.files.insert(key, bytes);
+        Ok(())
+    }
+
+    fn parse_sfall_file<T>(
+        &self,
+        name: &str,
+        parse: fn(&[u8]) -> std::io::Result<T>,
+    ) -> Result<Option<T>, CoreError> {
+        self.sfall_bytes(name)
+            .map(|bytes| {
+                parse(bytes).map_err(|e| {
+                    CoreError::new(CoreErrorCode::Parse, format!("failed to parse {name}: {e}"))
+                })
+            })
+            .transpose()
+    }
+
+    /// Replace an sfall file, keeping the case of an existing file's name.
+    fn store_sfall_file(&mut self, name: &str, bytes: Vec<u8>) {
+        let key = self
+            .files_of_kind(SlotFileKind::Sfall)
+            .find(|(relative, _)| relative.as_os_str().eq_ignore_ascii_case(name))
+            .map(|(relative, _)| relative.to_path_buf())
+            .unwrap_or_else(|| PathBuf::from(name));
+        self.files.insert(key, bytes);
+    }
+
+    fn automap_key(&self) -> Result<PathBuf, CoreError> {
+        self.files_of_kind(SlotFileKind::Automap)
+            .next()
+            .map(|(relative, _)| relative.to_path_buf())
+            .ok_or_else(|| {
+                CoreError::new(
+                    CoreErrorCode::Io,
+                    format!("no {AUTOMAP_FILE_NAME} in {}", self.path.display()),
+                )
+            })
+    }
+
+    fn map_save_key(&self, name: &str) -> Result<PathBuf, CoreError> {
+        self.files_of_kind(SlotFileKind::MapSave)
+            .find(|(relative, _)| relative.as_os_str().eq_ignore_ascii_case(name))
+            .map(|(relative, _)| relative.to_path_buf())
+            .ok_or_else(|| {
+                CoreError::new(
+                    CoreErrorCode::Io,
+                    format!("no map save named {name} in {}", self.path.display()),
+                )
+            })
+    }
+
+    fn files_of_kind(&self, kind: SlotFileKind) -> impl Iterator<Item = (&Path, &[u8])> {
+        self.files
+            .iter()
+            .filter(move |(relative, _)| classify_slot_file(relative) == kind)
+            .map(|(relative, bytes)| (relative.as_path(), bytes.as_slice()))
+    }
+}
+
+fn classify_slot_file(relative: &Path) -> SlotFileKind {
+    let mut components = relative.components();
+    let first = components.next();
+    if components.next().is_some() {
+        return match first {
+            Some(Component::Normal(dir)) if dir.eq_ignore_ascii_case(PROTO_DIR_NAME) => {
+                SlotFileKind::Proto
+            }
+            _ => SlotFileKind::Other,
+        };
+    }
+
+    let name = relative.to_string_lossy().to_ascii_lowercase();
+    if name == AUTOMAP_FILE_NAME.to_ascii_lowercase() {
+        SlotFileKind::Automap
+    } else if name.starts_with(SFALL_FILE_PREFIX) {
+        SlotFileKind::Sfall
+    } else if relative
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(MAP_SAVE_EXTENSION))
+    {
+        SlotFileKind::MapSave
+    } else {
+        SlotFileKind::Other
+    }
+}
+
+fn is_save_dat(relative: &Path) -> bool {
+    relative.components().count() == 1
+        && relative
+            .as_os_str()
+            .eq_ignore_ascii_case(SAVE_DAT_FILE_NAME)
+}
+
+fn paths_eq_ignore_ascii_case(a: &Path, b: &Path) -> bool {
+    let mut a = a.components();
+    let mut b = b.components();
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return true,
+            (Some(x), Some(y)) if x.as_os_str().eq_ignore_ascii_case(y.as_os_str()) => {}
+            _ => return false,
+        }
+    }
+}
+
+fn collect_slot_files(
+    root: &Path,
+    relative: &Path,
+    out: &mut BTreeMap<PathBuf, Vec<u8>>,
+) -> Result<(), CoreError> {
+    let dir = root.join(relative);
+    let entries = fs::read_dir(&dir)
+        .map_err(|e| io_error(format!("failed to read directory {}: {e}", dir.display())))?;
+    for entry in entries {
+        let entry = entry
+            .map_err(|e| io_error(format!("failed to read directory {}: {e}", dir.display())))?;
+        let child = relative.join(entry.file_name());
+        let file_type = entry
+            .file_type()
+            .map_err(|e| io_error(format!("failed to inspect {}: {e}", entry.path().display())))?;
+        if file_type.is_dir() {
+            collect_slot_files(root, &child, out)?;
+        } else if file_type.is_file() {
+            let bytes = fs::read(entry.path())
+                .map_err(|e| io_error(format!("failed to read {}: {e}", entry.path().display())))?;
+            out.insert(child, bytes);
+        }
+    }
+    Ok(())
+}
+
+fn write_slot_files(
+    dir: &Path,
+    save_dat_name: &str,
+    save_dat: &[u8],
+    files: &BTreeMap<PathBuf, Vec<u8>>,
+) -> io::Result<()> {
+    let files = std::iter::once((Path::new(save_dat_name), save_dat)).chain(
+        files
+            .iter()
+            .map(|(path, bytes)| (path.as_path(), bytes.as_slice())),
+    );
+    for (relative, bytes) in files {
+        let path = dir.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                io::Error::new(
+                    e.kind(),
+                    format!("failed to create directory {}: {e}", parent.display()),
+                )
+            })?;
+        }
+        fs::write(&path, bytes).map_err(|e| {
+            io::Error::new(e.kind(), format!("failed to write {}: {e}", path.display()))
+        })?;
+    }
+    Ok(())
+}
+
+fn encode_map_save(map_name: &str, map: &MapSave) -> Result<Vec<u8>, CoreError> {
+    map.to_bytes().map_err(|e| map_edit_error(map_name, e))
+}
+
+fn map_edit_error(map_name: &str, e: std::io::Error) -> CoreError {
+    CoreError::new(
+        CoreErrorCode::UnsupportedOperation,
+        format!("failed to edit map save {map_name}: {e}"),
+    )
+}
+
+fn automap_map_index(map_id: i32) -> Result<usize, CoreError> {
+    usize::try_from(map_id).map_err(|_| {
+        CoreError::new(
+            CoreErrorCode::UnsupportedOperation,
+            format!("invalid map id {map_id}"),
+        )
+    })
+}
+
+fn set_automap_objects_seen(map: &mut MapSave, seen: bool) {
+    for object in map.objects.iter_mut().flatten() {
+        if automap_tile_for(object).is_some() {
+            if seen {
+                object.flags |= OBJECT_FLAG_SEEN;
+            } else {
+                object.flags &= !OBJECT_FLAG_SEEN;
+            }
+        }
+    }
+}
+
+fn automap_edit_error(e: std::io::Error) -> CoreError {
+    CoreError::new(
+        CoreErrorCode::UnsupportedOperation,
+        format!("failed to edit {AUTOMAP_FILE_NAME}: {e}"),
+    )
+}
+
+fn sfall_edit_error(name: &str, e: std::io::Error) -> CoreError {
+    CoreError::new(
+        CoreErrorCode::UnsupportedOperation,
+        format!("failed to edit {name}: {e}"),
+    )
+}
+
+fn io_error(message: String) -> CoreError {
+    CoreError::new(CoreErrorCode::Io, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{SlotFileKind, classify_slot_file};
+
+    #[test]
+    fn classifies_slot_files_by_name() {
+        let cases = [
+            ("ARCAVES.SAV", SlotFileKind::MapSave),
+            ("arcaves.sav", SlotFileKind::MapSave),
+            ("AUTOMAP.SAV", SlotFileKind::Automap),
+            ("sfallgv.sav", SlotFileKind::Sfall),
+            ("proto/items/00000455.pro", SlotFileKind::Proto),
+            ("PROTO/critters/00000062.pro", SlotFileKind::Proto),
+            ("notes.txt", SlotFileKind::Other),
+            ("extra/ARCAVES.SAV", SlotFileKind::Other),
+        ];
+        for (path, kind) in cases {
+            assert_eq!(classify_slot_file(Path::new(path)), kind, "{path}");
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::error::{CoreError, CoreErrorCode};
+use super::item_catalog::{DatArchive, find_master_dat_path, resolve_case_insensitive_path};
+
+pub const THUMBNAIL_WIDTH: usize = 224;
+pub const THUMBNAIL_HEIGHT: usize = 133;
+
+const PALETTE_ARCHIVE_PATH: &str = "color.pal";
+const PALETTE_COLOR_COUNT: usize = 256;
+const PALETTE_COMPONENT_MAX: u8 = 63;
+/// Indices the game animates (water, fire, monitors); a still image must
+/// avoid them or it flickers in the load menu.
+const PALETTE_CYCLE_RANGE: std::ops::RangeInclusive<usize> = 229..=254;
+
+/// The game's 256-colour palette, used to convert between palettized images
+/// and RGB.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    colors: [[u8; 3]; PALETTE_COLOR_COUNT],
+}
+
+impl Palette {
+    pub fn load_from_install_dir(install_dir: &Path) -> Result<Self, CoreError> {
+        let bytes = match find_color_pal_path(install_dir) {
+            Some(path) => fs::read(&path).map_err(|e| {
+                CoreError::new(
+                    CoreErrorCode::Io,
+                    format!("failed to read {}: {e}", path.display()),
+                )
+            })?,
+            None => {
+                let dat = find_master_dat_path(install_dir)
+                    .and_then(|path| DatArchive::open(&path).ok())
+                    .ok_or_else(|| {
+                        CoreError::new(
+                            CoreErrorCode::Io,
+                            format!(
+                                "could not find color.pal or a readable master.dat under {}",
+                                install_dir.display()
+                            ),
+                        )
+                    })?;
+                dat.read_file(PALETTE_ARCHIVE_PATH).map_err(|e| {
+                    CoreError::new(
+                        CoreErrorCode::Io,
+                        format!(
+                            "failed to load {PALETTE_ARCHIVE_PATH} from {}: {e}",
+                            dat.path().display()
+                        ),
+                    )
+                })?
+            }
+        };
+
+        Self::from_pal_bytes(&bytes)
+    }
+
+    /// Parse `color.pal`: 256 RGB triples with 6-bit components, followed by
+    /// lookup tables that are not needed here.
+    pub fn from_pal_bytes(bytes: &[u8]) -> Result<Self, CoreError> {
+        let table = bytes.get(..PALETTE_COLOR_COUNT * 3).ok_or_else(|| {
+            CoreError::new(
+                CoreErrorCode::Parse,
+                format!(
+                    "palette is {} bytes, expected at least {}",
+                    bytes.len(),
+                    PALETTE_COLOR_COUNT * 3
+                ),
+            )
+        })?;
+
+        let mut colors = [[0u8; 3]; PALETTE_COLOR_COUNT];
+        for (color, rgb) in colors.iter_mut().zip(table.chunks_exact(3)) {
+            // Out-of-range entries mark unused slots; the game shows them black.
+            if rgb.iter().all(|&c| c <= PALETTE_COMPONENT_MAX) {
+                *color = [rgb[0] << 2, rgb[1] << 2, rgb[2] << 2];
+            }
+        }
+        Ok(Self { colors })
+    }
+
+    /// Stand-in for when the game's `color.pal` is not available: index `i`
+    /// is shown as grey level `i`. The game's palette is not ordered by
+    /// brightness, so pictures come out as noisy, approximate greys. Good
+    /// enough for a preview, not for quantizing an image that goes back into
+    /// a save.
+    pub fn builtin() -> Self {
+        let mut colors = [[0u8; 3]; PALETTE_COLOR_COUNT];
+        for (index, color) in colors.iter_mut().enumerate() {
+            *color = [index as u8; 3];
+        }
+        Self { colors }
+    }
+
+    pub fn rgb(&self, index: u8) -> [u8; 3] {
+        self.colors[usize::from(index)]
+    }
+
+    /// Expand palette indices to opaque RGBA pixels.
+    pub fn to_rgba(&self, indices: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(indices.len() * 4);
+        for &index in indices {
+            out.extend_from_slice(&self.rgb(index));
+            out.push(u8::MAX);
+        }
+        out
+    }
+
+    /// Index of the closest non-animated colour, by squared RGB distance.
+    pub fn nearest_index(&self, rgb: [u8; 3]) -> u8 {
+        let mut best = (0usize, u32::MAX);
+        for (index, color) in self.colors.iter().enumerate() {
+            if PALETTE_CYCLE_RANGE.contains(&index) {
+                continue;
+            }
+            let distance: u32 = color
+                .iter()
+                .zip(rgb)
+                .map(|(&a, b)| u32::from(a.abs_diff(b)).pow(2))
+                .sum();
+            if distance < best.1 {
+                best = (index, distance);
+                if distance == 0 {
+                    break;
+                }
+            }
+        }
+        best.0 as u8
+    }
+
+    /// Map RGBA pixels to palette indices. Alpha is composited over black,
+    /// the colour the load menu shows behind the thumbnail.
+    pub fn quantize(&self, rgba: &[u8]) -> Vec<u8> {
+        let mut cache = std::collections::HashMap::new();
+        rgba.chunks_exact(4)
+            .map(|pixel| {
+                let alpha = u16::from(pixel[3]);
+                let rgb = [0, 1, 2].map(|c| (u16::from(pixel[c]) * alpha / 255) as u8);
+                *cache.entry(rgb).or_insert_with(|| self.nearest_index(rgb))
+            })
+            .collect()
+    }
+}
+
+/// Resize an RGBA image with a box filter: each output pixel averages the
+/// source pixels it covers, falling back to the nearest pixel when enlarging.
+pub fn resize_rgba(
+    rgba: &[u8],
+    src_width: usize,
+    src_height: usize,
+    dst_width: usize,
+    dst_height: usize,
+) -> Vec<u8> {
+    let span = |dst: usize, src_len: usize, dst_len: usize| {
+        let start = dst * src_len / dst_len;
+        let end = ((dst + 1) * src_len / dst_len).max(start + 1);
+        start..end
+    };
+
+    let mut out = Vec::with_capacity(dst_width * dst_height * 4);
+    for y in 0..dst_height {
+        let rows = span(y, src_height, dst_height);
+        for x in 0..dst_width {
+            let cols = span(x, src_width, dst_width);
+            let mut sum = [0u64; 4];
+            for row in rows.clone() {
+                for col in cols.clone() {
+                    let offset = (row * src_width + col) * 4;
+                    for (total, &c) in sum.iter_mut().zip(&rgba[offset..offset + 4]) {
+                        *total += u64::from(c);
+                    }
+                }
+            }
+            let count = (rows.len() * cols.len()) as u64;
+            out.extend(sum.map(|total| ((total + count / 2) / count) as u8));
+        }
+    }
+    out
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+fn find_color_pal_path(install_dir: &Path) -> Option<PathBuf> {
+    [["data", "color.pal"].as_slice(), ["color.pal"].as_slice()]
+        .iter()
+        .find_map(|parts| resolve_case_insensitive_path(install_dir, parts))
+        .filter(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Palette, resize_rgba};
+
+    #[test]
+    fn from_pal_bytes_scales_six_bit_components() {
+        let mut bytes = vec![0u8; 256 * 3 + 32_768];
+        bytes[3..6].copy_from_slice(&[63, 32, 1]);
+        bytes[6..9].copy_from_slice(&[64, 0, 0]);
+
+        let palette = Palette::from_pal_bytes(&bytes).expect("palette should parse");
+        assert_eq!(palette.rgb(1), [252, 128, 4]);
+        assert_eq!(palette.rgb(2), [0, 0, 0]);
+        assert_eq!(palette.to_rgba(&[1]), vec![252, 128, 4, 255]);
+        assert!(Palette::from_pal_bytes(&bytes[..100]).is_err());
+    }
+
+    #[test]
+    fn resize_and_quantize_avoid_animated_colours() {
+        let rgba = [[0, 0, 0, 255], [200, 200, 200, 255]].concat();
+        assert_eq!(resize_rgba(&rgba, 2, 1, 1, 1), vec![100, 100, 100, 255]);
+        assert_eq!(resize_rgba(&rgba, 2, 1, 4, 1).len(), 16);
+
+        let palette = Palette::builtin();
+        assert_eq!(palette.quantize(&[100, 100, 100, 255]), vec![100]);
+        assert_eq!(palette.quantize(&[240, 240, 240, 255]), vec![228]);
+        assert_eq!(palette.quantize(&[240, 240, 240, 0]), vec![0]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::event_queue::QueueEvent;
 use crate::gender::Gender;
 use serde::{Deserialize, Serialize};
 
@@ -44,6 +45,43 @@ pub struct InventoryEntry {
     pub pid: i32,
 }
 
+/// Where a critter carries an equipped item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EquipmentSlot {
+    Armor,
+    LeftHand,
+    RightHand,
+}
+
+/// PIDs of the items the player has equipped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EquippedItems {
+    pub armor: Option<i32>,
+    pub left_hand: Option<i32>,
+    pub right_hand: Option<i32>,
+}
+
+/// A container or critter on a saved map, with what it carries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MapInventoryHolder {
+    pub kind: MapInventoryHolderKind,
+    pub object_id: i32,
+    pub pid: i32,
+    pub elevation: usize,
+    pub tile: i32,
+    pub inventory: Vec<InventoryEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MapInventoryHolderKind {
+    Container,
+    Critter,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct ItemCatalogEntry {
@@ -61,6 +99,13 @@ pub struct ResolvedInventoryEntry {
     pub name: Option<String>,
     pub base_weight: Option<i32>,
     pub item_type: Option<i32>,
+    /// Ammo PID loaded in a weapon, -1 when it has never been loaded.
+    pub ammo_pid: Option<i32>,
+    /// Rounds loaded in a weapon.
+    pub ammo_loaded: Option<i32>,
+    /// Rounds left in the top box of ammo, or charges left in a misc item.
+    pub charges: Option<i32>,
+    pub key_code: Option<i32>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -133,6 +178,62 @@ pub struct PerkEntry {
     pub rank: i32,
 }
 
+/// An addiction perk the character has, or is about to get from a pending
+/// withdrawal event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AddictionEntry {
+    /// Index of the addiction perk.
+    pub index: usize,
+    pub name: String,
+    /// Whether the addiction perk is currently set.
+    pub active: bool,
+    /// Pending withdrawal start/end events for this addiction, or `None`
+    /// when the event queue was not decoded.
+    pub withdrawal_events: Option<usize>,
+}
+
+/// What [`Session::level_up_to`](super::Session::level_up_to) granted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LevelUpReport {
+    pub from_level: i32,
+    pub to_level: i32,
+    pub experience: i32,
+    pub skill_points: i32,
+    pub hit_points: i32,
+    /// Levels that earned a perk pick; the perks themselves are left to the
+    /// caller.
+    pub perk_levels: Vec<i32>,
+    /// Whether a perk pick now waits in the character editor. The editor
+    /// holds a single pick, and only Fallout 2's editor state is decoded.
+    pub free_perk: bool,
+}
+
+/// Which part of the character a [`RuleViolation`] concerns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CharacterRule {
+    Level,
+    Special,
+    TaggedSkills,
+    Traits,
+    Perks,
+    SkillPoints,
+}
+
+/// A way the character breaks the rules the game enforces while playing,
+/// reported by [`Session::validate_character`](super::Session::validate_character).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleViolation {
+    pub rule: CharacterRule,
+    /// Stat, skill, trait or perk index the finding is about, when it is
+    /// about a single one.
+    pub index: Option<usize>,
+    pub message: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct KillCountEntry {
@@ -148,6 +249,14 @@ pub struct TraitEntry {
     pub name: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GlobalVarEntry {
+    pub index: usize,
+    pub name: Option<String>,
+    pub value: i32,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct CharacterExport {
@@ -167,6 +276,10 @@ pub struct CharacterExport {
     pub elevation: i16,
     pub global_var_count: usize,
     pub hp: Option<i32>,
+    #[serde(default)]
+    pub radiation: Option<i32>,
+    #[serde(default)]
+    pub poison: Option<i32>,
     pub karma: i32,
     pub reputation: i32,
     pub special: Vec<StatEntry>,
@@ -177,4 +290,10 @@ pub struct CharacterExport {
     pub tagged_skills: Vec<usize>,
     pub kill_counts: Vec<KillCountEntry>,
     pub inventory: Vec<InventoryEntry>,
+    /// Pending event queue entries, or `None` when the queue was not
+    /// decoded.
+    #[serde(default)]
+    pub queue_events: Option<Vec<QueueEvent>>,
+    #[serde(default)]
+    pub addictions: Vec<AddictionEntry>,
 }
@@ -0,0 +1,242 @@
+//! The timed event queue both games save: Fallout 1 in handler 15,
+//! Fallout 2 in handler 24.
+
+use std::io::{self, Read, Seek};
+
+use serde::{Deserialize, Serialize};
+
+use crate::reader::BigEndianReader;
+
+/// Highest event type Fallout 1 queues.
+pub const FALLOUT1_EVENT_TYPE_MAX: i32 = 12;
+/// Highest event type Fallout 2 queues; it adds ambient sound effects.
+pub const FALLOUT2_EVENT_TYPE_MAX: i32 = 13;
+
+const MAX_QUEUE_EVENT_COUNT: i32 = 10_000;
+
+/// One pending entry from the game's timed event queue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueEvent {
+    /// Game time (in ticks) at which the event fires.
+    pub time: u32,
+    pub object_id: i32,
+    #[serde(flatten)]
+    pub data: QueueEventData,
+}
+
+/// Event type plus its payload, as written by the queue write procs of
+/// fallout1-ce and fallout2-ce.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QueueEventData {
+    Drug {
+        stats: [i32; 3],
+        modifiers: [i32; 3],
+    },
+    Knockout,
+    Withdrawal {
+        /// 1 when the event starts withdrawal, 0 when it ends it.
+        start: i32,
+        item_pid: i32,
+        perk: i32,
+    },
+    Script {
+        sid: i32,
+        fixed_param: i32,
+    },
+    GameTime,
+    Poison,
+    Radiation {
+        level: i32,
+        is_healing: i32,
+    },
+    Flare,
+    Explosion,
+    ItemTrickle,
+    Sneak,
+    ExplosionFailure,
+    MapUpdate,
+    /// Fallout 2 only.
+    SoundEffect,
+}
+
+impl QueueEventData {
+    pub fn event_type(&self) -> i32 {
+        match self {
+            Self::Drug { .. } => 0,
+            Self::Knockout => 1,
+            Self::Withdrawal { .. } => 2,
+            Self::Script { .. } => 3,
+            Self::GameTime => 4,
+            Self::Poison => 5,
+            Self::Radiation { .. } => 6,
+            Self::Flare => 7,
+            Self::Explosion => 8,
+            Self::ItemTrickle => 9,
+            Self::Sneak => 10,
+            Self::ExplosionFailure => 11,
+            Self::MapUpdate => 12,
+            Self::SoundEffect => 13,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Drug { .. } => "drug",
+            Self::Knockout => "knockout",
+            Self::Withdrawal { .. } => "withdrawal",
+            Self::Script { .. } => "script",
+            Self::GameTime => "game_time",
+            Self::Poison => "poison",
+            Self::Radiation { .. } => "radiation",
+            Self::Flare => "flare",
+            Self::Explosion => "explosion",
+            Self::ItemTrickle => "item_trickle",
+            Self::Sneak => "sneak",
+            Self::ExplosionFailure => "explosion_failure",
+            Self::MapUpdate => "map_update",
+            Self::SoundEffect => "sound_effect",
+        }
+    }
+}
+
+/// Parse a saved queue whose event types run up to `event_type_max`.
+pub fn parse_event_queue<R: Read + Seek>(
+    r: &mut BigEndianReader<R>,
+    event_type_max: i32,
+) -> io::Result<Vec<QueueEvent>> {
+    let count = r.read_i32()?;
+    if !(0..=MAX_QUEUE_EVENT_COUNT).contains(&count) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid event queue count: {count}"),
+        ));
+    }
+
+    let mut events = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        // 12-byte header: time (4) + type (4) + objectId (4)
+        let time = r.read_u32()?;
+        let event_type = r.read_i32()?;
+        let object_id = r.read_i32()?;
+
+        // Queue payloads from the q_func readProc handlers; Fallout 2 shares
+        // Fallout 1's and only adds sound effects, which carry none.
+        if event_type > event_type_max {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown event type: {event_type}"),
+            ));
+        }
+        let data = match event_type {
+            0 => QueueEventData::Drug {
+                stats: r.read_i32_array::<3>()?,
+                modifiers: r.read_i32_array::<3>()?,
+            },
+            1 => QueueEventData::Knockout,
+            2 => QueueEventData::Withdrawal {
+                start: r.read_i32()?,
+                item_pid: r.read_i32()?,
+                perk: r.read_i32()?,
+            },
+            3 => QueueEventData::Script {
+                sid: r.read_i32()?,
+                fixed_param: r.read_i32()?,
+            },
+            4 => QueueEventData::GameTime,
+            5 => QueueEventData::Poison,
+            6 => QueueEventData::Radiation {
+                level: r.read_i32()?,
+                is_healing: r.read_i32()?,
+            },
+            7 => QueueEventData::Flare,
+            8 => QueueEventData::Explosion,
+            9 => QueueEventData::ItemTrickle,
+            10 => QueueEventData::Sneak,
+            11 => QueueEventData::ExplosionFailure,
+            12 => QueueEventData::MapUpdate,
+            13 => QueueEventData::SoundEffect,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unknown event type: {event_type}"),
+                ));
+            }
+        };
+
+        events.push(QueueEvent {
+            time,
+            object_id,
+            data,
+        });
+    }
+
+    Ok(events)
+}
+
+pub fn emit_event_queue(events: &[QueueEvent], out: &mut Vec<u8>) {
+    fn push_i32(out: &mut Vec<u8>, value: i32) {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+
+    push_i32(out, events.len() as i32);
+    for event in events {
+        out.extend_from_slice(&event.time.to_be_bytes());
+        push_i32(out, event.data.event_type());
+        push_i32(out, event.object_id);
+
+        match &event.data {
+            QueueEventData::Drug { stats, modifiers } => {
+                for &value in stats.iter().chain(modifiers) {
+                    push_i32(out, value);
+                }
+            }
+            QueueEventData::Withdrawal {
+                start,
+                item_pid,
+                perk,
+            } => {
+                push_i32(out, *start);
+                push_i32(out, *item_pid);
+                push_i32(out, *perk);
+            }
+            QueueEventData::Script { sid, fixed_param } => {
+                push_i32(out, *sid);
+                push_i32(out, *fixed_param);
+            }
+            QueueEventData::Radiation { level, is_healing } => {
+                push_i32(out, *level);
+                push_i32(out, *is_healing);
+            }
+            QueueEventData::Knockout
+            | QueueEventData::GameTime
+            | QueueEventData::Poison
+            | QueueEventData::Flare
+            | QueueEventData::Explosion
+            | QueueEventData::ItemTrickle
+            | QueueEventData::Sneak
+            | QueueEventData::ExplosionFailure
+            | QueueEventData::MapUpdate
+            | QueueEventData::SoundEffect => {}
+        }
+    }
+}
+
+/// Move every event by `delta` ticks, as the clock moves, holding fire times
+/// to the u32 range.
+pub fn shift_event_times(events: &mut [QueueEvent], delta: i64) {
+    for event in events {
+        event.time = (i64::from(event.time) + delta).clamp(0, i64::from(u32::MAX)) as u32;
+    }
+}
+
+pub fn check_queue_event_index(events: &[QueueEvent], index: usize) -> io::Result<()> {
+    let count = events.len();
+    if index >= count {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid queue event index {index}, expected 0..{count}"),
+        ));
+    }
+    Ok(())
+}
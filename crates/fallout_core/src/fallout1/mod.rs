@@ -3,22 +3,30 @@ pub mod object;
 pub mod sections;
 pub mod types;
 
+use std::collections::BTreeSet;
 use std::io::{self, Cursor, Read, Seek};
 
 use crate::common::blob_patching::SectionBlob;
-use crate::common::{blob_emission, blob_patching, layout_management};
+use crate::common::{blob_emission, blob_patching, game_calendar, layout_management};
+use crate::event_queue::{
+    FALLOUT1_EVENT_TYPE_MAX, QueueEvent, check_queue_event_index, emit_event_queue,
+    parse_event_queue, shift_event_times,
+};
 use crate::gender::Gender;
 use crate::layout::{ByteRange, FileLayout, SectionId, SectionLayout};
-use crate::object::GameObject;
+use crate::map_save::{ELEVATION_COUNT, HEX_GRID_SIZE};
+use crate::object::{GameObject, InventoryItem, ItemPrototype};
 use crate::reader::BigEndianReader;
 use header::SaveHeader;
 use sections::{
     CombatState, CritterProtoData, PcStats, parse_combat_state, parse_critter_proto,
     parse_game_global_vars, parse_kill_counts, parse_map_file_list, parse_pc_stats, parse_perks,
     parse_player_combat_id, parse_player_object, parse_tagged_skills, parse_traits,
-    skip_event_queue,
 };
-use types::{KILL_TYPE_COUNT, PERK_COUNT, SAVEABLE_STAT_COUNT, SKILL_COUNT, TAGGED_SKILL_COUNT};
+use types::{
+    GAME_CALENDAR_EPOCH, KILL_TYPE_COUNT, PERK_COUNT, PREVIEW_SIZE, SAVEABLE_STAT_COUNT,
+    SKILL_COUNT, TAGGED_SKILL_COUNT, TRAIT_GIFTED, TRAIT_SKILLED,
+};
 
 const STAT_STRENGTH: usize = 0;
 const STAT_PERCEPTION: usize = 1;
@@ -28,6 +36,20 @@ const STAT_INTELLIGENCE: usize = 4;
 const STAT_AGILITY: usize = 5;
 const STAT_LUCK: usize = 6;
 const STAT_INVALID: i32 = -1;
+const STAT_MAXIMUM_HIT_POINTS: usize = 7;
+const STAT_MAXIMUM_ACTION_POINTS: usize = 8;
+const STAT_ARMOR_CLASS: usize = 9;
+const STAT_MELEE_DAMAGE: usize = 11;
+const STAT_CARRY_WEIGHT: usize = 12;
+const STAT_SEQUENCE: usize = 13;
+const STAT_HEALING_RATE: usize = 14;
+const STAT_CRITICAL_CHANCE: usize = 15;
+const STAT_BETTER_CRITICALS: usize = 16;
+const STAT_RADIATION_RESISTANCE: usize = 31;
+const STAT_POISON_RESISTANCE: usize = 32;
+const DERIVED_STAT_COUNT: usize = 11;
+const SPECIAL_MIN: i32 = 1;
+const SPECIAL_MAX: i32 = 10;
 
 const STAT_AGE_INDEX: usize = 33;
 const STAT_GENDER_INDEX: usize = 34;
@@ -36,8 +58,19 @@ const HEADER_CHARACTER_NAME_OFFSET: usize = 24 + HEADER_VERSION_BLOCK_LEN;
 const HEADER_CHARACTER_NAME_LEN: usize = 32;
 const HEADER_DESCRIPTION_OFFSET: usize = HEADER_CHARACTER_NAME_OFFSET + HEADER_CHARACTER_NAME_LEN;
 const HEADER_DESCRIPTION_LEN: usize = 30;
+// File date (day, month, year: i16; time: i32) precedes the game date.
+const HEADER_GAME_DATE_OFFSET: usize =
+    HEADER_DESCRIPTION_OFFSET + HEADER_DESCRIPTION_LEN + 3 * 2 + 4;
+const HEADER_GAME_TIME_OFFSET: usize = HEADER_GAME_DATE_OFFSET + 3 * 2;
+const HEADER_ELEVATION_OFFSET: usize = HEADER_GAME_TIME_OFFSET + 4;
+const HEADER_MAP_OFFSET: usize = HEADER_ELEVATION_OFFSET + 2;
+const HEADER_MAP_FILENAME_OFFSET: usize = HEADER_MAP_OFFSET + 2;
+const HEADER_MAP_FILENAME_LEN: usize = 16;
+const HEADER_PREVIEW_OFFSET: usize = HEADER_MAP_FILENAME_OFFSET + HEADER_MAP_FILENAME_LEN;
 const CRITTER_PROTO_BASE_STATS_OFFSET: usize = 8;
 const I32_WIDTH: usize = 4;
+const CRITTER_PROTO_BONUS_STATS_OFFSET: usize =
+    CRITTER_PROTO_BASE_STATS_OFFSET + SAVEABLE_STAT_COUNT * I32_WIDTH;
 const CRITTER_PROTO_SKILLS_OFFSET: usize = CRITTER_PROTO_BASE_STATS_OFFSET
     + SAVEABLE_STAT_COUNT * I32_WIDTH
     + SAVEABLE_STAT_COUNT * I32_WIDTH;
@@ -76,9 +109,9 @@ const SKILL_SPEECH: usize = 14;
 const SKILL_BARTER: usize = 15;
 
 // Trait indices
+const TRAIT_BRUISER: i32 = 1;
+const TRAIT_SMALL_FRAME: i32 = 2;
 const TRAIT_GOOD_NATURED: i32 = 10;
-const TRAIT_SKILLED: i32 = 14;
-const TRAIT_GIFTED: i32 = 15;
 
 // Perk indices
 const PERK_MR_FIXIT: usize = 31;
@@ -125,7 +158,9 @@ pub struct SaveGame {
     pub header: SaveHeader,
     pub player_combat_id: i32,
     pub global_var_count: usize,
+    pub global_vars: Vec<i32>,
     pub map_files: Vec<String>,
+    pub automap_size: i32,
     pub player_object: GameObject,
     pub center_tile: i32,
     pub critter_data: CritterProtoData,
@@ -135,6 +170,9 @@ pub struct SaveGame {
     pub perks: [i32; PERK_COUNT],
     pub combat_state: CombatState,
     pub pc_stats: PcStats,
+    /// Handler 15 event queue, or `None` when handlers 14-16 could not be
+    /// decoded and were left as raw bytes.
+    pub queue_events: Option<Vec<QueueEvent>>,
     pub selected_traits: [i32; 2],
 }
 
@@ -194,6 +232,60 @@ impl SaveGame {
         bonus
     }
 
+    /// Derived stats as the engine recomputes them from SPECIAL. Max HP uses
+    /// base Strength and Endurance with trait modifiers; the others use the
+    /// full SPECIAL totals.
+    pub fn derived_base_stats(&self) -> [(usize, i32); DERIVED_STAT_COUNT] {
+        let strength = self.special_total(STAT_STRENGTH);
+        let perception = self.special_total(STAT_PERCEPTION);
+        let endurance = self.special_total(STAT_ENDURANCE);
+        let agility = self.special_total(STAT_AGILITY);
+        let luck = self.special_total(STAT_LUCK);
+        let max_hp = self.base_stat_with_traits(STAT_STRENGTH)
+            + 2 * self.base_stat_with_traits(STAT_ENDURANCE)
+            + 15;
+
+        [
+            (STAT_MAXIMUM_HIT_POINTS, max_hp),
+            (STAT_MAXIMUM_ACTION_POINTS, agility / 2 + 5),
+            (STAT_ARMOR_CLASS, agility),
+            (STAT_MELEE_DAMAGE, (strength - 5).max(1)),
+            (STAT_CARRY_WEIGHT, 25 * strength + 25),
+            (STAT_SEQUENCE, 2 * perception),
+            (STAT_HEALING_RATE, (endurance / 3).max(1)),
+            (STAT_CRITICAL_CHANCE, luck),
+            (STAT_BETTER_CRITICALS, 0),
+            (STAT_RADIATION_RESISTANCE, 2 * endurance),
+            (STAT_POISON_RESISTANCE, 5 * endurance),
+        ]
+    }
+
+    /// Base SPECIAL value with trait modifiers applied, before any bonus.
+    pub fn base_stat_with_traits(&self, stat_index: usize) -> i32 {
+        self.critter_data.base_stats[stat_index] + self.trait_stat_modifier(stat_index)
+    }
+
+    /// SPECIAL value the game uses: base with traits plus the bonuses from
+    /// armor, drugs and items, held to 1..=10.
+    pub fn special_total(&self, stat_index: usize) -> i32 {
+        (self.base_stat_with_traits(stat_index) + self.critter_data.bonus_stats[stat_index])
+            .clamp(SPECIAL_MIN, SPECIAL_MAX)
+    }
+
+    fn trait_stat_modifier(&self, stat_index: usize) -> i32 {
+        let mut modifier = 0;
+        if self.has_trait(TRAIT_GIFTED) {
+            modifier += 1;
+        }
+        if stat_index == STAT_STRENGTH && self.has_trait(TRAIT_BRUISER) {
+            modifier += 2;
+        }
+        if stat_index == STAT_AGILITY && self.has_trait(TRAIT_SMALL_FRAME) {
+            modifier += 1;
+        }
+        modifier
+    }
+
     fn total_stat(&self, stat_index: usize) -> i32 {
         self.critter_data.base_stats[stat_index] + self.critter_data.bonus_stats[stat_index]
     }
@@ -239,10 +331,8 @@ impl SaveGame {
         let mut modifier = 0;
 
         match skill_index {
-            SKILL_FIRST_AID | SKILL_DOCTOR => {
-                if self.has_perk_rank(PERK_MEDIC) {
-                    modifier += 20;
-                }
+            SKILL_FIRST_AID | SKILL_DOCTOR if self.has_perk_rank(PERK_MEDIC) => {
+                modifier += 20;
             }
             SKILL_SNEAK => {
                 // Ghost perk depends on light level at runtime; skip it for save files.
@@ -251,20 +341,14 @@ impl SaveGame {
                     modifier += 10;
                 }
             }
-            SKILL_LOCKPICK | SKILL_STEAL | SKILL_TRAPS => {
-                if self.has_perk_rank(PERK_MASTER_THIEF) {
-                    modifier += 10;
-                }
+            SKILL_LOCKPICK | SKILL_STEAL | SKILL_TRAPS if self.has_perk_rank(PERK_MASTER_THIEF) => {
+                modifier += 10;
             }
-            SKILL_SCIENCE | SKILL_REPAIR => {
-                if self.has_perk_rank(PERK_MR_FIXIT) {
-                    modifier += 20;
-                }
+            SKILL_SCIENCE | SKILL_REPAIR if self.has_perk_rank(PERK_MR_FIXIT) => {
+                modifier += 20;
             }
-            SKILL_SPEECH | SKILL_BARTER => {
-                if self.has_perk_rank(PERK_SPEAKER) {
-                    modifier += 20;
-                }
+            SKILL_SPEECH | SKILL_BARTER if self.has_perk_rank(PERK_SPEAKER) => {
+                modifier += 20;
             }
             _ => {}
         }
@@ -383,6 +467,28 @@ impl Document {
         Ok(())
     }
 
+    pub fn set_radiation(&mut self, radiation: i32) -> io::Result<()> {
+        check_non_negative(radiation, "radiation")?;
+        self.player_critter_data_mut()?.radiation = radiation;
+        self.rewrite_handler5_from_player_object()
+    }
+
+    pub fn set_poison(&mut self, poison: i32) -> io::Result<()> {
+        check_non_negative(poison, "poison")?;
+        self.player_critter_data_mut()?.poison = poison;
+        self.rewrite_handler5_from_player_object()
+    }
+
+    fn player_critter_data_mut(&mut self) -> io::Result<&mut object::CritterObjectData> {
+        match &mut self.save.player_object.object_data {
+            object::ObjectData::Critter(data) => Ok(data),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "player object has no critter data",
+            )),
+        }
+    }
+
     pub fn set_character_name(&mut self, value: &str) -> io::Result<()> {
         let blob = self.section_blob_mut(SectionId::Header)?;
         blob_patching::patch_fixed_string_in_blob(
@@ -397,6 +503,52 @@ impl Document {
         Ok(())
     }
 
+    /// Replace the palettized load-menu thumbnail.
+    pub fn set_preview(&mut self, preview: &[u8]) -> io::Result<()> {
+        if preview.len() != PREVIEW_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "preview is {} bytes, expected {PREVIEW_SIZE}",
+                    preview.len()
+                ),
+            ));
+        }
+
+        let blob = self.section_blob_mut(SectionId::Header)?;
+        blob_patching::patch_bytes_in_blob(
+            blob,
+            HEADER_PREVIEW_OFFSET,
+            preview,
+            "header",
+            "preview",
+        )?;
+        self.save.header.preview = preview.to_vec();
+        Ok(())
+    }
+
+    /// Record the uncompressed size of the slot's `AUTOMAP.SAV`, stored at
+    /// the end of handler 3.
+    pub fn set_automap_size(&mut self, size: i32) -> io::Result<()> {
+        if size < 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid automap size {size}"),
+            ));
+        }
+
+        let blob = self.section_blob_mut(SectionId::Handler(3))?;
+        let offset = blob.bytes.len().checked_sub(I32_WIDTH).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "handler 3 too short for automap size",
+            )
+        })?;
+        blob_patching::patch_i32_in_blob(blob, offset, size, "handler 3", "automap size")?;
+        self.save.automap_size = size;
+        Ok(())
+    }
+
     pub fn set_description(&mut self, value: &str) -> io::Result<()> {
         let blob = self.section_blob_mut(SectionId::Header)?;
         blob_patching::patch_fixed_string_in_blob(
@@ -411,6 +563,121 @@ impl Document {
         Ok(())
     }
 
+    /// Move the game clock to `game_time` ticks, rewriting the header's game
+    /// date to match. Pending queue events keep their distance from the
+    /// current time, so timed effects and deadlines move with the clock. An
+    /// undecoded queue cannot be moved, so the clock is left alone too.
+    pub fn set_game_time(&mut self, game_time: u32) -> io::Result<()> {
+        let delta = i64::from(game_time) - i64::from(self.save.header.game_time);
+        let queue_events = self.queue_events_mut()?;
+        if !queue_events.is_empty() {
+            shift_event_times(queue_events, delta);
+            self.rewrite_handler15_from_queue_events()?;
+        }
+
+        let (month, day, year) = game_calendar::game_date(GAME_CALENDAR_EPOCH, game_time);
+        let blob = self.section_blob_mut(SectionId::Header)?;
+        for (index, (value, field)) in [
+            (month, "game_month"),
+            (day, "game_day"),
+            (year, "game_year"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            blob_patching::patch_i16_in_blob(
+                blob,
+                HEADER_GAME_DATE_OFFSET + index * 2,
+                value,
+                "header",
+                field,
+            )?;
+        }
+        blob_patching::patch_i32_in_blob(
+            blob,
+            HEADER_GAME_TIME_OFFSET,
+            game_time as i32,
+            "header",
+            "game_time",
+        )?;
+
+        let header = &mut self.save.header;
+        header.game_month = month;
+        header.game_day = day;
+        header.game_year = year;
+        header.game_time = game_time;
+        Ok(())
+    }
+
+    /// Put the player on `tile` of `elevation` in the map saved as
+    /// `map_file`, which must be one of the slot's map saves (`map_files`).
+    /// The view is centred on the new tile.
+    pub fn set_player_location(
+        &mut self,
+        map_id: i16,
+        map_file: &str,
+        elevation: i16,
+        tile: i32,
+    ) -> io::Result<()> {
+        let Some(map_file) = self
+            .save
+            .map_files
+            .iter()
+            .find(|name| name.eq_ignore_ascii_case(map_file))
+        else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("map {map_file} is not one of the saved maps"),
+            ));
+        };
+        if !(0..ELEVATION_COUNT as i16).contains(&elevation) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "invalid elevation {elevation}, expected 0..{}",
+                    ELEVATION_COUNT - 1
+                ),
+            ));
+        }
+        if !(0..HEX_GRID_SIZE).contains(&tile) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid tile {tile}, expected 0..{}", HEX_GRID_SIZE - 1),
+            ));
+        }
+        // The header names the map with a lowercase extension, e.g. `ARCAVES.sav`.
+        let stem = map_file
+            .rsplit_once('.')
+            .map_or(map_file.as_str(), |(stem, _)| stem);
+        let map_filename = format!("{stem}.sav");
+
+        let blob = self.section_blob_mut(SectionId::Header)?;
+        blob_patching::patch_i16_in_blob(
+            blob,
+            HEADER_ELEVATION_OFFSET,
+            elevation,
+            "header",
+            "elevation",
+        )?;
+        blob_patching::patch_i16_in_blob(blob, HEADER_MAP_OFFSET, map_id, "header", "map")?;
+        blob_patching::patch_fixed_string_in_blob(
+            blob,
+            HEADER_MAP_FILENAME_OFFSET,
+            HEADER_MAP_FILENAME_LEN,
+            &map_filename,
+            "header",
+            "map_filename",
+        )?;
+        self.save.header.elevation = elevation;
+        self.save.header.map = map_id;
+        self.save.header.map_filename = map_filename;
+
+        self.save.player_object.tile = tile;
+        self.save.player_object.elevation = i32::from(elevation);
+        self.save.center_tile = tile;
+        self.rewrite_handler5_from_player_object()
+    }
+
     pub fn set_base_stat(&mut self, stat_index: usize, value: i32) -> io::Result<()> {
         let offset = CRITTER_PROTO_BASE_STATS_OFFSET + stat_index * I32_WIDTH;
         self.patch_handler6_i32(offset, value, &format!("stat {stat_index}"))?;
@@ -418,6 +685,24 @@ impl Document {
         Ok(())
     }
 
+    pub fn set_bonus_stat(&mut self, stat_index: usize, value: i32) -> io::Result<()> {
+        check_bonus_stat_index(stat_index)?;
+        let offset = CRITTER_PROTO_BONUS_STATS_OFFSET + stat_index * I32_WIDTH;
+        self.patch_handler6_i32(offset, value, &format!("bonus stat {stat_index}"))?;
+        self.save.critter_data.bonus_stats[stat_index] = value;
+        Ok(())
+    }
+
+    /// Rewrite the derived base stats that differ from what SPECIAL implies.
+    pub fn recalculate_derived_stats(&mut self) -> io::Result<()> {
+        for (stat_index, value) in self.save.derived_base_stats() {
+            if self.save.critter_data.base_stats[stat_index] != value {
+                self.set_base_stat(stat_index, value)?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn set_age(&mut self, age: i32) -> io::Result<()> {
         self.patch_handler6_i32(CRITTER_PROTO_AGE_OFFSET, age, "age")?;
         self.save.critter_data.base_stats[STAT_AGE_INDEX] = age;
@@ -485,6 +770,27 @@ impl Document {
         Ok(())
     }
 
+    pub fn set_global_var(&mut self, index: usize, value: i32) -> io::Result<()> {
+        if index >= self.save.global_vars.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "invalid global var index {index}, expected 0..{}",
+                    self.save.global_vars.len().saturating_sub(1)
+                ),
+            ));
+        }
+
+        // Handler 4 is a second copy of handler 2; the game reads both, so keep them identical.
+        let offset = index * I32_WIDTH;
+        for (handler, label) in [(2, "handler 2"), (4, "handler 4")] {
+            let blob = self.section_blob_mut(SectionId::Handler(handler))?;
+            blob_patching::patch_i32_in_blob(blob, offset, value, label, "global var")?;
+        }
+        self.save.global_vars[index] = value;
+        Ok(())
+    }
+
     pub fn set_trait(&mut self, slot: usize, trait_index: i32) -> io::Result<()> {
         if slot >= self.save.selected_traits.len() {
             return Err(io::Error::new(
@@ -554,6 +860,81 @@ impl Document {
         self.set_perk_rank(perk_index, 0)
     }
 
+    pub fn set_kill_count(&mut self, kill_type: usize, count: i32) -> io::Result<()> {
+        if kill_type >= KILL_TYPE_COUNT {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "invalid kill type {kill_type}, expected 0..{}",
+                    KILL_TYPE_COUNT - 1
+                ),
+            ));
+        }
+        if count < 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid kill count {count}, expected >= 0"),
+            ));
+        }
+
+        let offset = kill_type * I32_WIDTH;
+        let blob = self.section_blob_mut(SectionId::Handler(7))?;
+        blob_patching::patch_i32_in_blob(blob, offset, count, "handler 7", "kill count")?;
+        self.save.kill_counts[kill_type] = count;
+        Ok(())
+    }
+
+    /// Replace the tagged skills. Three tags are allowed, or four when the
+    /// Tag! perk is held; the fourth slot is the one the perk granted.
+    pub fn set_tagged_skills(&mut self, skills: &[usize]) -> io::Result<()> {
+        let limit = if self.save.has_perk_rank(PERK_TAG) {
+            TAGGED_SKILL_COUNT
+        } else {
+            TAGGED_SKILL_COUNT - 1
+        };
+        if skills.len() > limit {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "cannot tag {} skills, at most {limit} allowed",
+                    skills.len()
+                ),
+            ));
+        }
+        let mut tagged = [-1; TAGGED_SKILL_COUNT];
+        for (slot, &skill_index) in skills.iter().enumerate() {
+            if skill_index >= SKILL_COUNT {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "invalid skill index {skill_index}, expected 0..{}",
+                        SKILL_COUNT - 1
+                    ),
+                ));
+            }
+            if skills[..slot].contains(&skill_index) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("skill {skill_index} is tagged more than once"),
+                ));
+            }
+            tagged[slot] = skill_index as i32;
+        }
+
+        let blob = self.section_blob_mut(SectionId::Handler(8))?;
+        for (slot, &value) in tagged.iter().enumerate() {
+            blob_patching::patch_i32_in_blob(
+                blob,
+                slot * I32_WIDTH,
+                value,
+                "handler 8",
+                "tagged skill",
+            )?;
+        }
+        self.save.tagged_skills = tagged;
+        Ok(())
+    }
+
     pub fn set_inventory_quantity(&mut self, pid: i32, quantity: i32) -> io::Result<()> {
         if quantity < 0 {
             return Err(io::Error::new(
@@ -619,10 +1000,110 @@ impl Document {
         if !found {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
-                format!("cannot add new inventory pid={pid}: no existing template item in save"),
+                format!(
+                    "cannot add new inventory pid={pid}: item is not in inventory and no item prototype was provided"
+                ),
+            ));
+        }
+
+        self.rewrite_handler5_from_player_object()
+    }
+
+    /// Add `quantity` of an item, creating a new inventory object from `proto`
+    /// when the player does not already carry that PID. The new object's id
+    /// is above both the player's ids and `id_floor`, the highest id in use
+    /// elsewhere in the slot.
+    pub fn add_inventory_item_from_prototype(
+        &mut self,
+        proto: &ItemPrototype,
+        quantity: i32,
+        id_floor: i32,
+    ) -> io::Result<()> {
+        let pid = proto.pid;
+        if self
+            .save
+            .player_object
+            .inventory
+            .iter()
+            .any(|item| item.object.pid == pid)
+        {
+            return self.add_inventory_item(pid, quantity);
+        }
+        if quantity <= 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid inventory quantity {quantity}, expected > 0"),
             ));
         }
 
+        let id = self
+            .save
+            .player_object
+            .max_object_id()
+            .max(id_floor)
+            .checked_add(1)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "no free object id available for new inventory item",
+                )
+            })?;
+        let object = GameObject::new_item(id, proto)?;
+        // The engine inserts newly added items at the front of the inventory.
+        self.save
+            .player_object
+            .inventory
+            .insert(0, InventoryItem { quantity, object });
+
+        self.rewrite_handler5_from_player_object()
+    }
+
+    pub fn equip_inventory_item(&mut self, slot_flag: i32, pid: i32) -> io::Result<()> {
+        object::equip_inventory_item(&mut self.save.player_object, slot_flag, pid)?;
+        self.rewrite_handler5_from_player_object()
+    }
+
+    /// Clear an equipment slot, returning the PID of the item that was in it.
+    pub fn unequip_inventory_item(&mut self, slot_flag: i32) -> io::Result<Option<i32>> {
+        let pid = object::unequip_inventory_item(&mut self.save.player_object.inventory, slot_flag);
+        if pid.is_some() {
+            self.rewrite_handler5_from_player_object()?;
+        }
+        Ok(pid)
+    }
+
+    /// Set the loaded ammo type and round count of every stack of weapon `pid`.
+    pub fn set_weapon_ammo(&mut self, pid: i32, ammo_pid: i32, rounds: i32) -> io::Result<()> {
+        object::set_weapon_ammo(
+            &mut self.save.player_object.inventory,
+            pid,
+            ammo_pid,
+            rounds,
+        )?;
+        self.rewrite_handler5_from_player_object()
+    }
+
+    /// Detach an item stack (or part of one) from the player so it can be
+    /// placed elsewhere. Equipped items must be unequipped first.
+    pub fn take_inventory_object(
+        &mut self,
+        pid: i32,
+        quantity: Option<i32>,
+    ) -> io::Result<InventoryItem> {
+        let item = object::take_inventory_item(
+            &mut self.save.player_object.inventory,
+            pid,
+            quantity,
+            false,
+        )?;
+        self.rewrite_handler5_from_player_object()?;
+        Ok(item)
+    }
+
+    pub fn insert_inventory_object(&mut self, item: InventoryItem) -> io::Result<()> {
+        let mut used_ids = BTreeSet::new();
+        self.save.player_object.collect_object_ids(&mut used_ids);
+        object::insert_inventory_item(&mut self.save.player_object.inventory, item, &used_ids)?;
         self.rewrite_handler5_from_player_object()
     }
 
@@ -676,6 +1157,64 @@ impl Document {
         self.rewrite_handler5_from_player_object()
     }
 
+    pub fn remove_queue_event(&mut self, index: usize) -> io::Result<QueueEvent> {
+        let queue_events = self.queue_events_mut()?;
+        check_queue_event_index(queue_events, index)?;
+        let removed = queue_events.remove(index);
+        self.rewrite_handler15_from_queue_events()?;
+        Ok(removed)
+    }
+
+    /// Move an event to a new fire time. The engine only pops events from the
+    /// head of its time-ordered list, so the queue is re-sorted afterwards.
+    pub fn set_queue_event_time(&mut self, index: usize, time: u32) -> io::Result<()> {
+        let queue_events = self.queue_events_mut()?;
+        check_queue_event_index(queue_events, index)?;
+        queue_events[index].time = time;
+        queue_events.sort_by_key(|event| event.time);
+        self.rewrite_handler15_from_queue_events()
+    }
+
+    /// Drop every pending event `remove` selects, returning them in queue
+    /// order.
+    pub fn remove_queue_events_where<F>(&mut self, mut remove: F) -> io::Result<Vec<QueueEvent>>
+    where
+        F: FnMut(&QueueEvent) -> bool,
+    {
+        let queue_events = self.queue_events_mut()?;
+        let (removed, kept) = std::mem::take(queue_events)
+            .into_iter()
+            .partition(|event| remove(event));
+        *queue_events = kept;
+        if !removed.is_empty() {
+            self.rewrite_handler15_from_queue_events()?;
+        }
+        Ok(removed)
+    }
+
+    fn queue_events_mut(&mut self) -> io::Result<&mut Vec<QueueEvent>> {
+        self.save.queue_events.as_mut().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "event queue (handler 15) was not decoded",
+            )
+        })
+    }
+
+    fn rewrite_handler15_from_queue_events(&mut self) -> io::Result<()> {
+        let mut blob = Vec::new();
+        emit_event_queue(
+            self.save.queue_events.as_deref().unwrap_or_default(),
+            &mut blob,
+        );
+        layout_management::replace_section_blob(
+            &mut self.section_blobs,
+            &mut self.layout,
+            SectionId::Handler(15),
+            blob,
+        )
+    }
+
     fn patch_handler6_i32(&mut self, offset: usize, raw: i32, field: &str) -> io::Result<()> {
         let blob = self.section_blob_mut(SectionId::Handler(6))?;
         blob_patching::patch_i32_in_blob(blob, offset, raw, "handler 6", field)
@@ -729,10 +1268,33 @@ impl Document {
     }
 }
 
+fn check_non_negative(value: i32, field: &str) -> io::Result<()> {
+    if value < 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid {field} level {value}, expected >= 0"),
+        ));
+    }
+    Ok(())
+}
+
+fn check_bonus_stat_index(stat_index: usize) -> io::Result<()> {
+    if stat_index >= SAVEABLE_STAT_COUNT {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "invalid stat index {stat_index}, expected 0..{}",
+                SAVEABLE_STAT_COUNT - 1
+            ),
+        ));
+    }
+    Ok(())
+}
+
 fn parse_handlers_14_to_16<R: Read + Seek>(
     r: &mut BigEndianReader<R>,
     capture: &mut Option<&mut Capture<'_>>,
-) -> io::Result<[i32; 2]> {
+) -> io::Result<(Vec<QueueEvent>, [i32; 2])> {
     // Handler 14: no-op (0 bytes)
     let h14_pos = r.position()? as usize;
     if let Some(c) = capture.as_deref_mut() {
@@ -741,7 +1303,7 @@ fn parse_handlers_14_to_16<R: Read + Seek>(
 
     // Handler 15: event queue (variable)
     let h15_start = r.position()? as usize;
-    skip_event_queue(r)?;
+    let queue_events = parse_event_queue(r, FALLOUT1_EVENT_TYPE_MAX)?;
     let h15_end = r.position()? as usize;
     if let Some(c) = capture.as_deref_mut() {
         c.record(SectionId::Handler(15), h15_start, h15_end);
@@ -755,7 +1317,7 @@ fn parse_handlers_14_to_16<R: Read + Seek>(
         c.record(SectionId::Handler(16), h16_start, h16_end);
     }
 
-    Ok(traits)
+    Ok((queue_events, traits))
 }
 
 fn parse_internal<R: Read + Seek>(
@@ -873,18 +1435,19 @@ fn parse_internal<R: Read + Seek>(
         c.record(SectionId::Handler(13), h13_start, h13_end);
     }
 
-    // Handlers 14-16: try to parse traits; fall back to [-1, -1] on failure.
+    // Handlers 14-16: try to parse the event queue and traits; on failure
+    // leave them undecoded in the tail with no queue and traits [-1, -1].
     let pre_traits_pos = r.position()?;
     let pre_traits_capture_len = capture.as_deref().map(|c| c.sections.len());
-    let selected_traits = match parse_handlers_14_to_16(r, &mut capture) {
-        Ok(traits) => traits,
+    let (queue_events, selected_traits) = match parse_handlers_14_to_16(r, &mut capture) {
+        Ok((queue_events, traits)) => (Some(queue_events), traits),
         Err(_) => {
             r.seek_to(pre_traits_pos)?;
             if let (Some(c), Some(len)) = (capture, pre_traits_capture_len) {
                 c.sections.truncate(len);
                 c.blobs.truncate(len);
             }
-            [-1, -1]
+            (None, [-1, -1])
         }
     };
 
@@ -892,7 +1455,9 @@ fn parse_internal<R: Read + Seek>(
         header,
         player_combat_id,
         global_var_count,
+        global_vars: globals.global_vars,
         map_files: map_list.map_files,
+        automap_size: map_list.automap_size,
         player_object: player_section.player_object,
         center_tile: player_section.center_tile,
         critter_data,
@@ -902,6 +1467,7 @@ fn parse_internal<R: Read + Seek>(
         perks,
         combat_state,
         pc_stats,
+        queue_events,
         selected_traits,
     })
 }
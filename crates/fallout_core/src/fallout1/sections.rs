@@ -254,52 +254,6 @@ pub fn parse_pc_stats<R: Read + Seek>(r: &mut BigEndianReader<R>) -> io::Result<
     })
 }
 
-// --- Handler 15: Event Queue ---
-
-/// Skip the event queue. Returns Ok(()) if successfully parsed and skipped.
-pub fn skip_event_queue<R: Read + Seek>(r: &mut BigEndianReader<R>) -> io::Result<()> {
-    let count = r.read_i32()?;
-    if !(0..=10_000).contains(&count) {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!("invalid event queue count: {count}"),
-        ));
-    }
-
-    for _ in 0..count {
-        // 12-byte header: time (4) + type (4) + objectId (4)
-        let _time = r.read_i32()?;
-        let event_type = r.read_i32()?;
-        let _object_id = r.read_i32()?;
-
-        // Fallout 1 queue payload sizes from fallout1-ce q_func readProc handlers.
-        let extra_bytes: u64 = match event_type {
-            0 => 24, // DrugEffectEvent: stats[3] + modifiers[3]
-            1 => 0,  // Knockout
-            2 => 12, // WithdrawalEvent: field_0 + pid + perk
-            3 => 8,  // ScriptEvent: sid + fixedParam
-            4 => 0,  // Game time
-            5 => 0,  // Poison
-            6 => 8,  // RadiationEvent: radiationLevel + isHealing
-            7 => 0,  // Flare
-            8 => 0,  // Explosion
-            9 => 0,  // Item trickle
-            10 => 0, // Sneak
-            11 => 0, // Explosion failure
-            12 => 0, // Map update event
-            _ => {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    format!("unknown event type: {event_type}"),
-                ));
-            }
-        };
-        r.skip(extra_bytes)?;
-    }
-
-    Ok(())
-}
-
 // --- Handler 16: Traits ---
 
 pub fn parse_traits<R: Read + Seek>(r: &mut BigEndianReader<R>) -> io::Result<[i32; 2]> {
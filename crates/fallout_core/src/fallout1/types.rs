@@ -1,3 +1,6 @@
+use crate::common::game_calendar::CalendarEpoch;
+use crate::perk::{PerkEffect, PerkInfo};
+
 // Save file constants
 pub const SIGNATURE: &[u8] = b"FALLOUT SAVE FILE";
 pub const PREVIEW_SIZE: usize = 29_792; // 224x133 thumbnail
@@ -9,6 +12,21 @@ pub const PERK_COUNT: usize = 64;
 pub const KILL_TYPE_COUNT: usize = 15;
 pub const PC_STAT_COUNT: usize = 5;
 pub const TAGGED_SKILL_COUNT: usize = 4;
+pub const PC_LEVEL_MAX: i32 = 21;
+
+// Perk and trait indices that feed the level-up rules.
+pub const PERK_EDUCATED: usize = 18;
+pub const PERK_LIFEGIVER: usize = 28;
+pub const PERK_TAG: usize = 51;
+pub const TRAIT_SKILLED: i32 = 14;
+pub const TRAIT_GIFTED: i32 = 15;
+
+/// The game clock starts on 5 December 2161.
+pub const GAME_CALENDAR_EPOCH: CalendarEpoch = CalendarEpoch {
+    year: 2161,
+    month: 11,
+    day: 4,
+};
 
 // Object type and PID utilities — re-exported from shared module
 pub use crate::object::{OBJ_TYPE_CRITTER, OBJ_TYPE_ITEM, OBJ_TYPE_MISC, obj_type_from_pid};
@@ -158,6 +176,111 @@ pub const PERK_NAMES: [&str; PERK_COUNT] = [
     "Combat Armor",
 ];
 
+const SKILL_SMALL_GUNS: usize = 0;
+const SKILL_UNARMED: usize = 3;
+const SKILL_FIRST_AID: usize = 6;
+const SKILL_DOCTOR: usize = 7;
+const SKILL_SNEAK: usize = 8;
+const SKILL_LOCKPICK: usize = 9;
+const SKILL_STEAL: usize = 10;
+const SKILL_SCIENCE: usize = 12;
+const SKILL_REPAIR: usize = 13;
+const SKILL_SPEECH: usize = 14;
+const SKILL_BARTER: usize = 15;
+const SKILL_OUTDOORSMAN: usize = 17;
+
+/// Perk selection rules, indexed like `PERK_NAMES`. SPECIAL requirements are
+/// in ST, PE, EN, CH, IN, AG, LK order.
+#[rustfmt::skip]
+pub const PERK_INFO: [PerkInfo; PERK_COUNT] = [
+    PerkInfo::selectable(1, 3, [0, 5, 0, 0, 0, 0, 0]),  // Awareness
+    PerkInfo::selectable(1, 15, [0, 0, 0, 0, 0, 6, 0]), // Bonus HtH Attacks
+    PerkInfo::selectable(3, 3, [6, 0, 0, 0, 0, 6, 0]),  // Bonus HtH Damage
+    PerkInfo::selectable(2, 6, [0, 0, 0, 0, 0, 5, 0]),  // Bonus Move
+    PerkInfo::selectable(2, 6, [0, 0, 0, 0, 0, 6, 6]),  // Bonus Ranged Damage
+    PerkInfo::selectable(1, 12, [0, 6, 0, 0, 6, 7, 0]), // Bonus Rate of Fire
+    PerkInfo::selectable(3, 3, [0, 6, 0, 0, 0, 0, 0]),  // Earlier Sequence
+    PerkInfo::selectable(3, 3, [0, 0, 6, 0, 0, 0, 0]),  // Faster Healing
+    PerkInfo::selectable(3, 6, [0, 0, 0, 0, 0, 0, 6]),  // More Criticals
+    PerkInfo::selectable(3, 3, [0, 6, 0, 0, 0, 0, 0]),  // Night Vision
+    PerkInfo::selectable(3, 3, [0, 0, 0, 6, 0, 0, 0]),  // Presence
+    PerkInfo::selectable(3, 6, [0, 0, 6, 0, 4, 0, 0]),  // Rad Resistance
+    PerkInfo::selectable(3, 3, [0, 0, 6, 0, 0, 0, 6]),  // Toughness
+    PerkInfo::selectable(3, 3, [6, 0, 6, 0, 0, 0, 0]),  // Strong Back
+    PerkInfo::selectable(2, 9, [0, 7, 0, 0, 6, 0, 0]),  // Sharpshooter
+    PerkInfo::selectable(1, 6, [0, 0, 0, 0, 0, 6, 0]).with_skills(&[(SKILL_SNEAK, 50)]), // Silent Running
+    PerkInfo::selectable(3, 3, [0, 0, 6, 0, 6, 0, 0]).with_skills(&[(SKILL_OUTDOORSMAN, 40)]), // Survivalist
+    PerkInfo::selectable(1, 12, [0, 0, 0, 7, 0, 0, 0]).with_skills(&[(SKILL_BARTER, 60)]), // Master Trader
+    PerkInfo::selectable(3, 6, [0, 0, 0, 0, 6, 0, 0]),  // Educated
+    PerkInfo::selectable(3, 3, [0, 7, 0, 0, 5, 6, 0]).with_skills(&[(SKILL_FIRST_AID, 40)]), // Healer
+    PerkInfo::selectable(1, 6, [0, 0, 0, 0, 0, 0, 8]),  // Fortune Finder
+    PerkInfo::selectable(1, 9, [0, 6, 0, 0, 0, 4, 6]),  // Better Criticals
+    PerkInfo::selectable(1, 6, [0, 7, 0, 0, 5, 0, 0]),  // Empathy
+    PerkInfo::selectable(1, 24, [8, 0, 0, 0, 0, 8, 0]).with_skills(&[(SKILL_UNARMED, 80)]), // Slayer
+    PerkInfo::selectable(1, 24, [0, 8, 0, 0, 0, 8, 0]).with_skills(&[(SKILL_SMALL_GUNS, 80)]), // Sniper
+    PerkInfo::selectable(1, 18, [0, 0, 0, 0, 0, 10, 0])
+        .with_skills(&[(SKILL_SNEAK, 80), (SKILL_UNARMED, 80)]), // Silent Death
+    PerkInfo::selectable(3, 12, [0, 0, 0, 0, 0, 5, 0]), // Action Boy
+    PerkInfo::selectable(1, 15, [0, 0, 0, 0, 0, 0, 0]), // Mental Block
+    PerkInfo::selectable(3, 12, [0, 0, 4, 0, 0, 0, 0]), // Lifegiver
+    PerkInfo::selectable(1, 9, [0, 0, 0, 0, 0, 6, 0]),  // Dodger
+    PerkInfo::selectable(3, 6, [0, 0, 3, 0, 0, 0, 0]),  // Snakeater
+    PerkInfo::selectable(1, 12, [0; 7])
+        .with_any_skill(&[(SKILL_SCIENCE, 40), (SKILL_REPAIR, 40)]), // Mr. Fixit
+    PerkInfo::selectable(1, 12, [0; 7])
+        .with_any_skill(&[(SKILL_FIRST_AID, 40), (SKILL_DOCTOR, 40)]), // Medic
+    PerkInfo::selectable(1, 12, [0; 7])
+        .with_any_skill(&[(SKILL_STEAL, 50), (SKILL_LOCKPICK, 50)]), // Master Thief
+    PerkInfo::selectable(1, 9, [0; 7]).with_skills(&[(SKILL_SPEECH, 50)]), // Speaker
+    PerkInfo::selectable(3, 6, [0; 7]),                 // Heave Ho!
+    PerkInfo::selectable(1, 3, [0, 6, 0, 0, 0, 0, 0]),  // Friendly Foe
+    PerkInfo::selectable(1, 15, [0, 0, 0, 0, 0, 8, 0]).with_skills(&[(SKILL_STEAL, 80)]), // Pickpocket
+    PerkInfo::selectable(1, 6, [0; 7]).with_skills(&[(SKILL_SNEAK, 60)]), // Ghost
+    PerkInfo::selectable(1, 12, [0, 0, 0, 10, 0, 0, 0]), // Cult of Personality
+    PerkInfo::selectable(1, 9, [0, 0, 0, 0, 0, 0, 8]),  // Scrounger
+    PerkInfo::selectable(1, 9, [0; 7]),                 // Explorer
+    PerkInfo::selectable(1, 9, [0, 0, 5, 0, 0, 0, 0]),  // Flower Child
+    PerkInfo::selectable(2, 6, [0, 0, 6, 0, 0, 0, 0]).with_skills(&[(SKILL_OUTDOORSMAN, 40)]), // Pathfinder
+    PerkInfo::selectable(1, 9, [0, 0, 0, 0, 5, 0, 0]).with_skills(&[(SKILL_OUTDOORSMAN, 25)]), // Animal Friend
+    PerkInfo::selectable(1, 3, [0, 7, 0, 0, 0, 0, 0]),  // Scout
+    PerkInfo::selectable(1, 9, [0, 0, 0, 0, 0, 0, 4]),  // Mysterious Stranger
+    PerkInfo::selectable(1, 6, [0, 6, 0, 0, 0, 0, 0]),  // Ranger
+    PerkInfo::selectable(1, 3, [0, 0, 0, 0, 0, 5, 0]),  // Quick Pockets
+    PerkInfo::selectable(3, 3, [0, 0, 0, 0, 4, 0, 0]),  // Smooth Talker
+    PerkInfo::selectable(3, 3, [0, 0, 0, 0, 4, 0, 0]),  // Swift Learner
+    PerkInfo::selectable(1, 12, [0; 7]),                // Tag!
+    PerkInfo::selectable(1, 9, [0; 7]),                 // Mutate!
+    PerkInfo::addiction(),                              // Nuka-Cola Addiction
+    PerkInfo::addiction(),                              // Buffout Addiction
+    PerkInfo::addiction(),                              // Mentats Addiction
+    PerkInfo::addiction(),                              // Psycho Addiction
+    PerkInfo::addiction(),                              // Radaway Addiction
+    PerkInfo::hidden(),                                 // Weapon Long Range
+    PerkInfo::hidden(),                                 // Weapon Accurate
+    PerkInfo::hidden(),                                 // Weapon Penetrate
+    PerkInfo::hidden(),                                 // Weapon Knockback
+    PerkInfo::hidden(),                                 // Powered Armor
+    PerkInfo::hidden(),                                 // Combat Armor
+];
+
+const STAT_RADIATION_RESISTANCE: usize = 31;
+
+/// Effects of the perks armor protos grant while worn.
+pub const ARMOR_PERK_EFFECTS: [PerkEffect; 2] = [
+    PerkEffect {
+        perk: 62, // Powered Armor
+        stat: STAT_RADIATION_RESISTANCE,
+        modifier: 30,
+        special: [3, 0, 0, 0, 0, 0, 0],
+    },
+    PerkEffect {
+        perk: 63, // Combat Armor
+        stat: STAT_RADIATION_RESISTANCE,
+        modifier: 20,
+        special: [0; 7],
+    },
+];
+
 pub const TRAIT_NAMES: [&str; 16] = [
     "Fast Metabolism",
     "Bruiser",
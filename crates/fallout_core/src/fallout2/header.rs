@@ -22,6 +22,8 @@ pub struct SaveHeader {
     pub elevation: i16,
     pub map: i16,
     pub map_filename: String,
+    /// Palettized 224x133 thumbnail shown in the load menu.
+    pub preview: Vec<u8>,
 }
 
 impl SaveHeader {
@@ -68,8 +70,8 @@ impl SaveHeader {
         let map = r.read_i16()?;
         let map_filename = r.read_fixed_string(16)?;
 
-        // Skip thumbnail and padding.
-        r.skip((PREVIEW_SIZE + HEADER_PADDING) as u64)?;
+        let preview = r.read_bytes(PREVIEW_SIZE)?;
+        r.skip(HEADER_PADDING as u64)?;
 
         Ok(Self {
             character_name,
@@ -88,6 +90,7 @@ impl SaveHeader {
             elevation,
             map,
             map_filename,
+            preview,
         })
     }
 }
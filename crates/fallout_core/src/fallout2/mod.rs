@@ -3,21 +3,31 @@ pub mod object;
 pub mod sections;
 pub mod types;
 
+use std::collections::BTreeSet;
 use std::io::{self, Cursor, Read, Seek};
 
 use crate::common::blob_patching::SectionBlob;
-use crate::common::{blob_emission, blob_patching, layout_management};
+use crate::common::{blob_emission, blob_patching, game_calendar, layout_management};
+use crate::event_queue::{
+    FALLOUT2_EVENT_TYPE_MAX, QueueEvent, check_queue_event_index, emit_event_queue,
+    parse_event_queue, shift_event_times,
+};
 use crate::gender::Gender;
 use crate::layout::{ByteRange, FileLayout, SectionId, SectionLayout};
-use crate::object::GameObject;
+use crate::map_save::{ELEVATION_COUNT, HEX_GRID_SIZE};
+use crate::object::{GameObject, InventoryItem, ItemPrototype};
 use crate::reader::BigEndianReader;
 use header::SaveHeader;
 use sections::{
-    CombatState, CritterProtoData, PcStats, parse_critter_proto_nearby, parse_game_global_vars,
-    parse_kill_counts, parse_map_file_list, parse_player_combat_id, parse_player_object,
-    parse_post_tagged_sections, parse_tagged_skills,
+    CharacterEditorState, CombatState, CritterProtoData, PartyMemberState, PcStats, Preferences,
+    WorldMapState, emit_preferences, invalid_preference_field, parse_critter_proto_nearby,
+    parse_game_global_vars, parse_kill_counts, parse_map_file_list, parse_player_combat_id,
+    parse_player_object, parse_post_tagged_sections, parse_tagged_skills,
+};
+use types::{
+    GAME_CALENDAR_EPOCH, GAME_MOVIE_COUNT, KILL_TYPE_COUNT, PERK_COUNT, PREVIEW_SIZE,
+    SAVEABLE_STAT_COUNT, SKILL_COUNT, SKILL_USES_PER_DAY, TAGGED_SKILL_COUNT, TRAIT_GIFTED,
 };
-use types::{KILL_TYPE_COUNT, PERK_COUNT, SAVEABLE_STAT_COUNT, SKILL_COUNT, TAGGED_SKILL_COUNT};
 
 const STAT_STRENGTH: usize = 0;
 const STAT_PERCEPTION: usize = 1;
@@ -27,6 +37,20 @@ const STAT_INTELLIGENCE: usize = 4;
 const STAT_AGILITY: usize = 5;
 const STAT_LUCK: usize = 6;
 const STAT_INVALID: i32 = -1;
+const STAT_MAXIMUM_HIT_POINTS: usize = 7;
+const STAT_MAXIMUM_ACTION_POINTS: usize = 8;
+const STAT_ARMOR_CLASS: usize = 9;
+const STAT_MELEE_DAMAGE: usize = 11;
+const STAT_CARRY_WEIGHT: usize = 12;
+const STAT_SEQUENCE: usize = 13;
+const STAT_HEALING_RATE: usize = 14;
+const STAT_CRITICAL_CHANCE: usize = 15;
+const STAT_BETTER_CRITICALS: usize = 16;
+const STAT_RADIATION_RESISTANCE: usize = 31;
+const STAT_POISON_RESISTANCE: usize = 32;
+const DERIVED_STAT_COUNT: usize = 11;
+const SPECIAL_MIN: i32 = 1;
+const SPECIAL_MAX: i32 = 10;
 
 const SKILL_SMALL_GUNS: usize = 0;
 const SKILL_BIG_GUNS: usize = 1;
@@ -47,8 +71,9 @@ const SKILL_BARTER: usize = 15;
 const SKILL_GAMBLING: usize = 16;
 const SKILL_OUTDOORSMAN: usize = 17;
 
+const TRAIT_BRUISER: i32 = 1;
+const TRAIT_SMALL_FRAME: i32 = 2;
 const TRAIT_GOOD_NATURED: i32 = 10;
-const TRAIT_GIFTED: i32 = 15;
 
 const GAME_DIFFICULTY_EASY: i32 = 0;
 const GAME_DIFFICULTY_HARD: i32 = 2;
@@ -76,6 +101,15 @@ const HEADER_CHARACTER_NAME_OFFSET: usize = 24 + HEADER_VERSION_BLOCK_LEN;
 const HEADER_CHARACTER_NAME_LEN: usize = 32;
 const HEADER_DESCRIPTION_OFFSET: usize = HEADER_CHARACTER_NAME_OFFSET + HEADER_CHARACTER_NAME_LEN;
 const HEADER_DESCRIPTION_LEN: usize = 30;
+// File date (day, month, year: i16; time: i32) precedes the game date.
+const HEADER_GAME_DATE_OFFSET: usize =
+    HEADER_DESCRIPTION_OFFSET + HEADER_DESCRIPTION_LEN + 3 * 2 + 4;
+const HEADER_GAME_TIME_OFFSET: usize = HEADER_GAME_DATE_OFFSET + 3 * 2;
+const HEADER_ELEVATION_OFFSET: usize = HEADER_GAME_TIME_OFFSET + 4;
+const HEADER_MAP_OFFSET: usize = HEADER_ELEVATION_OFFSET + 2;
+const HEADER_MAP_FILENAME_OFFSET: usize = HEADER_MAP_OFFSET + 2;
+const HEADER_MAP_FILENAME_LEN: usize = 16;
+const HEADER_PREVIEW_OFFSET: usize = HEADER_MAP_FILENAME_OFFSET + HEADER_MAP_FILENAME_LEN;
 const I32_WIDTH: usize = 4;
 const PC_STATS_UNSPENT_SKILL_POINTS_OFFSET: usize = 0;
 const PC_STATS_LEVEL_OFFSET: usize = I32_WIDTH;
@@ -227,6 +261,7 @@ pub struct SaveGame {
     pub header: SaveHeader,
     pub player_combat_id: i32,
     pub global_var_count: usize,
+    pub global_vars: Vec<i32>,
     pub map_files: Vec<String>,
     pub automap_size: i32,
     pub player_object: GameObject,
@@ -239,7 +274,15 @@ pub struct SaveGame {
     pub combat_state: CombatState,
     pub pc_stats: PcStats,
     pub selected_traits: [i32; 2],
-    pub game_difficulty: i32,
+    pub preferences: Preferences,
+    pub character_editor: Option<CharacterEditorState>,
+    pub world_map: Option<WorldMapState>,
+    pub movies_seen: Option<[bool; GAME_MOVIE_COUNT]>,
+    pub skill_use_times: Option<[[i32; SKILL_USES_PER_DAY]; SKILL_COUNT]>,
+    pub party_members: Option<PartyMemberState>,
+    /// Handler 24 event queue, or `None` when handlers 18-24 could not be
+    /// decoded and were left as raw bytes.
+    pub queue_events: Option<Vec<QueueEvent>>,
     pub party_member_count: usize,
     pub ai_packet_count: usize,
     pub layout_detection_score: i32,
@@ -355,6 +398,28 @@ impl Document {
         Ok(())
     }
 
+    pub fn set_radiation(&mut self, radiation: i32) -> io::Result<()> {
+        check_non_negative(radiation, "radiation")?;
+        self.player_critter_data_mut()?.radiation = radiation;
+        self.rewrite_handler5_from_player_object()
+    }
+
+    pub fn set_poison(&mut self, poison: i32) -> io::Result<()> {
+        check_non_negative(poison, "poison")?;
+        self.player_critter_data_mut()?.poison = poison;
+        self.rewrite_handler5_from_player_object()
+    }
+
+    fn player_critter_data_mut(&mut self) -> io::Result<&mut object::CritterObjectData> {
+        match &mut self.save.player_object.object_data {
+            object::ObjectData::Critter(data) => Ok(data),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "player object has no critter data",
+            )),
+        }
+    }
+
     pub fn set_character_name(&mut self, value: &str) -> io::Result<()> {
         let blob = self.section_blob_mut(SectionId::Header)?;
         blob_patching::patch_fixed_string_in_blob(
@@ -369,6 +434,52 @@ impl Document {
         Ok(())
     }
 
+    /// Replace the palettized load-menu thumbnail.
+    pub fn set_preview(&mut self, preview: &[u8]) -> io::Result<()> {
+        if preview.len() != PREVIEW_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "preview is {} bytes, expected {PREVIEW_SIZE}",
+                    preview.len()
+                ),
+            ));
+        }
+
+        let blob = self.section_blob_mut(SectionId::Header)?;
+        blob_patching::patch_bytes_in_blob(
+            blob,
+            HEADER_PREVIEW_OFFSET,
+            preview,
+            "header",
+            "preview",
+        )?;
+        self.save.header.preview = preview.to_vec();
+        Ok(())
+    }
+
+    /// Record the uncompressed size of the slot's `AUTOMAP.SAV`, stored at
+    /// the end of handler 3.
+    pub fn set_automap_size(&mut self, size: i32) -> io::Result<()> {
+        if size < 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid automap size {size}"),
+            ));
+        }
+
+        let blob = self.section_blob_mut(SectionId::Handler(3))?;
+        let offset = blob.bytes.len().checked_sub(I32_WIDTH).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "handler 3 too short for automap size",
+            )
+        })?;
+        blob_patching::patch_i32_in_blob(blob, offset, size, "handler 3", "automap size")?;
+        self.save.automap_size = size;
+        Ok(())
+    }
+
     pub fn set_description(&mut self, value: &str) -> io::Result<()> {
         let blob = self.section_blob_mut(SectionId::Header)?;
         blob_patching::patch_fixed_string_in_blob(
@@ -383,12 +494,148 @@ impl Document {
         Ok(())
     }
 
+    /// Move the game clock to `game_time` ticks, rewriting the header's game
+    /// date to match. Pending queue events keep their distance from the
+    /// current time, so timed effects and deadlines move with the clock. An
+    /// undecoded queue cannot be moved, so the clock is left alone too.
+    pub fn set_game_time(&mut self, game_time: u32) -> io::Result<()> {
+        let delta = i64::from(game_time) - i64::from(self.save.header.game_time);
+        let queue_events = self.queue_events_mut()?;
+        if !queue_events.is_empty() {
+            shift_event_times(queue_events, delta);
+            self.rewrite_handler24_from_queue_events()?;
+        }
+
+        let (month, day, year) = game_calendar::game_date(GAME_CALENDAR_EPOCH, game_time);
+        let blob = self.section_blob_mut(SectionId::Header)?;
+        for (index, (value, field)) in [
+            (month, "game_month"),
+            (day, "game_day"),
+            (year, "game_year"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            blob_patching::patch_i16_in_blob(
+                blob,
+                HEADER_GAME_DATE_OFFSET + index * 2,
+                value,
+                "header",
+                field,
+            )?;
+        }
+        blob_patching::patch_i32_in_blob(
+            blob,
+            HEADER_GAME_TIME_OFFSET,
+            game_time as i32,
+            "header",
+            "game_time",
+        )?;
+
+        let header = &mut self.save.header;
+        header.game_month = month;
+        header.game_day = day;
+        header.game_year = year;
+        header.game_time = game_time;
+        Ok(())
+    }
+
+    /// Put the player on `tile` of `elevation` in the map saved as
+    /// `map_file`, which must be one of the slot's map saves (`map_files`).
+    /// The view is centred on the new tile.
+    pub fn set_player_location(
+        &mut self,
+        map_id: i16,
+        map_file: &str,
+        elevation: i16,
+        tile: i32,
+    ) -> io::Result<()> {
+        let Some(map_file) = self
+            .save
+            .map_files
+            .iter()
+            .find(|name| name.eq_ignore_ascii_case(map_file))
+        else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("map {map_file} is not one of the saved maps"),
+            ));
+        };
+        if !(0..ELEVATION_COUNT as i16).contains(&elevation) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "invalid elevation {elevation}, expected 0..{}",
+                    ELEVATION_COUNT - 1
+                ),
+            ));
+        }
+        if !(0..HEX_GRID_SIZE).contains(&tile) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid tile {tile}, expected 0..{}", HEX_GRID_SIZE - 1),
+            ));
+        }
+        // The header names the map with a lowercase extension, e.g. `ARCAVES.sav`.
+        let stem = map_file
+            .rsplit_once('.')
+            .map_or(map_file.as_str(), |(stem, _)| stem);
+        let map_filename = format!("{stem}.sav");
+
+        let blob = self.section_blob_mut(SectionId::Header)?;
+        blob_patching::patch_i16_in_blob(
+            blob,
+            HEADER_ELEVATION_OFFSET,
+            elevation,
+            "header",
+            "elevation",
+        )?;
+        blob_patching::patch_i16_in_blob(blob, HEADER_MAP_OFFSET, map_id, "header", "map")?;
+        blob_patching::patch_fixed_string_in_blob(
+            blob,
+            HEADER_MAP_FILENAME_OFFSET,
+            HEADER_MAP_FILENAME_LEN,
+            &map_filename,
+            "header",
+            "map_filename",
+        )?;
+        self.save.header.elevation = elevation;
+        self.save.header.map = map_id;
+        self.save.header.map_filename = map_filename;
+
+        self.save.player_object.tile = tile;
+        self.save.player_object.elevation = i32::from(elevation);
+        self.save.center_tile = tile;
+        self.rewrite_handler5_from_player_object()
+    }
+
     pub fn set_base_stat(&mut self, stat_index: usize, value: i32) -> io::Result<()> {
         self.patch_base_stat_handler(stat_index, value, &format!("stat {stat_index}"))?;
         self.save.critter_data.base_stats[stat_index] = value;
         Ok(())
     }
 
+    pub fn set_bonus_stat(&mut self, stat_index: usize, value: i32) -> io::Result<()> {
+        check_bonus_stat_index(stat_index)?;
+        let critter_data = self.save.critter_data.clone();
+        let blob = self.section_blob_mut(SectionId::Handler(6))?;
+        let offset = find_bonus_stat_offset_in_handler6(&blob.bytes, &critter_data, stat_index)?;
+        let field = format!("bonus stat {stat_index}");
+        blob_patching::patch_i32_in_blob(blob, offset, value, "handler 6", &field)?;
+        self.save.critter_data.bonus_stats[stat_index] = value;
+        Ok(())
+    }
+
+    /// Rewrite the derived base stats that differ from what SPECIAL implies.
+    pub fn recalculate_derived_stats(&mut self) -> io::Result<()> {
+        for (stat_index, value) in self.save.derived_base_stats() {
+            if self.save.critter_data.base_stats[stat_index] != value {
+                self.set_base_stat(stat_index, value)?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn set_age(&mut self, age: i32) -> io::Result<()> {
         self.patch_base_stat_handler(STAT_AGE_INDEX, age, "age")?;
         self.save.critter_data.base_stats[STAT_AGE_INDEX] = age;
@@ -448,6 +695,27 @@ impl Document {
         Ok(())
     }
 
+    pub fn set_global_var(&mut self, index: usize, value: i32) -> io::Result<()> {
+        if index >= self.save.global_vars.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "invalid global var index {index}, expected 0..{}",
+                    self.save.global_vars.len().saturating_sub(1)
+                ),
+            ));
+        }
+
+        // Handler 4 is a second copy of handler 2; the game reads both, so keep them identical.
+        let offset = index * I32_WIDTH;
+        for (handler, label) in [(2, "handler 2"), (4, "handler 4")] {
+            let blob = self.section_blob_mut(SectionId::Handler(handler))?;
+            blob_patching::patch_i32_in_blob(blob, offset, value, label, "global var")?;
+        }
+        self.save.global_vars[index] = value;
+        Ok(())
+    }
+
     pub fn set_trait(&mut self, slot: usize, trait_index: i32) -> io::Result<()> {
         if slot >= self.save.selected_traits.len() {
             return Err(io::Error::new(
@@ -517,6 +785,122 @@ impl Document {
         self.set_perk_rank(perk_index, 0)
     }
 
+    pub fn set_kill_count(&mut self, kill_type: usize, count: i32) -> io::Result<()> {
+        if kill_type >= KILL_TYPE_COUNT {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "invalid kill type {kill_type}, expected 0..{}",
+                    KILL_TYPE_COUNT - 1
+                ),
+            ));
+        }
+        if count < 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid kill count {count}, expected >= 0"),
+            ));
+        }
+
+        let offset = kill_type * I32_WIDTH;
+        let blob = self.section_blob_mut(SectionId::Handler(7))?;
+        blob_patching::patch_i32_in_blob(blob, offset, count, "handler 7", "kill count")?;
+        self.save.kill_counts[kill_type] = count;
+        Ok(())
+    }
+
+    /// Replace the tagged skills. Three tags are allowed, or four when the
+    /// Tag! perk is held; the fourth slot is the one the perk granted.
+    pub fn set_tagged_skills(&mut self, skills: &[usize]) -> io::Result<()> {
+        let limit = if self.save.has_perk_rank(PERK_TAG) {
+            TAGGED_SKILL_COUNT
+        } else {
+            TAGGED_SKILL_COUNT - 1
+        };
+        if skills.len() > limit {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "cannot tag {} skills, at most {limit} allowed",
+                    skills.len()
+                ),
+            ));
+        }
+        let mut tagged = [-1; TAGGED_SKILL_COUNT];
+        for (slot, &skill_index) in skills.iter().enumerate() {
+            if skill_index >= SKILL_COUNT {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "invalid skill index {skill_index}, expected 0..{}",
+                        SKILL_COUNT - 1
+                    ),
+                ));
+            }
+            if skills[..slot].contains(&skill_index) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("skill {skill_index} is tagged more than once"),
+                ));
+            }
+            tagged[slot] = skill_index as i32;
+        }
+
+        let blob = self.section_blob_mut(SectionId::Handler(8))?;
+        for (slot, &value) in tagged.iter().enumerate() {
+            blob_patching::patch_i32_in_blob(
+                blob,
+                slot * I32_WIDTH,
+                value,
+                "handler 8",
+                "tagged skill",
+            )?;
+        }
+        self.save.tagged_skills = tagged;
+        Ok(())
+    }
+
+    pub fn set_preferences(&mut self, preferences: &Preferences) -> io::Result<()> {
+        if let Some(field) = invalid_preference_field(preferences) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("preference {field} is out of range"),
+            ));
+        }
+        let mut blob = Vec::new();
+        emit_preferences(preferences, &mut blob);
+        layout_management::replace_section_blob(
+            &mut self.section_blobs,
+            &mut self.layout,
+            SectionId::Handler(17),
+            blob,
+        )?;
+        self.save.preferences = preferences.clone();
+        Ok(())
+    }
+
+    /// Rewrite handler 18: the level the character editor last saw and
+    /// whether a perk pick is pending there.
+    pub fn set_character_editor_state(&mut self, state: &CharacterEditorState) -> io::Result<()> {
+        if self.save.character_editor.is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "handler 18 was not decoded",
+            ));
+        }
+        let mut blob = Vec::with_capacity(5);
+        blob.extend_from_slice(&state.last_level.to_be_bytes());
+        blob.push(state.free_perk);
+        layout_management::replace_section_blob(
+            &mut self.section_blobs,
+            &mut self.layout,
+            SectionId::Handler(18),
+            blob,
+        )?;
+        self.save.character_editor = Some(state.clone());
+        Ok(())
+    }
+
     pub fn set_inventory_quantity(&mut self, pid: i32, quantity: i32) -> io::Result<()> {
         if quantity < 0 {
             return Err(io::Error::new(
@@ -582,13 +966,113 @@ impl Document {
         if !found {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
-                format!("cannot add new inventory pid={pid}: no existing template item in save"),
+                format!(
+                    "cannot add new inventory pid={pid}: item is not in inventory and no item prototype was provided"
+                ),
             ));
         }
 
         self.rewrite_handler5_from_player_object()
     }
 
+    /// Add `quantity` of an item, creating a new inventory object from `proto`
+    /// when the player does not already carry that PID. The new object's id
+    /// is above both the player's ids and `id_floor`, the highest id in use
+    /// elsewhere in the slot.
+    pub fn add_inventory_item_from_prototype(
+        &mut self,
+        proto: &ItemPrototype,
+        quantity: i32,
+        id_floor: i32,
+    ) -> io::Result<()> {
+        let pid = proto.pid;
+        if self
+            .save
+            .player_object
+            .inventory
+            .iter()
+            .any(|item| item.object.pid == pid)
+        {
+            return self.add_inventory_item(pid, quantity);
+        }
+        if quantity <= 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid inventory quantity {quantity}, expected > 0"),
+            ));
+        }
+
+        let id = self
+            .save
+            .player_object
+            .max_object_id()
+            .max(id_floor)
+            .checked_add(1)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "no free object id available for new inventory item",
+                )
+            })?;
+        let object = GameObject::new_item(id, proto)?;
+        // The engine inserts newly added items at the front of the inventory.
+        self.save
+            .player_object
+            .inventory
+            .insert(0, InventoryItem { quantity, object });
+
+        self.rewrite_handler5_from_player_object()
+    }
+
+    pub fn equip_inventory_item(&mut self, slot_flag: i32, pid: i32) -> io::Result<()> {
+        object::equip_inventory_item(&mut self.save.player_object, slot_flag, pid)?;
+        self.rewrite_handler5_from_player_object()
+    }
+
+    /// Clear an equipment slot, returning the PID of the item that was in it.
+    pub fn unequip_inventory_item(&mut self, slot_flag: i32) -> io::Result<Option<i32>> {
+        let pid = object::unequip_inventory_item(&mut self.save.player_object.inventory, slot_flag);
+        if pid.is_some() {
+            self.rewrite_handler5_from_player_object()?;
+        }
+        Ok(pid)
+    }
+
+    /// Set the loaded ammo type and round count of every stack of weapon `pid`.
+    pub fn set_weapon_ammo(&mut self, pid: i32, ammo_pid: i32, rounds: i32) -> io::Result<()> {
+        object::set_weapon_ammo(
+            &mut self.save.player_object.inventory,
+            pid,
+            ammo_pid,
+            rounds,
+        )?;
+        self.rewrite_handler5_from_player_object()
+    }
+
+    /// Detach an item stack (or part of one) from the player so it can be
+    /// placed elsewhere. Equipped items must be unequipped first.
+    pub fn take_inventory_object(
+        &mut self,
+        pid: i32,
+        quantity: Option<i32>,
+    ) -> io::Result<InventoryItem> {
+        let item = object::take_inventory_item(
+            &mut self.save.player_object.inventory,
+            pid,
+            quantity,
+            false,
+        )?;
+        self.rewrite_handler5_from_player_object()?;
+        Ok(item)
+    }
+
+    pub fn insert_inventory_object(&mut self, item: InventoryItem) -> io::Result<()> {
+        let mut used_ids = BTreeSet::new();
+        self.save.player_object.collect_object_ids(&mut used_ids);
+        object::insert_inventory_item(&mut self.save.player_object.inventory, item, &used_ids)?;
+        self.rewrite_handler5_from_player_object()
+    }
+
     pub fn remove_inventory_item(&mut self, pid: i32, quantity: Option<i32>) -> io::Result<()> {
         let total_before: i64 = self
             .save
@@ -639,6 +1123,41 @@ impl Document {
         self.rewrite_handler5_from_player_object()
     }
 
+    pub fn remove_queue_event(&mut self, index: usize) -> io::Result<QueueEvent> {
+        let queue_events = self.queue_events_mut()?;
+        check_queue_event_index(queue_events, index)?;
+        let removed = queue_events.remove(index);
+        self.rewrite_handler24_from_queue_events()?;
+        Ok(removed)
+    }
+
+    /// Move an event to a new fire time. The engine only pops events from the
+    /// head of its time-ordered list, so the queue is re-sorted afterwards.
+    pub fn set_queue_event_time(&mut self, index: usize, time: u32) -> io::Result<()> {
+        let queue_events = self.queue_events_mut()?;
+        check_queue_event_index(queue_events, index)?;
+        queue_events[index].time = time;
+        queue_events.sort_by_key(|event| event.time);
+        self.rewrite_handler24_from_queue_events()
+    }
+
+    /// Drop every pending event `remove` selects, returning them in queue
+    /// order.
+    pub fn remove_queue_events_where<F>(&mut self, mut remove: F) -> io::Result<Vec<QueueEvent>>
+    where
+        F: FnMut(&QueueEvent) -> bool,
+    {
+        let queue_events = self.queue_events_mut()?;
+        let (removed, kept) = std::mem::take(queue_events)
+            .into_iter()
+            .partition(|event| remove(event));
+        *queue_events = kept;
+        if !removed.is_empty() {
+            self.rewrite_handler24_from_queue_events()?;
+        }
+        Ok(removed)
+    }
+
     fn patch_base_stat_handler(
         &mut self,
         stat_index: usize,
@@ -693,6 +1212,29 @@ impl Document {
         blob_patching::patch_i32_in_blob(blob, offset, value, "handler 15", "trait")
     }
 
+    fn queue_events_mut(&mut self) -> io::Result<&mut Vec<QueueEvent>> {
+        self.save.queue_events.as_mut().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "event queue (handler 24) was not decoded",
+            )
+        })
+    }
+
+    fn rewrite_handler24_from_queue_events(&mut self) -> io::Result<()> {
+        let mut blob = Vec::new();
+        emit_event_queue(
+            self.save.queue_events.as_deref().unwrap_or_default(),
+            &mut blob,
+        );
+        layout_management::replace_section_blob(
+            &mut self.section_blobs,
+            &mut self.layout,
+            SectionId::Handler(24),
+            blob,
+        )
+    }
+
     fn rewrite_handler5_from_player_object(&mut self) -> io::Result<()> {
         let mut blob = Vec::new();
         self.save.player_object.emit_to_vec(&mut blob)?;
@@ -752,6 +1294,66 @@ fn find_base_stat_offset_in_handler6(
     Ok(first + prefix.len())
 }
 
+fn check_non_negative(value: i32, field: &str) -> io::Result<()> {
+    if value < 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid {field} level {value}, expected >= 0"),
+        ));
+    }
+    Ok(())
+}
+
+fn check_bonus_stat_index(stat_index: usize) -> io::Result<()> {
+    if stat_index >= SAVEABLE_STAT_COUNT {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "invalid stat index {stat_index}, expected 0..{}",
+                SAVEABLE_STAT_COUNT - 1
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn find_bonus_stat_offset_in_handler6(
+    handler6_bytes: &[u8],
+    critter_data: &CritterProtoData,
+    stat_index: usize,
+) -> io::Result<usize> {
+    let mut prefix = Vec::new();
+    prefix.extend_from_slice(&critter_data.sneak_working.to_be_bytes());
+    prefix.extend_from_slice(&critter_data.flags.to_be_bytes());
+    for value in &critter_data.base_stats {
+        prefix.extend_from_slice(&value.to_be_bytes());
+    }
+    for value in critter_data.bonus_stats.iter().take(stat_index) {
+        prefix.extend_from_slice(&value.to_be_bytes());
+    }
+
+    let mut matches = handler6_bytes
+        .windows(prefix.len())
+        .enumerate()
+        .filter_map(|(idx, window)| (window == prefix.as_slice()).then_some(idx));
+
+    let first = matches.next().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "could not locate bonus stat prefix in handler 6 blob",
+        )
+    })?;
+
+    if matches.next().is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("ambiguous bonus stat prefix match in handler 6 blob for index {stat_index}"),
+        ));
+    }
+
+    Ok(first + prefix.len())
+}
+
 fn find_skill_offset_in_handler6(
     handler6_bytes: &[u8],
     critter_data: &CritterProtoData,
@@ -896,7 +1498,7 @@ fn parse_internal<R: Read + Seek>(
         c.record(SectionId::Handler(5), h5_start, h5_end);
     }
 
-    // Handler 6: Critter proto data (372 bytes)
+    // Handler 6: Critter proto data (376 bytes)
     let h6_start = r.position()? as usize;
     let critter_data = parse_critter_proto_nearby(r)?;
     let h6_end = r.position()? as usize;
@@ -932,14 +1534,14 @@ fn parse_internal<R: Read + Seek>(
     let post_start = h9_pos;
     let post_tagged = parse_post_tagged_sections(r)?;
 
-    if let Some(c) = capture {
+    if let Some(c) = capture.as_deref_mut() {
         let h10_end = post_tagged.h10_end as usize;
         let h11_end = post_tagged.h11_end as usize;
         let h12_end = post_tagged.h12_end as usize;
         let h13_end = post_tagged.h13_end as usize;
         let h15_end = post_tagged.h15_end as usize;
         let h16_end = post_tagged.h16_end as usize;
-        let h17_prefix_end = post_tagged.h17_prefix_end as usize;
+        let h17_end = post_tagged.h17_end as usize;
 
         c.record(SectionId::Handler(10), post_start, h10_end);
         c.record(SectionId::Handler(11), h10_end, h11_end);
@@ -948,13 +1550,67 @@ fn parse_internal<R: Read + Seek>(
         c.record(SectionId::Handler(14), h13_end, h13_end);
         c.record(SectionId::Handler(15), h13_end, h15_end);
         c.record(SectionId::Handler(16), h15_end, h16_end);
-        c.record(SectionId::Handler(17), h16_end, h17_prefix_end);
+        c.record(SectionId::Handler(17), h16_end, h17_end);
+
+        if let Some(post) = &post_tagged.post_preferences {
+            let h18_end = post.h18_end as usize;
+            let h19_end = post.h19_end as usize;
+            let h20_end = post.h20_end as usize;
+            let h21_end = post.h21_end as usize;
+            let h22_end = post.h22_end as usize;
+            let h23_end = post.h23_end as usize;
+
+            c.record(SectionId::Handler(18), h17_end, h18_end);
+            c.record(SectionId::Handler(19), h18_end, h19_end);
+            c.record(SectionId::Handler(20), h19_end, h20_end);
+            c.record(SectionId::Handler(21), h20_end, h21_end);
+            c.record(SectionId::Handler(22), h21_end, h22_end);
+            c.record(SectionId::Handler(23), h22_end, h23_end);
+        }
     }
 
+    // Handler 24: event queue, which only has a known start once handlers
+    // 18-23 decoded. If it does not parse, it stays in the tail with
+    // handler 25 (interface) and what follows.
+    let queue_events = if post_tagged.post_preferences.is_some() {
+        let h24_start = r.position()?;
+        match parse_event_queue(r, FALLOUT2_EVENT_TYPE_MAX) {
+            Ok(events) => {
+                if let Some(c) = capture {
+                    c.record(
+                        SectionId::Handler(24),
+                        h24_start as usize,
+                        r.position()? as usize,
+                    );
+                }
+                Some(events)
+            }
+            Err(_) => {
+                r.seek_to(h24_start)?;
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let (character_editor, world_map, movies_seen, skill_use_times, party_members) =
+        match post_tagged.post_preferences {
+            Some(post) => (
+                Some(post.character_editor),
+                Some(post.world_map),
+                Some(post.movies_seen),
+                Some(post.skill_use_times),
+                Some(post.party_members),
+            ),
+            None => (None, None, None, None, None),
+        };
+
     Ok(SaveGame {
         header,
         player_combat_id,
         global_var_count,
+        global_vars: globals.global_vars,
         map_files: map_info.map_files,
         automap_size: map_info.automap_size,
         player_object: player_section.player_object,
@@ -967,7 +1623,13 @@ fn parse_internal<R: Read + Seek>(
         combat_state: post_tagged.combat_state,
         pc_stats: post_tagged.pc_stats,
         selected_traits: post_tagged.selected_traits,
-        game_difficulty: post_tagged.game_difficulty,
+        preferences: post_tagged.preferences,
+        character_editor,
+        world_map,
+        movies_seen,
+        skill_use_times,
+        party_members,
+        queue_events,
         party_member_count: post_tagged.party_member_count,
         ai_packet_count: post_tagged.ai_packet_count,
         layout_detection_score: post_tagged.detection_score,
@@ -975,6 +1637,12 @@ fn parse_internal<R: Read + Seek>(
 }
 
 impl SaveGame {
+    /// Game difficulty from the handler 17 preferences (0 easy, 1 normal,
+    /// 2 hard).
+    pub fn game_difficulty(&self) -> i32 {
+        self.preferences.game_difficulty
+    }
+
     pub fn effective_skill_value(&self, skill_index: usize) -> i32 {
         if skill_index >= SKILL_COUNT {
             return 0;
@@ -1029,6 +1697,60 @@ impl SaveGame {
         bonus
     }
 
+    /// Derived stats as the engine recomputes them from SPECIAL. Max HP uses
+    /// base Strength and Endurance with trait modifiers; the others use the
+    /// full SPECIAL totals.
+    pub fn derived_base_stats(&self) -> [(usize, i32); DERIVED_STAT_COUNT] {
+        let strength = self.special_total(STAT_STRENGTH);
+        let perception = self.special_total(STAT_PERCEPTION);
+        let endurance = self.special_total(STAT_ENDURANCE);
+        let agility = self.special_total(STAT_AGILITY);
+        let luck = self.special_total(STAT_LUCK);
+        let max_hp = self.base_stat_with_traits(STAT_STRENGTH)
+            + 2 * self.base_stat_with_traits(STAT_ENDURANCE)
+            + 15;
+
+        [
+            (STAT_MAXIMUM_HIT_POINTS, max_hp),
+            (STAT_MAXIMUM_ACTION_POINTS, agility / 2 + 5),
+            (STAT_ARMOR_CLASS, agility),
+            (STAT_MELEE_DAMAGE, (strength - 5).max(1)),
+            (STAT_CARRY_WEIGHT, 25 * strength + 25),
+            (STAT_SEQUENCE, 2 * perception),
+            (STAT_HEALING_RATE, (endurance / 3).max(1)),
+            (STAT_CRITICAL_CHANCE, luck),
+            (STAT_BETTER_CRITICALS, 0),
+            (STAT_RADIATION_RESISTANCE, 2 * endurance),
+            (STAT_POISON_RESISTANCE, 5 * endurance),
+        ]
+    }
+
+    /// Base SPECIAL value with trait modifiers applied, before any bonus.
+    pub fn base_stat_with_traits(&self, stat_index: usize) -> i32 {
+        self.critter_data.base_stats[stat_index] + self.trait_stat_modifier(stat_index)
+    }
+
+    /// SPECIAL value the game uses: base with traits plus the bonuses from
+    /// armor, drugs and items, held to 1..=10.
+    pub fn special_total(&self, stat_index: usize) -> i32 {
+        (self.base_stat_with_traits(stat_index) + self.critter_data.bonus_stats[stat_index])
+            .clamp(SPECIAL_MIN, SPECIAL_MAX)
+    }
+
+    fn trait_stat_modifier(&self, stat_index: usize) -> i32 {
+        let mut modifier = 0;
+        if self.has_trait(TRAIT_GIFTED) {
+            modifier += 1;
+        }
+        if stat_index == STAT_STRENGTH && self.has_trait(TRAIT_BRUISER) {
+            modifier += 2;
+        }
+        if stat_index == STAT_AGILITY && self.has_trait(TRAIT_SMALL_FRAME) {
+            modifier += 1;
+        }
+        modifier
+    }
+
     fn total_stat(&self, stat_index: usize) -> i32 {
         self.critter_data.base_stats[stat_index] + self.critter_data.bonus_stats[stat_index]
     }
@@ -1104,10 +1826,8 @@ impl SaveGame {
                 }
                 let _ = self.has_perk_rank(PERK_GHOST);
             }
-            SKILL_SCIENCE | SKILL_REPAIR => {
-                if self.has_perk_rank(PERK_MR_FIXIT) {
-                    modifier += 10;
-                }
+            SKILL_SCIENCE | SKILL_REPAIR if self.has_perk_rank(PERK_MR_FIXIT) => {
+                modifier += 10;
             }
             SKILL_SPEECH | SKILL_BARTER => {
                 if skill_index == SKILL_SPEECH {
@@ -1125,10 +1845,8 @@ impl SaveGame {
                     modifier += 20;
                 }
             }
-            SKILL_GAMBLING => {
-                if self.has_perk_rank(PERK_GAMBLER) {
-                    modifier += 20;
-                }
+            SKILL_GAMBLING if self.has_perk_rank(PERK_GAMBLER) => {
+                modifier += 20;
             }
             SKILL_OUTDOORSMAN => {
                 if self.has_perk_rank(PERK_RANGER) {
@@ -1165,9 +1883,9 @@ impl SaveGame {
             return 0;
         }
 
-        if self.game_difficulty == GAME_DIFFICULTY_HARD {
+        if self.game_difficulty() == GAME_DIFFICULTY_HARD {
             -10
-        } else if self.game_difficulty == GAME_DIFFICULTY_EASY {
+        } else if self.game_difficulty() == GAME_DIFFICULTY_EASY {
             20
         } else {
             0
@@ -3,8 +3,8 @@ use std::io::{self, Read, Seek};
 use crate::reader::BigEndianReader;
 
 use super::types::{
-    KILL_TYPE_COUNT, PC_STAT_COUNT, PERK_COUNT, SAVEABLE_STAT_COUNT, SKILL_COUNT,
-    TAGGED_SKILL_COUNT,
+    GAME_MOVIE_COUNT, KILL_TYPE_COUNT, PC_STAT_COUNT, PERK_COUNT, SAVEABLE_STAT_COUNT, SKILL_COUNT,
+    SKILL_USES_PER_DAY, TAGGED_SKILL_COUNT, WORLD_MAP_SUBTILES_PER_TILE,
 };
 use crate::object::GameObject;
 
@@ -14,6 +14,11 @@ const MAX_PARTY_MEMBER_COUNT: usize = 64;
 const AI_PACKET_INT_COUNT: usize = 45;
 const TRAITS_MAX_SELECTED_COUNT: usize = 2;
 const TRAIT_COUNT: i32 = 16;
+const MAX_WORLD_MAP_AREA_COUNT: i32 = 512;
+const MAX_WORLD_MAP_ENTRANCE_COUNT: i32 = 64;
+const MAX_WORLD_MAP_TILE_COUNT: i32 = 4096;
+const MAX_ENCOUNTER_COUNTER_COUNT: i32 = 100_000;
+const PREFERENCES_MAX_VOLUME: i32 = 0x7FFF;
 
 // --- Handler 1: Player Combat ID ---
 
@@ -160,6 +165,7 @@ pub struct CritterProtoData {
     pub body_type: i32,
     pub experience: i32,
     pub kill_type: i32,
+    pub damage_type: i32,
 }
 
 pub fn parse_critter_proto<R: Read + Seek>(
@@ -173,6 +179,7 @@ pub fn parse_critter_proto<R: Read + Seek>(
     let body_type = r.read_i32()?;
     let experience = r.read_i32()?;
     let kill_type = r.read_i32()?;
+    let damage_type = r.read_i32()?;
 
     Ok(CritterProtoData {
         sneak_working,
@@ -183,6 +190,7 @@ pub fn parse_critter_proto<R: Read + Seek>(
         body_type,
         experience,
         kill_type,
+        damage_type,
     })
 }
 
@@ -213,7 +221,7 @@ pub fn parse_critter_proto_nearby<R: Read + Seek>(
             guessed_pos + delta as u64
         };
 
-        if pos + 372 > file_len {
+        if pos + 376 > file_len {
             continue;
         }
 
@@ -364,7 +372,8 @@ pub struct PostTaggedSections {
     pub combat_state: CombatState,
     pub pc_stats: PcStats,
     pub selected_traits: [i32; TRAITS_MAX_SELECTED_COUNT],
-    pub game_difficulty: i32,
+    pub preferences: Preferences,
+    pub post_preferences: Option<PostPreferenceSections>,
     pub party_member_count: usize,
     pub ai_packet_count: usize,
     pub detection_score: i32,
@@ -374,7 +383,7 @@ pub struct PostTaggedSections {
     pub h13_end: u64,
     pub h15_end: u64,
     pub h16_end: u64,
-    pub h17_prefix_end: u64,
+    pub h17_end: u64,
 }
 
 pub fn parse_post_tagged_sections<R: Read + Seek>(
@@ -421,11 +430,17 @@ pub fn parse_post_tagged_sections<R: Read + Seek>(
                 Err(_) => continue,
             };
 
+            // A misaligned candidate almost never yields plausible
+            // preferences, so only then pay for walking handlers 18-23.
+            let tail_parsed = preferences_look_valid(&post_pc.preferences)
+                && parse_post_preference_sections(r, party_member_count).is_ok();
+
             let score = match score_post_tagged_candidate(
                 &perks,
                 &combat_state,
                 &pc_stats,
                 &post_pc,
+                tail_parsed,
                 party_member_count,
                 ai_packet_count,
             ) {
@@ -466,16 +481,27 @@ pub fn parse_post_tagged_sections<R: Read + Seek>(
     let h13_end = r.position()?;
 
     let post_pc = parse_post_pc_sections(r)?;
-    let h17_prefix_end = r.position()?;
+    let h17_end = r.position()?;
     let h15_end = h13_end + 8;
     let h16_end = h15_end + 4;
 
+    // Handlers 18+ are optional: if they do not decode cleanly, leave the
+    // stream after handler 17 so the rest is kept as an opaque tail.
+    let post_preferences = match parse_post_preference_sections(r, best_party_count) {
+        Ok(v) => Some(v),
+        Err(_) => {
+            r.seek_to(h17_end)?;
+            None
+        }
+    };
+
     Ok(PostTaggedSections {
         perks,
         combat_state,
         pc_stats,
         selected_traits: post_pc.selected_traits,
-        game_difficulty: post_pc.game_difficulty,
+        preferences: post_pc.preferences,
+        post_preferences,
         party_member_count: best_party_count,
         ai_packet_count: best_ai_packet_count,
         detection_score: best_score,
@@ -485,7 +511,7 @@ pub fn parse_post_tagged_sections<R: Read + Seek>(
         h13_end,
         h15_end,
         h16_end,
-        h17_prefix_end,
+        h17_end,
     })
 }
 
@@ -579,7 +605,7 @@ fn parse_pc_stats<R: Read + Seek>(r: &mut BigEndianReader<R>) -> io::Result<PcSt
 
 struct PostPcSections {
     selected_traits: [i32; TRAITS_MAX_SELECTED_COUNT],
-    game_difficulty: i32,
+    preferences: Preferences,
 }
 
 fn parse_post_pc_sections<R: Read + Seek>(
@@ -598,24 +624,437 @@ fn parse_post_pc_sections<R: Read + Seek>(
     // Handler 16: automap flags.
     let _automap_flags = r.read_i32()?;
 
-    // Handler 17: preferences (we only need game difficulty).
-    let game_difficulty = r.read_i32()?;
-    let _combat_difficulty = r.read_i32()?;
-    let _violence_level = r.read_i32()?;
-    let _target_highlight = r.read_i32()?;
-    let _combat_looks = r.read_i32()?;
+    // Handler 17: preferences.
+    let preferences = parse_preferences(r)?;
 
     Ok(PostPcSections {
         selected_traits: [trait1, trait2],
-        game_difficulty,
+        preferences,
+    })
+}
+
+// --- Handler 17: Preferences ---
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preferences {
+    pub game_difficulty: i32,
+    pub combat_difficulty: i32,
+    pub violence_level: i32,
+    pub target_highlight: i32,
+    pub combat_looks: i32,
+    pub combat_messages: i32,
+    pub combat_taunts: i32,
+    pub language_filter: i32,
+    pub running: i32,
+    pub subtitles: i32,
+    pub item_highlight: i32,
+    pub combat_speed: i32,
+    pub player_speedup: i32,
+    pub text_base_delay: f32,
+    pub master_volume: i32,
+    pub music_volume: i32,
+    pub sound_effects_volume: i32,
+    pub speech_volume: i32,
+    pub brightness: f32,
+    pub mouse_sensitivity: f32,
+}
+
+fn parse_preferences<R: Read + Seek>(r: &mut BigEndianReader<R>) -> io::Result<Preferences> {
+    Ok(Preferences {
+        game_difficulty: r.read_i32()?,
+        combat_difficulty: r.read_i32()?,
+        violence_level: r.read_i32()?,
+        target_highlight: r.read_i32()?,
+        combat_looks: r.read_i32()?,
+        combat_messages: r.read_i32()?,
+        combat_taunts: r.read_i32()?,
+        language_filter: r.read_i32()?,
+        running: r.read_i32()?,
+        subtitles: r.read_i32()?,
+        item_highlight: r.read_i32()?,
+        combat_speed: r.read_i32()?,
+        player_speedup: r.read_i32()?,
+        text_base_delay: r.read_f32()?,
+        master_volume: r.read_i32()?,
+        music_volume: r.read_i32()?,
+        sound_effects_volume: r.read_i32()?,
+        speech_volume: r.read_i32()?,
+        brightness: r.read_f32()?,
+        mouse_sensitivity: r.read_f32()?,
+    })
+}
+
+pub fn emit_preferences(prefs: &Preferences, out: &mut Vec<u8>) {
+    let ints = [
+        prefs.game_difficulty,
+        prefs.combat_difficulty,
+        prefs.violence_level,
+        prefs.target_highlight,
+        prefs.combat_looks,
+        prefs.combat_messages,
+        prefs.combat_taunts,
+        prefs.language_filter,
+        prefs.running,
+        prefs.subtitles,
+        prefs.item_highlight,
+        prefs.combat_speed,
+        prefs.player_speedup,
+    ];
+    for value in ints {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+    out.extend_from_slice(&prefs.text_base_delay.to_be_bytes());
+    for value in [
+        prefs.master_volume,
+        prefs.music_volume,
+        prefs.sound_effects_volume,
+        prefs.speech_volume,
+    ] {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+    out.extend_from_slice(&prefs.brightness.to_be_bytes());
+    out.extend_from_slice(&prefs.mouse_sensitivity.to_be_bytes());
+}
+
+fn preferences_look_valid(prefs: &Preferences) -> bool {
+    invalid_preference_field(prefs).is_none()
+}
+
+/// Name the first preference whose value lies outside what the options
+/// screen can produce.
+pub(crate) fn invalid_preference_field(prefs: &Preferences) -> Option<&'static str> {
+    let ints = [
+        ("game_difficulty", prefs.game_difficulty, 2),
+        ("combat_difficulty", prefs.combat_difficulty, 2),
+        ("violence_level", prefs.violence_level, 3),
+        ("target_highlight", prefs.target_highlight, 2),
+        ("combat_looks", prefs.combat_looks, 1),
+        ("combat_messages", prefs.combat_messages, 1),
+        ("combat_taunts", prefs.combat_taunts, 1),
+        ("language_filter", prefs.language_filter, 1),
+        ("running", prefs.running, 1),
+        ("subtitles", prefs.subtitles, 1),
+        ("item_highlight", prefs.item_highlight, 1),
+        ("combat_speed", prefs.combat_speed, 100),
+        ("player_speedup", prefs.player_speedup, 1),
+        ("master_volume", prefs.master_volume, PREFERENCES_MAX_VOLUME),
+        ("music_volume", prefs.music_volume, PREFERENCES_MAX_VOLUME),
+        (
+            "sound_effects_volume",
+            prefs.sound_effects_volume,
+            PREFERENCES_MAX_VOLUME,
+        ),
+        ("speech_volume", prefs.speech_volume, PREFERENCES_MAX_VOLUME),
+    ];
+    let floats = [
+        ("text_base_delay", prefs.text_base_delay),
+        ("brightness", prefs.brightness),
+        ("mouse_sensitivity", prefs.mouse_sensitivity),
+    ];
+
+    ints.iter()
+        .find(|(_, value, max)| !(0..=*max).contains(value))
+        .map(|(name, _, _)| *name)
+        .or_else(|| {
+            floats
+                .iter()
+                .find(|(_, value)| !(value.is_finite() && (0.0..=10.0).contains(value)))
+                .map(|(name, _)| *name)
+        })
+}
+
+// --- Handlers 18-23 ---
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharacterEditorState {
+    pub last_level: i32,
+    pub free_perk: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldMapState {
+    pub did_meet_frank: bool,
+    pub current_area_id: i32,
+    pub world_pos_x: i32,
+    pub world_pos_y: i32,
+    pub encounter_icon_visible: bool,
+    pub encounter_map_id: i32,
+    pub encounter_table_id: i32,
+    pub encounter_entry_id: i32,
+    pub is_in_car: bool,
+    pub car_area_id: i32,
+    pub car_fuel: i32,
+    pub areas: Vec<WorldMapArea>,
+    pub horizontal_tile_count: i32,
+    /// Fog state of each tile's 7x6 subtile grid (0 unknown, 1 known, 2 visited).
+    pub tiles: Vec<[i32; WORLD_MAP_SUBTILES_PER_TILE]>,
+    pub encounter_counters: Vec<EncounterCounter>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldMapArea {
+    pub x: i32,
+    pub y: i32,
+    pub state: i32,
+    pub visited_state: i32,
+    pub entrance_states: Vec<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncounterCounter {
+    pub table_index: i32,
+    pub entry_index: i32,
+    pub counter: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartyMemberState {
+    pub member_count: i32,
+    pub item_count: i32,
+    pub member_object_ids: Vec<i32>,
+    pub level_up_info: Vec<PartyMemberLevelUpInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartyMemberLevelUpInfo {
+    pub level: i32,
+    pub level_up_count: i32,
+    pub early_level_up: i32,
+}
+
+pub struct PostPreferenceSections {
+    pub character_editor: CharacterEditorState,
+    pub world_map: WorldMapState,
+    pub movies_seen: [bool; GAME_MOVIE_COUNT],
+    pub skill_use_times: [[i32; SKILL_USES_PER_DAY]; SKILL_COUNT],
+    pub party_members: PartyMemberState,
+    pub h18_end: u64,
+    pub h19_end: u64,
+    pub h20_end: u64,
+    pub h21_end: u64,
+    pub h22_end: u64,
+    pub h23_end: u64,
+}
+
+pub fn parse_post_preference_sections<R: Read + Seek>(
+    r: &mut BigEndianReader<R>,
+    party_member_count: usize,
+) -> io::Result<PostPreferenceSections> {
+    // Handler 18: character editor (last level + a single free-perk byte).
+    let last_level = r.read_i32()?;
+    let free_perk = r.read_u8()?;
+    if !(1..=99).contains(&last_level) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid character editor last level",
+        ));
+    }
+    let h18_end = r.position()?;
+
+    // Handler 19: world map.
+    let world_map = parse_world_map(r)?;
+    let h19_end = r.position()?;
+
+    // Handler 20: pipboy writes nothing.
+    let h20_end = h19_end;
+
+    // Handler 21: one seen flag per movie.
+    let movie_bytes = r.read_bytes(GAME_MOVIE_COUNT)?;
+    if movie_bytes.iter().any(|&b| b > 1) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid movie seen flags",
+        ));
+    }
+    let mut movies_seen = [false; GAME_MOVIE_COUNT];
+    for (seen, byte) in movies_seen.iter_mut().zip(&movie_bytes) {
+        *seen = *byte != 0;
+    }
+    let h21_end = r.position()?;
+
+    // Handler 22: skill-use timestamps. The engine dumps this array
+    // straight from memory, so unlike everything around it the values
+    // are little-endian.
+    let mut skill_use_times = [[0i32; SKILL_USES_PER_DAY]; SKILL_COUNT];
+    for uses in &mut skill_use_times {
+        for time in uses.iter_mut() {
+            let bytes = r.read_bytes(4)?;
+            *time = i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+    }
+    let h22_end = r.position()?;
+
+    // Handler 23: party members.
+    let party_members = parse_party_member_state(r, party_member_count)?;
+    let h23_end = r.position()?;
+
+    Ok(PostPreferenceSections {
+        character_editor: CharacterEditorState {
+            last_level,
+            free_perk,
+        },
+        world_map,
+        movies_seen,
+        skill_use_times,
+        party_members,
+        h18_end,
+        h19_end,
+        h20_end,
+        h21_end,
+        h22_end,
+        h23_end,
+    })
+}
+
+fn parse_world_map<R: Read + Seek>(r: &mut BigEndianReader<R>) -> io::Result<WorldMapState> {
+    let did_meet_frank = read_bool_i32(r, "world map frank flag")?;
+    let current_area_id = r.read_i32()?;
+    let world_pos_x = r.read_i32()?;
+    let world_pos_y = r.read_i32()?;
+    let encounter_icon_visible = read_bool_i32(r, "world map encounter icon flag")?;
+    let encounter_map_id = r.read_i32()?;
+    let encounter_table_id = r.read_i32()?;
+    let encounter_entry_id = r.read_i32()?;
+    let is_in_car = read_bool_i32(r, "world map car flag")?;
+    let car_area_id = r.read_i32()?;
+    let car_fuel = r.read_i32()?;
+
+    let area_count = read_count(r, MAX_WORLD_MAP_AREA_COUNT, "world map area count")?;
+    let mut areas = Vec::with_capacity(area_count);
+    for _ in 0..area_count {
+        let x = r.read_i32()?;
+        let y = r.read_i32()?;
+        let state = r.read_i32()?;
+        let visited_state = r.read_i32()?;
+        let entrance_count =
+            read_count(r, MAX_WORLD_MAP_ENTRANCE_COUNT, "world map entrance count")?;
+        let entrance_states = r.read_i32_vec(entrance_count)?;
+        areas.push(WorldMapArea {
+            x,
+            y,
+            state,
+            visited_state,
+            entrance_states,
+        });
+    }
+
+    let tile_count = read_count(r, MAX_WORLD_MAP_TILE_COUNT, "world map tile count")?;
+    let horizontal_tile_count = r.read_i32()?;
+    if tile_count > 0 && !(1..=tile_count as i32).contains(&horizontal_tile_count) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid world map horizontal tile count {horizontal_tile_count}"),
+        ));
+    }
+    let mut tiles = Vec::with_capacity(tile_count);
+    for _ in 0..tile_count {
+        let subtiles = r.read_i32_array::<WORLD_MAP_SUBTILES_PER_TILE>()?;
+        if !subtiles.iter().all(|state| (0..=2).contains(state)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid world map subtile state",
+            ));
+        }
+        tiles.push(subtiles);
+    }
+
+    let counter_count = read_count(
+        r,
+        MAX_ENCOUNTER_COUNTER_COUNT,
+        "world map encounter counter count",
+    )?;
+    let mut encounter_counters = Vec::with_capacity(counter_count);
+    for _ in 0..counter_count {
+        encounter_counters.push(EncounterCounter {
+            table_index: r.read_i32()?,
+            entry_index: r.read_i32()?,
+            counter: r.read_i32()?,
+        });
+    }
+
+    Ok(WorldMapState {
+        did_meet_frank,
+        current_area_id,
+        world_pos_x,
+        world_pos_y,
+        encounter_icon_visible,
+        encounter_map_id,
+        encounter_table_id,
+        encounter_entry_id,
+        is_in_car,
+        car_area_id,
+        car_fuel,
+        areas,
+        horizontal_tile_count,
+        tiles,
+        encounter_counters,
+    })
+}
+
+fn parse_party_member_state<R: Read + Seek>(
+    r: &mut BigEndianReader<R>,
+    party_member_count: usize,
+) -> io::Result<PartyMemberState> {
+    let member_count = r.read_i32()?;
+    if !(1..=MAX_PARTY_MEMBER_COUNT as i32).contains(&member_count) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid party member count {member_count}"),
+        ));
+    }
+    let item_count = r.read_i32()?;
+
+    // The player is slot 0 in both lists and is not written.
+    let member_object_ids = r.read_i32_vec(member_count as usize - 1)?;
+    let mut level_up_info = Vec::with_capacity(party_member_count.saturating_sub(1));
+    for _ in 1..party_member_count {
+        level_up_info.push(PartyMemberLevelUpInfo {
+            level: r.read_i32()?,
+            level_up_count: r.read_i32()?,
+            early_level_up: r.read_i32()?,
+        });
+    }
+
+    Ok(PartyMemberState {
+        member_count,
+        item_count,
+        member_object_ids,
+        level_up_info,
     })
 }
 
+fn read_bool_i32<R: Read + Seek>(r: &mut BigEndianReader<R>, what: &str) -> io::Result<bool> {
+    match r.read_i32()? {
+        0 => Ok(false),
+        1 => Ok(true),
+        v => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid {what} {v}"),
+        )),
+    }
+}
+
+fn read_count<R: Read + Seek>(
+    r: &mut BigEndianReader<R>,
+    max: i32,
+    what: &str,
+) -> io::Result<usize> {
+    let count = r.read_i32()?;
+    if !(0..=max).contains(&count) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid {what} {count}"),
+        ));
+    }
+    Ok(count as usize)
+}
+
 fn score_post_tagged_candidate(
     perks: &[i32; PERK_COUNT],
     combat_state: &CombatState,
     pc_stats: &PcStats,
     post_pc: &PostPcSections,
+    tail_parsed: bool,
     party_member_count: usize,
     ai_packet_count: usize,
 ) -> io::Result<i32> {
@@ -656,9 +1095,17 @@ fn score_post_tagged_candidate(
         score += 1;
     }
 
-    if (0..=2).contains(&post_pc.game_difficulty) {
+    if (0..=2).contains(&post_pc.preferences.game_difficulty) {
         score += 2;
     }
+    // Handler 17 and what follows are fixed-format, so a candidate that
+    // lines them up outweighs the small-count preference above.
+    if preferences_look_valid(&post_pc.preferences) {
+        score += 10;
+    }
+    if tail_parsed {
+        score += 10;
+    }
     if post_pc.selected_traits[0] == -1 || post_pc.selected_traits[1] == -1 {
         score += 1;
     }
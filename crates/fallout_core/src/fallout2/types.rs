@@ -1,3 +1,6 @@
+use crate::common::game_calendar::CalendarEpoch;
+use crate::perk::{PerkEffect, PerkInfo};
+
 // Save file constants.
 pub const SIGNATURE: &[u8] = b"FALLOUT SAVE FILE";
 pub const PREVIEW_SIZE: usize = 29_792; // 224x133 thumbnail
@@ -9,6 +12,24 @@ pub const PERK_COUNT: usize = 119;
 pub const KILL_TYPE_COUNT: usize = 19;
 pub const PC_STAT_COUNT: usize = 5;
 pub const TAGGED_SKILL_COUNT: usize = 4;
+pub const PC_LEVEL_MAX: i32 = 21;
+
+// Perk and trait indices that feed the level-up rules.
+pub const PERK_EDUCATED: usize = 18;
+pub const PERK_LIFEGIVER: usize = 28;
+pub const PERK_TAG: usize = 51;
+pub const TRAIT_SKILLED: i32 = 14;
+pub const TRAIT_GIFTED: i32 = 15;
+
+/// The game clock starts on 25 July 2241.
+pub const GAME_CALENDAR_EPOCH: CalendarEpoch = CalendarEpoch {
+    year: 2241,
+    month: 6,
+    day: 24,
+};
+pub const SKILL_USES_PER_DAY: usize = 3;
+pub const GAME_MOVIE_COUNT: usize = 17;
+pub const WORLD_MAP_SUBTILES_PER_TILE: usize = 7 * 6;
 
 // Object type and PID utilities — re-exported from shared module
 pub use crate::object::{OBJ_TYPE_CRITTER, OBJ_TYPE_ITEM, OBJ_TYPE_MISC, obj_type_from_pid};
@@ -199,6 +220,178 @@ pub const PERK_NAMES: [&str; PERK_COUNT] = [
     "JINXED",
 ];
 
+const SKILL_SMALL_GUNS: usize = 0;
+const SKILL_BIG_GUNS: usize = 1;
+const SKILL_UNARMED: usize = 3;
+const SKILL_MELEE_WEAPONS: usize = 4;
+const SKILL_FIRST_AID: usize = 6;
+const SKILL_DOCTOR: usize = 7;
+const SKILL_SNEAK: usize = 8;
+const SKILL_LOCKPICK: usize = 9;
+const SKILL_STEAL: usize = 10;
+const SKILL_TRAPS: usize = 11;
+const SKILL_SCIENCE: usize = 12;
+const SKILL_REPAIR: usize = 13;
+const SKILL_SPEECH: usize = 14;
+const SKILL_BARTER: usize = 15;
+const SKILL_GAMBLING: usize = 16;
+const SKILL_OUTDOORSMAN: usize = 17;
+
+/// Perk selection rules, indexed like `PERK_NAMES`. SPECIAL requirements are
+/// in ST, PE, EN, CH, IN, AG, LK order.
+#[rustfmt::skip]
+pub const PERK_INFO: [PerkInfo; PERK_COUNT] = [
+    PerkInfo::selectable(1, 3, [0, 5, 0, 0, 0, 0, 0]), // AWARENESS
+    PerkInfo::selectable(1, 15, [0, 0, 0, 0, 0, 6, 0]), // BONUS HTH ATTACKS
+    PerkInfo::selectable(3, 3, [6, 0, 0, 0, 0, 6, 0]), // BONUS HTH DAMAGE
+    PerkInfo::selectable(2, 6, [0, 0, 0, 0, 0, 5, 0]), // BONUS MOVE
+    PerkInfo::selectable(2, 6, [0, 0, 0, 0, 0, 6, 6]), // BONUS RANGED DAMAGE
+    PerkInfo::selectable(1, 15, [0, 6, 0, 0, 6, 7, 0]), // BONUS RATE OF FIRE
+    PerkInfo::selectable(3, 3, [0, 6, 0, 0, 0, 0, 0]), // EARLIER SEQUENCE
+    PerkInfo::selectable(3, 3, [0, 0, 6, 0, 0, 0, 0]), // FASTER HEALING
+    PerkInfo::selectable(3, 6, [0, 0, 0, 0, 0, 0, 6]), // MORE CRITICALS
+    PerkInfo::selectable(1, 3, [0, 6, 0, 0, 0, 0, 0]), // NIGHT VISION
+    PerkInfo::selectable(3, 3, [0, 0, 0, 6, 0, 0, 0]), // PRESENCE
+    PerkInfo::selectable(2, 6, [0, 0, 6, 0, 4, 0, 0]), // RAD RESISTANCE
+    PerkInfo::selectable(3, 3, [0, 0, 6, 0, 0, 0, 6]), // TOUGHNESS
+    PerkInfo::selectable(3, 3, [6, 0, 6, 0, 0, 0, 0]), // STRONG BACK
+    PerkInfo::selectable(1, 9, [0, 7, 0, 0, 6, 0, 0]), // SHARPSHOOTER
+    PerkInfo::selectable(1, 6, [0, 0, 0, 0, 0, 6, 0]).with_skills(&[(SKILL_SNEAK, 50)]), // SILENT RUNNING
+    PerkInfo::selectable(3, 3, [0, 0, 6, 0, 6, 0, 0]).with_skills(&[(SKILL_OUTDOORSMAN, 40)]), // SURVIVALIST
+    PerkInfo::selectable(1, 9, [0, 0, 0, 7, 0, 0, 0]).with_skills(&[(SKILL_BARTER, 75)]), // MASTER TRADER
+    PerkInfo::selectable(3, 6, [0, 0, 0, 0, 6, 0, 0]), // EDUCATED
+    PerkInfo::selectable(2, 3, [0, 7, 0, 0, 5, 6, 0]).with_skills(&[(SKILL_FIRST_AID, 40)]), // HEALER
+    PerkInfo::selectable(1, 6, [0, 0, 0, 0, 0, 0, 8]), // FORTUNE FINDER
+    PerkInfo::selectable(1, 9, [0, 6, 0, 0, 0, 4, 6]), // BETTER CRITICALS
+    PerkInfo::selectable(1, 6, [0, 7, 0, 0, 5, 0, 0]), // EMPATHY
+    PerkInfo::selectable(1, 24, [8, 0, 0, 0, 0, 8, 0]).with_skills(&[(SKILL_UNARMED, 80)]), // SLAYER
+    PerkInfo::selectable(1, 24, [0, 8, 0, 0, 0, 8, 0]).with_skills(&[(SKILL_SMALL_GUNS, 80)]), // SNIPER
+    PerkInfo::selectable(1, 18, [0, 0, 0, 0, 0, 10, 0]).with_skills(&[(SKILL_SNEAK, 80)]), // SILENT DEATH
+    PerkInfo::selectable(2, 12, [0, 0, 0, 0, 0, 5, 0]), // ACTION BOY
+    PerkInfo::hidden(), // MENTAL BLOCK
+    PerkInfo::selectable(2, 12, [0, 0, 4, 0, 0, 0, 0]), // LIFEGIVER
+    PerkInfo::selectable(1, 9, [0, 0, 0, 0, 0, 6, 0]), // DODGER
+    PerkInfo::selectable(2, 6, [0, 0, 3, 0, 0, 0, 0]), // SNAKEATER
+    PerkInfo::selectable(1, 12, [0; 7]).with_any_skill(&[(SKILL_SCIENCE, 40), (SKILL_REPAIR, 40)]), // MR FIXIT
+    PerkInfo::selectable(1, 12, [0; 7]).with_any_skill(&[(SKILL_FIRST_AID, 40), (SKILL_DOCTOR, 40)]), // MEDIC
+    PerkInfo::selectable(1, 12, [0; 7]).with_any_skill(&[(SKILL_STEAL, 50), (SKILL_LOCKPICK, 50)]), // MASTER THIEF
+    PerkInfo::selectable(1, 9, [0; 7]).with_skills(&[(SKILL_SPEECH, 50)]), // SPEAKER
+    PerkInfo::selectable(1, 6, [-8, 0, 0, 0, 0, 0, 0]), // HEAVE HO
+    PerkInfo::hidden(), // FRIENDLY FOE
+    PerkInfo::selectable(1, 15, [0, 0, 0, 0, 0, 8, 0]).with_skills(&[(SKILL_STEAL, 80)]), // PICKPOCKET
+    PerkInfo::selectable(1, 6, [0; 7]).with_skills(&[(SKILL_SNEAK, 60)]), // GHOST
+    PerkInfo::selectable(1, 12, [0, 0, 0, 10, 0, 0, 0]), // CULT OF PERSONALITY
+    PerkInfo::selectable(1, 9, [0, 0, 0, 0, 0, 0, 8]), // SCROUNGER
+    PerkInfo::selectable(1, 9, [0; 7]), // EXPLORER
+    PerkInfo::selectable(1, 9, [0, 0, 5, 0, 0, 0, 0]), // FLOWER CHILD
+    PerkInfo::selectable(2, 6, [0, 0, 6, 0, 0, 0, 0]).with_skills(&[(SKILL_OUTDOORSMAN, 40)]), // PATHFINDER
+    PerkInfo::hidden(), // ANIMAL FRIEND
+    PerkInfo::selectable(1, 3, [0, 7, 0, 0, 0, 0, 0]), // SCOUT
+    PerkInfo::selectable(1, 9, [0, 0, 0, 0, 0, 0, 4]), // MYSTERIOUS STRANGER
+    PerkInfo::selectable(1, 6, [0, 6, 0, 0, 0, 0, 0]), // RANGER
+    PerkInfo::selectable(1, 3, [0, 0, 0, 0, 0, 5, 0]), // QUICK POCKETS
+    PerkInfo::selectable(3, 3, [0, 0, 0, 0, 4, 0, 0]), // SMOOTH TALKER
+    PerkInfo::selectable(3, 3, [0, 0, 0, 0, 4, 0, 0]), // SWIFT LEARNER
+    PerkInfo::selectable(1, 12, [0; 7]), // TAG
+    PerkInfo::selectable(1, 9, [0; 7]), // MUTATE
+    PerkInfo::addiction(), // NUKA COLA ADDICTION
+    PerkInfo::addiction(), // BUFFOUT ADDICTION
+    PerkInfo::addiction(), // MENTATS ADDICTION
+    PerkInfo::addiction(), // PSYCHO ADDICTION
+    PerkInfo::addiction(), // RADAWAY ADDICTION
+    PerkInfo::hidden(), // WEAPON LONG RANGE
+    PerkInfo::hidden(), // WEAPON ACCURATE
+    PerkInfo::hidden(), // WEAPON PENETRATE
+    PerkInfo::hidden(), // WEAPON KNOCKBACK
+    PerkInfo::hidden(), // POWERED ARMOR
+    PerkInfo::hidden(), // COMBAT ARMOR
+    PerkInfo::hidden(), // WEAPON SCOPE RANGE
+    PerkInfo::hidden(), // WEAPON FAST RELOAD
+    PerkInfo::hidden(), // WEAPON NIGHT SIGHT
+    PerkInfo::hidden(), // WEAPON FLAMEBOY
+    PerkInfo::hidden(), // ARMOR ADVANCED I
+    PerkInfo::hidden(), // ARMOR ADVANCED II
+    PerkInfo::addiction(), // JET ADDICTION
+    PerkInfo::addiction(), // TRAGIC ADDICTION
+    PerkInfo::hidden(), // ARMOR CHARISMA
+    PerkInfo::hidden(), // GECKO SKINNING
+    PerkInfo::hidden(), // DERMAL IMPACT ARMOR
+    PerkInfo::hidden(), // DERMAL IMPACT ASSAULT ENHANCEMENT
+    PerkInfo::hidden(), // PHOENIX ARMOR IMPLANTS
+    PerkInfo::hidden(), // PHOENIX ASSAULT ENHANCEMENT
+    PerkInfo::hidden(), // VAULT CITY INOCULATIONS
+    PerkInfo::selectable(1, 6, [-9, 0, 0, 0, 0, 0, 0]), // ADRENALINE RUSH
+    PerkInfo::selectable(1, 3, [0, 6, 0, 0, 0, 0, 0]), // CAUTIOUS NATURE
+    PerkInfo::selectable(1, 3, [0, 0, 0, 0, 6, 0, 0]), // COMPREHENSION
+    PerkInfo::selectable(1, 9, [0, 0, 0, 0, 0, 4, 0]).with_skills(&[(SKILL_TRAPS, 75)]), // DEMOLITION EXPERT
+    PerkInfo::selectable(1, 6, [0; 7]).with_skills(&[(SKILL_GAMBLING, 50)]), // GAMBLER
+    PerkInfo::selectable(1, 12, [-9, 0, 0, 0, 0, 0, 0]), // GAIN STRENGTH
+    PerkInfo::selectable(1, 12, [0, -9, 0, 0, 0, 0, 0]), // GAIN PERCEPTION
+    PerkInfo::selectable(1, 12, [0, 0, -9, 0, 0, 0, 0]), // GAIN ENDURANCE
+    PerkInfo::selectable(1, 12, [0, 0, 0, -9, 0, 0, 0]), // GAIN CHARISMA
+    PerkInfo::selectable(1, 12, [0, 0, 0, 0, -9, 0, 0]), // GAIN INTELLIGENCE
+    PerkInfo::selectable(1, 12, [0, 0, 0, 0, 0, -9, 0]), // GAIN AGILITY
+    PerkInfo::selectable(1, 12, [0, 0, 0, 0, 0, 0, -9]), // GAIN LUCK
+    PerkInfo::selectable(1, 6, [0; 7]).with_skills(&[(SKILL_STEAL, 50)]), // HARMLESS
+    PerkInfo::selectable(1, 3, [0; 7]), // HERE AND NOW
+    PerkInfo::selectable(1, 12, [0; 7]).with_any_skill(&[(SKILL_UNARMED, 75), (SKILL_MELEE_WEAPONS, 75)]), // HTH EVADE
+    PerkInfo::selectable(1, 3, [0, 0, 5, 0, 0, 5, 0]), // KAMA SUTRA MASTER
+    PerkInfo::selectable(1, 9, [0, 0, 0, 6, 0, 0, 0]), // KARMA BEACON
+    PerkInfo::selectable(1, 9, [0, 0, 0, 0, 0, 5, 5]), // LIGHT STEP
+    PerkInfo::selectable(1, 12, [0; 7]).with_skills(&[(SKILL_DOCTOR, 60)]), // LIVING ANATOMY
+    PerkInfo::selectable(1, 3, [0, 0, 0, -9, 0, 0, 0]), // MAGNETIC PERSONALITY
+    PerkInfo::selectable(1, 6, [0; 7]).with_skills(&[(SKILL_BARTER, 50), (SKILL_SPEECH, 50)]), // NEGOTIATOR
+    PerkInfo::selectable(1, 6, [0; 7]), // PACK RAT
+    PerkInfo::selectable(1, 9, [0; 7]).with_skills(&[(SKILL_BIG_GUNS, 75)]), // PYROMANIAC
+    PerkInfo::selectable(1, 3, [0, 0, 0, 0, 0, 5, 0]), // QUICK RECOVERY
+    PerkInfo::selectable(1, 6, [0; 7]).with_skills(&[(SKILL_BARTER, 50)]), // SALESMAN
+    PerkInfo::selectable(1, 3, [6, 0, 0, 0, 0, 0, 0]), // STONEWALL
+    PerkInfo::selectable(1, 3, [0; 7]), // THIEF
+    PerkInfo::selectable(1, 12, [0, 0, 0, 0, 0, -9, 0]), // WEAPON HANDLING
+    PerkInfo::hidden(), // VAULT CITY TRAINING
+    PerkInfo::hidden(), // ALCOHOL RAISED HIT POINTS
+    PerkInfo::hidden(), // ALCOHOL RAISED HIT POINTS II
+    PerkInfo::hidden(), // ALCOHOL LOWERED HIT POINTS
+    PerkInfo::hidden(), // ALCOHOL LOWERED HIT POINTS II
+    PerkInfo::hidden(), // AUTODOC RAISED HIT POINTS
+    PerkInfo::hidden(), // AUTODOC RAISED HIT POINTS II
+    PerkInfo::hidden(), // AUTODOC LOWERED HIT POINTS
+    PerkInfo::hidden(), // AUTODOC LOWERED HIT POINTS II
+    PerkInfo::hidden(), // EXPERT EXCREMENT EXPEDITOR
+    PerkInfo::hidden(), // WEAPON ENHANCED KNOCKOUT
+    PerkInfo::hidden(), // JINXED
+];
+
+const STAT_RADIATION_RESISTANCE: usize = 31;
+
+/// Effects of the perks armor protos grant while worn.
+pub const ARMOR_PERK_EFFECTS: [PerkEffect; 4] = [
+    PerkEffect {
+        perk: 62, // POWERED ARMOR
+        stat: STAT_RADIATION_RESISTANCE,
+        modifier: 30,
+        special: [3, 0, 0, 0, 0, 0, 0],
+    },
+    PerkEffect {
+        perk: 63, // COMBAT ARMOR
+        stat: STAT_RADIATION_RESISTANCE,
+        modifier: 20,
+        special: [0; 7],
+    },
+    PerkEffect {
+        perk: 68, // ARMOR ADVANCED I
+        stat: STAT_RADIATION_RESISTANCE,
+        modifier: 60,
+        special: [4, 0, 0, 0, 0, 0, 0],
+    },
+    PerkEffect {
+        perk: 69, // ARMOR ADVANCED II
+        stat: STAT_RADIATION_RESISTANCE,
+        modifier: 75,
+        special: [4, 0, 0, 0, 0, 0, 0],
+    },
+];
+
 pub const TRAIT_NAMES: [&str; 16] = [
     "Fast Metabolism",
     "Bruiser",
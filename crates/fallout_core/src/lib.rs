@@ -1,8 +1,14 @@
+pub mod automap;
 pub mod common;
 pub mod core_api;
+pub mod event_queue;
 pub mod fallout1;
 pub mod fallout2;
 pub mod gender;
 pub mod layout;
+pub mod map_save;
 pub mod object;
+pub mod perk;
 pub mod reader;
+pub mod sfall;
+pub mod slots;
@@ -0,0 +1,757 @@
+//! Saved map state: the per-map `*.SAV` files the game writes beside `SAVE.DAT`.
+//!
+//! Fallout 2 gzips these files, Fallout 1 stores them as-is. Once
+//! decompressed both share one layout: a header, map and script local
+//! variables, the floor/roof squares of each present elevation, the map's
+//! script lists and finally every object on each elevation.
+
+use std::collections::BTreeSet;
+use std::io::{self, Cursor, Read, Write};
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+use crate::object::{
+    CRITTER_DATA_LEN, GameObject, InventoryItem, OBJ_TYPE_CRITTER, OBJ_TYPE_ITEM, OBJECT_BASE_LEN,
+    OBJECT_DATA_FLAGS_LEN, OBJECT_INVENTORY_HEADER_LEN, extra_data_size_candidates,
+    obj_type_from_pid,
+};
+use crate::reader::BigEndianReader;
+
+pub const MAP_VERSION_FALLOUT1: i32 = 19;
+pub const MAP_VERSION_FALLOUT2: i32 = 20;
+pub const ELEVATION_COUNT: usize = 3;
+pub const SQUARE_GRID_SIZE: usize = 100 * 100;
+pub const SCRIPT_TYPE_COUNT: usize = 5;
+pub const SCRIPT_LIST_EXTENT_SIZE: usize = 16;
+
+/// Header flag set on maps written as part of a savegame.
+pub const MAP_FLAG_SAVED: i32 = 0x01;
+
+const MAP_NAME_LEN: usize = 16;
+const MAP_HEADER_PADDING_FIELDS: usize = 44;
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const SCRIPT_TYPE_SPATIAL: i32 = 1;
+const SCRIPT_TYPE_TIMED: i32 = 2;
+const MAX_MAP_VAR_COUNT: i32 = 10_000;
+const MAX_SCRIPT_COUNT: i32 = 10_000;
+const MAX_OBJECTS_PER_ELEVATION: i32 = 100_000;
+const MAX_INVENTORY_LENGTH: i32 = 1000;
+const MAX_INVENTORY_QUANTITY: i32 = 1_000_000;
+pub const HEX_GRID_SIZE: i32 = 200 * 200;
+const ROTATION_COUNT: i32 = 6;
+const MAX_OBJECT_LAYOUT_STEPS: usize = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapHeader {
+    pub version: i32,
+    /// Raw, NUL-padded map file name; see [`MapHeader::name`].
+    pub name: [u8; MAP_NAME_LEN],
+    pub entering_tile: i32,
+    pub entering_elevation: i32,
+    pub entering_rotation: i32,
+    pub script_index: i32,
+    /// `MAP_FLAG_SAVED`, plus `0x02 << elevation` for each elevation the map lacks.
+    pub flags: i32,
+    pub darkness: i32,
+    pub map_index: i32,
+    pub last_visit_time: u32,
+    pub padding: [i32; MAP_HEADER_PADDING_FIELDS],
+}
+
+impl MapHeader {
+    pub fn name(&self) -> String {
+        let end = self
+            .name
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(MAP_NAME_LEN);
+        String::from_utf8_lossy(&self.name[..end]).to_string()
+    }
+
+    pub fn has_elevation(&self, elevation: usize) -> bool {
+        elevation < ELEVATION_COUNT && self.flags & (0x02 << elevation) == 0
+    }
+}
+
+/// Data that only spatial and timed scripts carry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptTarget {
+    None,
+    Spatial { built_tile: i32, radius: i32 },
+    Timed { time: i32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapScript {
+    pub sid: i32,
+    pub next: i32,
+    pub target: ScriptTarget,
+    pub flags: i32,
+    pub index: i32,
+    /// Program pointer at save time; meaningless once loaded.
+    pub program: i32,
+    pub owner_id: i32,
+    /// Start of this script's slice of [`MapSave::local_vars`].
+    pub local_vars_offset: i32,
+    pub local_vars_count: i32,
+    pub return_value: i32,
+    pub action: i32,
+    pub fixed_param: i32,
+    pub action_being_used: i32,
+    pub script_overrides: i32,
+    pub unknown_48: i32,
+    pub how_much: i32,
+    pub unknown_50: i32,
+}
+
+impl MapScript {
+    /// Script type from the SID's high byte (system, spatial, timed, item, critter).
+    pub fn script_type(&self) -> i32 {
+        self.sid >> 24
+    }
+}
+
+/// A block of script slots. The game always writes all 16 slots; only the
+/// first `length` hold live scripts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptListExtent {
+    pub scripts: Vec<MapScript>,
+    pub length: i32,
+    pub next: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptList {
+    pub count: i32,
+    pub extents: Vec<ScriptListExtent>,
+}
+
+impl ScriptList {
+    pub fn scripts(&self) -> impl Iterator<Item = &MapScript> {
+        self.extents.iter().flat_map(|extent| {
+            let live = usize::try_from(extent.length).unwrap_or(0);
+            extent.scripts.iter().take(live)
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MapSave {
+    /// Whether the file was gzip-compressed (Fallout 2) when read.
+    pub compressed: bool,
+    pub header: MapHeader,
+    /// Map-wide script variables (`MVAR_*`).
+    pub map_vars: Vec<i32>,
+    /// Local variables of the map's scripts.
+    pub local_vars: Vec<i32>,
+    /// Floor/roof tile ids per elevation; `None` where the header marks the
+    /// elevation as absent.
+    pub squares: [Option<Vec<i32>>; ELEVATION_COUNT],
+    pub script_lists: Vec<ScriptList>,
+    pub objects: [Vec<GameObject>; ELEVATION_COUNT],
+}
+
+impl MapSave {
+    /// Parse a map save as read from disk, gzipped or not.
+    pub fn parse(bytes: &[u8]) -> io::Result<Self> {
+        if bytes.starts_with(&GZIP_MAGIC) {
+            let mut payload = Vec::new();
+            GzDecoder::new(bytes).read_to_end(&mut payload)?;
+            let mut map = Self::parse_uncompressed(&payload)?;
+            map.compressed = true;
+            Ok(map)
+        } else {
+            Self::parse_uncompressed(bytes)
+        }
+    }
+
+    pub fn parse_uncompressed(data: &[u8]) -> io::Result<Self> {
+        let mut r = BigEndianReader::new(Cursor::new(data));
+
+        let version = r.read_i32()?;
+        if version != MAP_VERSION_FALLOUT1 && version != MAP_VERSION_FALLOUT2 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported map version {version}"),
+            ));
+        }
+        let name: [u8; MAP_NAME_LEN] = r
+            .read_bytes(MAP_NAME_LEN)?
+            .try_into()
+            .expect("read_bytes returns the requested length");
+        let entering_tile = r.read_i32()?;
+        let entering_elevation = r.read_i32()?;
+        let entering_rotation = r.read_i32()?;
+        let local_var_count = read_count(&mut r, MAX_MAP_VAR_COUNT, "local var")?;
+        let script_index = r.read_i32()?;
+        let flags = r.read_i32()?;
+        let darkness = r.read_i32()?;
+        let map_var_count = read_count(&mut r, MAX_MAP_VAR_COUNT, "map var")?;
+        let map_index = r.read_i32()?;
+        let last_visit_time = r.read_u32()?;
+        let padding = r.read_i32_array::<MAP_HEADER_PADDING_FIELDS>()?;
+        let header = MapHeader {
+            version,
+            name,
+            entering_tile,
+            entering_elevation,
+            entering_rotation,
+            script_index,
+            flags,
+            darkness,
+            map_index,
+            last_visit_time,
+            padding,
+        };
+
+        let map_vars = r.read_i32_vec(map_var_count)?;
+        let local_vars = r.read_i32_vec(local_var_count)?;
+
+        let mut squares: [Option<Vec<i32>>; ELEVATION_COUNT] = Default::default();
+        for (elevation, slot) in squares.iter_mut().enumerate() {
+            if header.has_elevation(elevation) {
+                *slot = Some(r.read_i32_vec(SQUARE_GRID_SIZE)?);
+            }
+        }
+
+        let mut script_lists = Vec::with_capacity(SCRIPT_TYPE_COUNT);
+        for _ in 0..SCRIPT_TYPE_COUNT {
+            script_lists.push(parse_script_list(&mut r)?);
+        }
+
+        let objects_start = r.position()? as usize;
+        let extra_sizes = discover_object_extra_sizes(data, objects_start + 4)?;
+        let _total_object_count = r.read_i32()?;
+        let mut extra_sizes = extra_sizes.into_iter();
+        let mut objects: [Vec<GameObject>; ELEVATION_COUNT] = Default::default();
+        for list in &mut objects {
+            let count = r.read_i32()?;
+            for _ in 0..count {
+                list.push(GameObject::parse_with_extra_sizes(
+                    &mut r,
+                    &mut extra_sizes,
+                )?);
+            }
+        }
+
+        Ok(Self {
+            compressed: false,
+            header,
+            map_vars,
+            local_vars,
+            squares,
+            script_lists,
+            objects,
+        })
+    }
+
+    /// Encode the map the way it was read: gzipped when the source was.
+    ///
+    /// The uncompressed form is byte-exact; the gzip stream of an unedited
+    /// map may differ from the game's own encoder, so callers that care keep
+    /// the original file for maps they did not touch.
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let payload = self.to_uncompressed_bytes()?;
+        if !self.compressed {
+            return Ok(payload);
+        }
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&payload)?;
+        encoder.finish()
+    }
+
+    pub fn to_uncompressed_bytes(&self) -> io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        let h = &self.header;
+        push_i32(&mut out, h.version);
+        out.extend_from_slice(&h.name);
+        push_i32(&mut out, h.entering_tile);
+        push_i32(&mut out, h.entering_elevation);
+        push_i32(&mut out, h.entering_rotation);
+        push_i32(&mut out, self.local_vars.len() as i32);
+        push_i32(&mut out, h.script_index);
+        push_i32(&mut out, h.flags);
+        push_i32(&mut out, h.darkness);
+        push_i32(&mut out, self.map_vars.len() as i32);
+        push_i32(&mut out, h.map_index);
+        out.extend_from_slice(&h.last_visit_time.to_be_bytes());
+        for value in h.padding {
+            push_i32(&mut out, value);
+        }
+
+        for &value in self.map_vars.iter().chain(&self.local_vars) {
+            push_i32(&mut out, value);
+        }
+
+        for (elevation, squares) in self.squares.iter().enumerate() {
+            match squares {
+                Some(squares) if squares.len() == SQUARE_GRID_SIZE => {
+                    squares.iter().for_each(|&value| push_i32(&mut out, value));
+                }
+                Some(squares) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "elevation {elevation} has {} squares, expected {SQUARE_GRID_SIZE}",
+                            squares.len()
+                        ),
+                    ));
+                }
+                None => {}
+            }
+        }
+
+        for list in &self.script_lists {
+            emit_script_list(list, &mut out)?;
+        }
+
+        let total: usize = self.objects.iter().map(Vec::len).sum();
+        push_i32(&mut out, total as i32);
+        for list in &self.objects {
+            push_i32(&mut out, list.len() as i32);
+            for object in list {
+                object.emit_to_vec(&mut out)?;
+            }
+        }
+
+        Ok(out)
+    }
+
+    pub fn name(&self) -> String {
+        self.header.name()
+    }
+
+    pub fn map_var(&self, index: usize) -> Option<i32> {
+        self.map_vars.get(index).copied()
+    }
+
+    pub fn set_map_var(&mut self, index: usize, value: i32) -> io::Result<()> {
+        let count = self.map_vars.len();
+        let slot = self.map_vars.get_mut(index).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid map var index {index}, expected 0..{count}"),
+            )
+        })?;
+        *slot = value;
+        Ok(())
+    }
+
+    /// Every top-level object with the elevation it sits on.
+    pub fn all_objects(&self) -> impl Iterator<Item = (usize, &GameObject)> {
+        self.objects
+            .iter()
+            .enumerate()
+            .flat_map(|(elevation, list)| list.iter().map(move |object| (elevation, object)))
+    }
+
+    pub fn critters(&self) -> impl Iterator<Item = (usize, &GameObject)> {
+        self.all_objects()
+            .filter(|(_, object)| obj_type_from_pid(object.pid) == OBJ_TYPE_CRITTER)
+    }
+
+    /// Items lying on the map, including containers.
+    pub fn items(&self) -> impl Iterator<Item = (usize, &GameObject)> {
+        self.all_objects()
+            .filter(|(_, object)| obj_type_from_pid(object.pid) == OBJ_TYPE_ITEM)
+    }
+
+    /// Items that can hold an inventory.
+    pub fn containers(&self) -> impl Iterator<Item = (usize, &GameObject)> {
+        self.items()
+            .filter(|(_, object)| is_inventory_holder(object))
+    }
+
+    /// Find a top-level object by its object id.
+    pub fn object_by_id(&self, id: i32) -> Option<&GameObject> {
+        self.all_objects()
+            .map(|(_, object)| object)
+            .find(|object| object.id == id)
+    }
+
+    fn object_by_id_mut(&mut self, id: i32) -> io::Result<&mut GameObject> {
+        self.objects
+            .iter_mut()
+            .flatten()
+            .find(|object| object.id == id)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("no object with id {id} on this map"),
+                )
+            })
+    }
+
+    /// Detach an item from the inventory of the container or critter `owner_id`.
+    /// Items an NPC has equipped come back unequipped.
+    pub fn take_inventory_item(
+        &mut self,
+        owner_id: i32,
+        pid: i32,
+        quantity: Option<i32>,
+    ) -> io::Result<InventoryItem> {
+        let owner = self.object_by_id_mut(owner_id)?;
+        crate::object::take_inventory_item(&mut owner.inventory, pid, quantity, true)
+    }
+
+    /// Put an item into the inventory of the container or critter `owner_id`.
+    pub fn insert_inventory_item(&mut self, owner_id: i32, item: InventoryItem) -> io::Result<()> {
+        let mut used_ids = BTreeSet::new();
+        for (_, object) in self.all_objects() {
+            object.collect_object_ids(&mut used_ids);
+        }
+        let owner = self.object_by_id_mut(owner_id)?;
+        if !is_inventory_holder(owner) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("object {owner_id} is not a container or critter"),
+            ));
+        }
+        crate::object::insert_inventory_item(&mut owner.inventory, item, &used_ids)
+    }
+}
+
+/// Containers and critters: the objects whose inventories can be edited.
+/// Without protos an empty container is recognised by the inventory
+/// capacity the game reserved for it.
+pub fn is_inventory_holder(object: &GameObject) -> bool {
+    match obj_type_from_pid(object.pid) {
+        OBJ_TYPE_CRITTER => true,
+        OBJ_TYPE_ITEM => !object.inventory.is_empty() || object.inventory_capacity > 0,
+        _ => false,
+    }
+}
+
+fn read_count<R: Read + std::io::Seek>(
+    r: &mut BigEndianReader<R>,
+    max: i32,
+    what: &str,
+) -> io::Result<usize> {
+    let count = r.read_i32()?;
+    if !(0..=max).contains(&count) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid {what} count {count}"),
+        ));
+    }
+    Ok(count as usize)
+}
+
+fn parse_script_list<R: Read + std::io::Seek>(
+    r: &mut BigEndianReader<R>,
+) -> io::Result<ScriptList> {
+    let count = r.read_i32()?;
+    if !(0..=MAX_SCRIPT_COUNT).contains(&count) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid script count {count}"),
+        ));
+    }
+    let extent_count = (count as usize).div_ceil(SCRIPT_LIST_EXTENT_SIZE);
+    let mut extents = Vec::with_capacity(extent_count);
+    for _ in 0..extent_count {
+        let mut scripts = Vec::with_capacity(SCRIPT_LIST_EXTENT_SIZE);
+        for _ in 0..SCRIPT_LIST_EXTENT_SIZE {
+            scripts.push(parse_script(r)?);
+        }
+        let length = r.read_i32()?;
+        let next = r.read_i32()?;
+        extents.push(ScriptListExtent {
+            scripts,
+            length,
+            next,
+        });
+    }
+    Ok(ScriptList { count, extents })
+}
+
+fn parse_script<R: Read + std::io::Seek>(r: &mut BigEndianReader<R>) -> io::Result<MapScript> {
+    let sid = r.read_i32()?;
+    let next = r.read_i32()?;
+    let target = match sid >> 24 {
+        SCRIPT_TYPE_SPATIAL => ScriptTarget::Spatial {
+            built_tile: r.read_i32()?,
+            radius: r.read_i32()?,
+        },
+        SCRIPT_TYPE_TIMED => ScriptTarget::Timed {
+            time: r.read_i32()?,
+        },
+        _ => ScriptTarget::None,
+    };
+    Ok(MapScript {
+        sid,
+        next,
+        target,
+        flags: r.read_i32()?,
+        index: r.read_i32()?,
+        program: r.read_i32()?,
+        owner_id: r.read_i32()?,
+        local_vars_offset: r.read_i32()?,
+        local_vars_count: r.read_i32()?,
+        return_value: r.read_i32()?,
+        action: r.read_i32()?,
+        fixed_param: r.read_i32()?,
+        action_being_used: r.read_i32()?,
+        script_overrides: r.read_i32()?,
+        unknown_48: r.read_i32()?,
+        how_much: r.read_i32()?,
+        unknown_50: r.read_i32()?,
+    })
+}
+
+fn emit_script_list(list: &ScriptList, out: &mut Vec<u8>) -> io::Result<()> {
+    let expected_extents = usize::try_from(list.count)
+        .unwrap_or(0)
+        .div_ceil(SCRIPT_LIST_EXTENT_SIZE);
+    if list.extents.len() != expected_extents {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "script list of {} scripts has {} extents, expected {expected_extents}",
+                list.count,
+                list.extents.len()
+            ),
+        ));
+    }
+    push_i32(out, list.count);
+    for extent in &list.extents {
+        if extent.scripts.len() != SCRIPT_LIST_EXTENT_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "script list extent has {} slots, expected {SCRIPT_LIST_EXTENT_SIZE}",
+                    extent.scripts.len()
+                ),
+            ));
+        }
+        for script in &extent.scripts {
+            push_i32(out, script.sid);
+            push_i32(out, script.next);
+            match script.target {
+                ScriptTarget::None => {}
+                ScriptTarget::Spatial { built_tile, radius } => {
+                    push_i32(out, built_tile);
+                    push_i32(out, radius);
+                }
+                ScriptTarget::Timed { time } => push_i32(out, time),
+            }
+            for value in [
+                script.flags,
+                script.index,
+                script.program,
+                script.owner_id,
+                script.local_vars_offset,
+                script.local_vars_count,
+                script.return_value,
+                script.action,
+                script.fixed_param,
+                script.action_being_used,
+                script.script_overrides,
+                script.unknown_48,
+                script.how_much,
+                script.unknown_50,
+            ] {
+                push_i32(out, value);
+            }
+        }
+        push_i32(out, extent.length);
+        push_i32(out, extent.next);
+    }
+    Ok(())
+}
+
+fn push_i32(out: &mut Vec<u8>, value: i32) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+fn i32_at(data: &[u8], pos: usize) -> Option<i32> {
+    let bytes = data.get(pos..pos.checked_add(4)?)?;
+    Some(i32::from_be_bytes(bytes.try_into().ok()?))
+}
+
+struct ObjectHeader {
+    pid: i32,
+    inventory_length: usize,
+}
+
+/// Sanity-check an object record at `pos` without knowing its layout yet.
+fn object_header_at(data: &[u8], pos: usize, elevation: Option<usize>) -> Option<ObjectHeader> {
+    let field = |index: usize| i32_at(data, pos + index * 4);
+    let tile = field(1)?;
+    let rotation = field(7)?;
+    let fid = field(8)?;
+    let object_elevation = field(10)?;
+    let pid = field(11)?;
+    let inventory_length = field(18)?;
+
+    // PID -1 marks script-only anchor objects; they save just a flags field.
+    if pid != -1
+        && (!(0..=5).contains(&(pid >> 24)) || obj_type_from_pid(fid) != obj_type_from_pid(pid))
+    {
+        return None;
+    }
+    if !(0..ELEVATION_COUNT as i32).contains(&object_elevation)
+        || elevation.is_some_and(|e| e as i32 != object_elevation)
+        || !(-1..HEX_GRID_SIZE).contains(&tile)
+        || !(0..ROTATION_COUNT).contains(&rotation)
+        || !(-1..=MAX_INVENTORY_LENGTH).contains(&inventory_length)
+    {
+        return None;
+    }
+    Some(ObjectHeader {
+        pid,
+        inventory_length: inventory_length.max(0) as usize,
+    })
+}
+
+#[derive(Clone, Copy)]
+struct ListLevel {
+    remaining: usize,
+    inventory: bool,
+}
+
+struct LayoutChoice {
+    data_start: usize,
+    levels: Vec<ListLevel>,
+    elevation: usize,
+    sizes_len: usize,
+    inventory_length: usize,
+    candidates: &'static [u8],
+    next: usize,
+}
+
+/// Work out the subtype data size of every object in the map's object lists.
+///
+/// Item and scenery records carry 0, 4 or 8 bytes depending on a proto
+/// subtype we do not have, so this walks the lists trying each size and
+/// backtracks whenever the bytes that follow stop looking like objects. The
+/// walk must end exactly at the end of the data. Sizes are returned in file
+/// order for [`GameObject::parse_with_extra_sizes`].
+fn discover_object_extra_sizes(data: &[u8], start: usize) -> io::Result<Vec<u8>> {
+    let mut sizes = Vec::new();
+    let mut levels: Vec<ListLevel> = Vec::new();
+    let mut elevation = 0usize;
+    let mut pos = start;
+    let mut choices: Vec<LayoutChoice> = Vec::new();
+
+    let apply = |sizes: &mut Vec<u8>,
+                 levels: &mut Vec<ListLevel>,
+                 data_start: usize,
+                 size: u8,
+                 inventory_length: usize| {
+        sizes.push(size);
+        if inventory_length > 0 {
+            levels.push(ListLevel {
+                remaining: inventory_length,
+                inventory: true,
+            });
+        }
+        data_start + size as usize
+    };
+
+    for _ in 0..MAX_OBJECT_LAYOUT_STEPS {
+        let advanced = match levels.last_mut() {
+            None if elevation == ELEVATION_COUNT => {
+                if pos == data.len() {
+                    return Ok(sizes);
+                }
+                false
+            }
+            None => match i32_at(data, pos) {
+                Some(count) if (0..=MAX_OBJECTS_PER_ELEVATION).contains(&count) => {
+                    pos += 4;
+                    elevation += 1;
+                    levels.push(ListLevel {
+                        remaining: count as usize,
+                        inventory: false,
+                    });
+                    true
+                }
+                _ => false,
+            },
+            Some(level) if level.remaining == 0 => {
+                levels.pop();
+                true
+            }
+            Some(level) => {
+                level.remaining -= 1;
+                let inventory = level.inventory;
+                let quantity_ok = !inventory
+                    || i32_at(data, pos)
+                        .is_some_and(|qty| (1..=MAX_INVENTORY_QUANTITY).contains(&qty));
+                let object_pos = if inventory { pos + 4 } else { pos };
+                let expected_elevation = (!inventory).then_some(elevation - 1);
+                match object_header_at(data, object_pos, expected_elevation) {
+                    Some(header) if quantity_ok => {
+                        let fixed = OBJECT_BASE_LEN + OBJECT_INVENTORY_HEADER_LEN;
+                        let (data_start, candidates): (usize, &'static [u8]) =
+                            if obj_type_from_pid(header.pid) == OBJ_TYPE_CRITTER {
+                                (object_pos + fixed + CRITTER_DATA_LEN, &[0])
+                            } else {
+                                (
+                                    object_pos + fixed + OBJECT_DATA_FLAGS_LEN,
+                                    extra_data_size_candidates(header.pid),
+                                )
+                            };
+                        if candidates.len() > 1 {
+                            choices.push(LayoutChoice {
+                                data_start,
+                                levels: levels.clone(),
+                                elevation,
+                                sizes_len: sizes.len(),
+                                inventory_length: header.inventory_length,
+                                candidates,
+                                next: 1,
+                            });
+                        }
+                        pos = apply(
+                            &mut sizes,
+                            &mut levels,
+                            data_start,
+                            candidates[0],
+                            header.inventory_length,
+                        );
+                        true
+                    }
+                    _ => false,
+                }
+            }
+        };
+        if advanced {
+            continue;
+        }
+
+        // Dead end: resume from the most recent object with untried sizes.
+        loop {
+            let Some(choice) = choices.last_mut() else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("could not lay out map objects starting at offset {start}"),
+                ));
+            };
+            if choice.next < choice.candidates.len() {
+                let size = choice.candidates[choice.next];
+                choice.next += 1;
+                levels.clone_from(&choice.levels);
+                elevation = choice.elevation;
+                sizes.truncate(choice.sizes_len);
+                pos = apply(
+                    &mut sizes,
+                    &mut levels,
+                    choice.data_start,
+                    size,
+                    choice.inventory_length,
+                );
+                break;
+            }
+            choices.pop();
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("gave up laying out map objects starting at offset {start}"),
+    ))
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::io::{self, Read, Seek};
 
 use crate::reader::BigEndianReader;
@@ -5,13 +6,62 @@ use crate::reader::BigEndianReader;
 // Object type extracted from PID: (pid >> 24) & 0x0F
 pub const OBJ_TYPE_ITEM: i32 = 0;
 pub const OBJ_TYPE_CRITTER: i32 = 1;
+pub const OBJ_TYPE_SCENERY: i32 = 2;
+pub const OBJ_TYPE_WALL: i32 = 3;
 pub const OBJ_TYPE_MISC: i32 = 5;
 
+// Item subtypes as stored at offset 0x20 of an item .PRO file.
+pub const ITEM_TYPE_ARMOR: i32 = 0;
+pub const ITEM_TYPE_CONTAINER: i32 = 1;
+pub const ITEM_TYPE_DRUG: i32 = 2;
+pub const ITEM_TYPE_WEAPON: i32 = 3;
+pub const ITEM_TYPE_AMMO: i32 = 4;
+pub const ITEM_TYPE_MISC: i32 = 5;
+pub const ITEM_TYPE_KEY: i32 = 6;
+
+// Object flags marking an inventory item as equipped by its owner.
+pub const OBJECT_FLAG_RIGHT_HAND: i32 = 0x0100_0000;
+pub const OBJECT_FLAG_LEFT_HAND: i32 = 0x0200_0000;
+pub const OBJECT_FLAG_WORN: i32 = 0x0400_0000;
+pub const OBJECT_FLAGS_EQUIPPED: i32 =
+    OBJECT_FLAG_RIGHT_HAND | OBJECT_FLAG_LEFT_HAND | OBJECT_FLAG_WORN;
+// Set once the player has seen the object; the automap draws seen walls and
+// scenery.
+pub const OBJECT_FLAG_SEEN: i32 = 0x4000_0000;
+
 pub fn obj_type_from_pid(pid: i32) -> i32 {
     (pid >> 24) & 0x0F
 }
 
-#[derive(Debug)]
+/// Art ids carry the object type in the same bits as PIDs.
+pub fn obj_type_from_fid(fid: i32) -> i32 {
+    (fid >> 24) & 0x0F
+}
+
+/// Exit grids are the only misc objects that carry destination data.
+pub fn is_exit_grid_pid(pid: i32) -> bool {
+    (0x500_0010..=0x500_0017).contains(&pid)
+}
+
+// Fixed-size parts of a saved object record.
+pub(crate) const OBJECT_BASE_LEN: usize = 72;
+pub(crate) const OBJECT_INVENTORY_HEADER_LEN: usize = 12;
+pub(crate) const CRITTER_DATA_LEN: usize = 44;
+pub(crate) const OBJECT_DATA_FLAGS_LEN: usize = 4;
+
+/// Sizes the subtype data after the flags field can take for a PID.
+///
+/// Items and scenery depend on their proto subtype (weapons, doors, stairs,
+/// elevators and ladders carry 4 or 8 bytes), so all sizes are possible.
+pub(crate) fn extra_data_size_candidates(pid: i32) -> &'static [u8] {
+    match obj_type_from_pid(pid) {
+        OBJ_TYPE_ITEM | OBJ_TYPE_SCENERY => &[0, 4, 8],
+        OBJ_TYPE_MISC if is_exit_grid_pid(pid) => &[16],
+        _ => &[0],
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct GameObject {
     pub id: i32,
     pub tile: i32,
@@ -37,16 +87,17 @@ pub struct GameObject {
     pub inventory: Vec<InventoryItem>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ObjectData {
     Critter(CritterObjectData),
     Item(ItemObjectData),
+    /// Scenery, walls, tiles and misc objects other than exit grids.
     Scenery(SceneryObjectData),
+    /// Exit grids.
     Misc(MiscObjectData),
-    Other,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CritterObjectData {
     pub field_0: i32,
     pub damage_last_turn: i32,
@@ -61,34 +112,387 @@ pub struct CritterObjectData {
     pub poison: i32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ItemObjectData {
     pub flags: i32,
     pub extra_bytes: u8, // 0, 4, or 8
     pub extra_data: Vec<u8>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SceneryObjectData {
     pub flags: i32,
+    /// Subtype data: door open flags, stairs/ladder destinations or elevator
+    /// type and level. Empty for everything else.
+    pub extra_data: Vec<u8>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MiscObjectData {
+    pub flags: i32,
     pub map: i32,
     pub tile: i32,
     pub elevation: i32,
     pub rotation: i32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InventoryItem {
     pub quantity: i32,
     pub object: GameObject,
 }
 
+/// Fields from an item .PRO file needed to create a fresh instance of it.
+///
+/// `extra_data` holds the subtype-specific proto update data a new object
+/// starts with: ammo count and ammo PID for weapons, the charge/quantity for
+/// ammo and misc items, the key code for keys, and nothing for other types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemPrototype {
+    pub pid: i32,
+    pub item_type: i32,
+    pub fid: i32,
+    pub flags: i32,
+    pub light_distance: i32,
+    pub light_intensity: i32,
+    pub extra_data: Vec<u8>,
+}
+
+/// Damage protection an armor proto grants while worn, one entry per damage
+/// type (normal, laser, fire, plasma, electrical, EMP, explosion).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArmorPrototype {
+    pub pid: i32,
+    pub armor_class: i32,
+    pub damage_resistance: [i32; DAMAGE_TYPE_COUNT],
+    pub damage_threshold: [i32; DAMAGE_TYPE_COUNT],
+    /// Perk whose effect applies while the armor is worn, or -1 for none.
+    pub perk: i32,
+}
+
+pub const DAMAGE_TYPE_COUNT: usize = 7;
+
+/// Stat changes a drug proto queues when taken: one set right away and one
+/// after each of its two delays, all applied to the same three stats.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrugPrototype {
+    pub pid: i32,
+    /// Affected stats, -1 for an unused slot. A first stat of -2 is picked
+    /// at random when the drug is taken.
+    pub stats: [i32; 3],
+    pub amounts: [[i32; 3]; 3],
+}
+
+impl DrugPrototype {
+    /// Whether a drug-effect queue event with these stats and modifiers is
+    /// one of this drug's stages.
+    pub fn queued_effect(&self, stats: &[i32; 3], modifiers: &[i32; 3]) -> bool {
+        let stats_match = self
+            .stats
+            .iter()
+            .zip(stats)
+            .enumerate()
+            .all(|(i, (&proto, &queued))| proto == queued || (i == 0 && proto == -2));
+        stats_match && self.amounts.contains(modifiers)
+    }
+}
+
+/// Number of proto update bytes following the flags field for an item subtype.
+pub fn item_extra_bytes_for_type(item_type: i32) -> u8 {
+    match item_type {
+        ITEM_TYPE_WEAPON => 8,
+        ITEM_TYPE_AMMO | ITEM_TYPE_MISC | ITEM_TYPE_KEY => 4,
+        _ => 0,
+    }
+}
+
+/// Decoded proto update data of an item object.
+///
+/// The raw bytes carry no type tag, so decoding needs the item type from the
+/// item's proto. Every value is stored big-endian.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemExtraData {
+    /// Rounds currently loaded and the PID of the loaded ammo (-1 when the
+    /// weapon has never been loaded).
+    Weapon {
+        ammo_loaded: i32,
+        ammo_pid: i32,
+    },
+    /// Rounds left in the top box of an ammo stack.
+    Ammo {
+        quantity: i32,
+    },
+    /// Charges left in a misc item such as a geiger counter or motion sensor.
+    Misc {
+        charges: i32,
+    },
+    Key {
+        key_code: i32,
+    },
+}
+
+impl ItemExtraData {
+    /// Decode `extra_data` for an item of `item_type`. Returns `None` for
+    /// types without update data or when the length does not match the type.
+    pub fn decode(item_type: i32, extra_data: &[u8]) -> Option<Self> {
+        if extra_data.len() != item_extra_bytes_for_type(item_type) as usize {
+            return None;
+        }
+        let word = |index: usize| {
+            let start = index * 4;
+            i32::from_be_bytes(extra_data[start..start + 4].try_into().unwrap())
+        };
+        match item_type {
+            ITEM_TYPE_WEAPON => Some(Self::Weapon {
+                ammo_loaded: word(0),
+                ammo_pid: word(1),
+            }),
+            ITEM_TYPE_AMMO => Some(Self::Ammo { quantity: word(0) }),
+            ITEM_TYPE_MISC => Some(Self::Misc { charges: word(0) }),
+            ITEM_TYPE_KEY => Some(Self::Key { key_code: word(0) }),
+            _ => None,
+        }
+    }
+
+    pub fn to_bytes(self) -> Vec<u8> {
+        let words: &[i32] = match &self {
+            Self::Weapon {
+                ammo_loaded,
+                ammo_pid,
+            } => &[*ammo_loaded, *ammo_pid],
+            Self::Ammo { quantity } => &[*quantity],
+            Self::Misc { charges } => &[*charges],
+            Self::Key { key_code } => &[*key_code],
+        };
+        words.iter().flat_map(|word| word.to_be_bytes()).collect()
+    }
+}
+
+/// Load `rounds` of `ammo_pid` into every stack of weapon `pid`.
+///
+/// Pass an `ammo_pid` of -1 with zero rounds to leave the weapon unloaded.
+pub fn set_weapon_ammo(
+    inventory: &mut [InventoryItem],
+    pid: i32,
+    ammo_pid: i32,
+    rounds: i32,
+) -> io::Result<()> {
+    if rounds < 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid loaded rounds {rounds}, expected >= 0"),
+        ));
+    }
+    if ammo_pid == -1 {
+        if rounds != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("cannot load {rounds} rounds without an ammo pid"),
+            ));
+        }
+    } else if obj_type_from_pid(ammo_pid) != OBJ_TYPE_ITEM {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("ammo pid=0x{:08x} is not an item pid", ammo_pid as u32),
+        ));
+    }
+
+    let bytes = ItemExtraData::Weapon {
+        ammo_loaded: rounds,
+        ammo_pid,
+    }
+    .to_bytes();
+    // Check every stack before touching any, so a bad one leaves all as they were.
+    let mut stacks = inventory
+        .iter_mut()
+        .filter(|item| item.object.pid == pid)
+        .map(|item| match &mut item.object.object_data {
+            ObjectData::Item(data) if data.extra_data.len() == bytes.len() => Ok(data),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("inventory item pid={pid} is not a weapon"),
+            )),
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    if stacks.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("inventory item pid={pid} not found"),
+        ));
+    }
+    for data in &mut stacks {
+        data.extra_data.clone_from(&bytes);
+    }
+    Ok(())
+}
+
+/// Detach `quantity` of `pid` (the whole stack when `None`) from an inventory.
+///
+/// Unequipped stacks are preferred. Equipped stacks are only taken when
+/// `allow_equipped` is set, and the detached item comes back unequipped.
+/// Splitting a stack copies its object, id included; [`insert_inventory_item`]
+/// renumbers it if the id is taken where it lands.
+pub fn take_inventory_item(
+    inventory: &mut Vec<InventoryItem>,
+    pid: i32,
+    quantity: Option<i32>,
+    allow_equipped: bool,
+) -> io::Result<InventoryItem> {
+    let is_equipped = |item: &InventoryItem| item.object.flags & OBJECT_FLAGS_EQUIPPED != 0;
+    let index = inventory
+        .iter()
+        .position(|item| item.object.pid == pid && !is_equipped(item))
+        .or_else(|| inventory.iter().position(|item| item.object.pid == pid))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("inventory item pid={pid} not found"),
+            )
+        })?;
+    if !allow_equipped && is_equipped(&inventory[index]) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("inventory item pid={pid} is equipped; unequip it first"),
+        ));
+    }
+
+    let available = inventory[index].quantity;
+    let mut taken = match quantity {
+        None => inventory.remove(index),
+        Some(qty) if qty <= 0 || qty > available => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid quantity {qty} for pid={pid}, expected 1..={available}"),
+            ));
+        }
+        Some(qty) if qty == available => inventory.remove(index),
+        Some(qty) => {
+            inventory[index].quantity -= qty;
+            InventoryItem {
+                quantity: qty,
+                object: inventory[index].object.clone(),
+            }
+        }
+    };
+    taken.object.flags &= !OBJECT_FLAGS_EQUIPPED;
+    Ok(taken)
+}
+
+/// The inventory item held in the slot marked by `slot_flag`, if any.
+pub fn equipped_inventory_item(
+    inventory: &[InventoryItem],
+    slot_flag: i32,
+) -> Option<&InventoryItem> {
+    inventory
+        .iter()
+        .find(|item| item.object.flags & slot_flag != 0)
+}
+
+/// Equip one `pid` from an unequipped stack into the slot marked by
+/// `slot_flag`, unequipping whatever held the slot before.
+///
+/// Like the engine, a single item is split off a larger stack so the rest
+/// stays in the backpack.
+pub fn equip_inventory_item(owner: &mut GameObject, slot_flag: i32, pid: i32) -> io::Result<()> {
+    if !owner
+        .inventory
+        .iter()
+        .any(|item| item.object.pid == pid && item.object.flags & OBJECT_FLAGS_EQUIPPED == 0)
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("no unequipped inventory item pid={pid} to equip"),
+        ));
+    }
+    unequip_inventory_item(&mut owner.inventory, slot_flag);
+    let mut item = take_inventory_item(&mut owner.inventory, pid, Some(1), false)?;
+    item.object.flags |= slot_flag;
+    let mut used_ids = BTreeSet::new();
+    owner.collect_object_ids(&mut used_ids);
+    insert_inventory_item(&mut owner.inventory, item, &used_ids)
+}
+
+/// Clear the slot marked by `slot_flag`, returning the PID that was in it.
+pub fn unequip_inventory_item(inventory: &mut [InventoryItem], slot_flag: i32) -> Option<i32> {
+    let item = inventory
+        .iter_mut()
+        .find(|item| item.object.flags & slot_flag != 0)?;
+    item.object.flags &= !slot_flag;
+    Some(item.object.pid)
+}
+
+/// Add a detached item to an inventory the way the engine does: merged into
+/// an unequipped stack of the same item, otherwise inserted at the front.
+///
+/// `used_ids` holds the object ids already taken wherever the inventory
+/// lives; colliding ids in the new item are replaced with unused ones.
+pub fn insert_inventory_item(
+    inventory: &mut Vec<InventoryItem>,
+    mut item: InventoryItem,
+    used_ids: &BTreeSet<i32>,
+) -> io::Result<()> {
+    let equipped = item.object.flags & OBJECT_FLAGS_EQUIPPED != 0;
+    if let Some(stack) = inventory.iter_mut().find(|stack| {
+        !equipped
+            && stack.object.flags & OBJECT_FLAGS_EQUIPPED == 0
+            && stack.object.pid == item.object.pid
+            && stack.object.inventory.is_empty()
+            && item.object.inventory.is_empty()
+            && item_extra_data(&stack.object) == item_extra_data(&item.object)
+    }) {
+        stack.quantity = stack.quantity.checked_add(item.quantity).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "inventory quantity overflow for pid={}: {} + {}",
+                    item.object.pid, stack.quantity, item.quantity
+                ),
+            )
+        })?;
+        return Ok(());
+    }
+
+    let max_used = used_ids.last().copied().unwrap_or(0);
+    let mut next_id = max_used.max(item.object.max_object_id());
+    item.object.renumber_colliding_ids(used_ids, &mut next_id)?;
+    inventory.insert(0, item);
+    Ok(())
+}
+
+fn item_extra_data(object: &GameObject) -> Option<&[u8]> {
+    match &object.object_data {
+        ObjectData::Item(data) => Some(&data.extra_data),
+        _ => None,
+    }
+}
+
+/// How `GameObject` parsing decides the size of subtype data it cannot
+/// know without the object's proto.
+enum ExtraDataSizes<'a> {
+    /// Guess item sizes from the data that follows; scenery carries none.
+    Probe,
+    /// Sizes already worked out for every object, in file order.
+    Known(&'a mut dyn Iterator<Item = u8>),
+}
+
 impl GameObject {
     pub fn parse<R: Read + Seek>(r: &mut BigEndianReader<R>) -> io::Result<Self> {
+        Self::parse_impl(r, &mut ExtraDataSizes::Probe)
+    }
+
+    /// Parse an object using subtype data sizes worked out beforehand, one per
+    /// object in file order (the object itself, then its inventory depth-first).
+    pub fn parse_with_extra_sizes<R: Read + Seek>(
+        r: &mut BigEndianReader<R>,
+        extra_sizes: &mut dyn Iterator<Item = u8>,
+    ) -> io::Result<Self> {
+        Self::parse_impl(r, &mut ExtraDataSizes::Known(extra_sizes))
+    }
+
+    fn parse_impl<R: Read + Seek>(
+        r: &mut BigEndianReader<R>,
+        sizes: &mut ExtraDataSizes<'_>,
+    ) -> io::Result<Self> {
         // 18 base fields (72 bytes)
         let id = r.read_i32()?;
         let tile = r.read_i32()?;
@@ -126,27 +530,37 @@ impl GameObject {
             ));
         }
 
-        // Type-specific proto update data
-        let obj_type = obj_type_from_pid(pid);
-        let object_data = match obj_type {
+        // Type-specific proto update data. Everything but critters starts
+        // with a flags field.
+        let known_size = match sizes {
+            ExtraDataSizes::Probe => None,
+            ExtraDataSizes::Known(iter) => Some(iter.next().ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("missing extra data size for object pid=0x{pid:08x}"),
+                )
+            })?),
+        };
+        let object_data = match obj_type_from_pid(pid) {
             OBJ_TYPE_CRITTER => ObjectData::Critter(parse_critter_object_data(r)?),
-            OBJ_TYPE_ITEM => ObjectData::Item(parse_item_object_data(r)?),
-            OBJ_TYPE_MISC => {
-                // Only exit grids (PID 0x5000010..0x5000017) have extra data
-                if (0x500_0010..=0x500_0017).contains(&pid) {
-                    ObjectData::Misc(parse_misc_object_data(r)?)
-                } else {
-                    ObjectData::Other
+            OBJ_TYPE_ITEM => ObjectData::Item(match known_size {
+                Some(size) => {
+                    let flags = r.read_i32()?;
+                    ItemObjectData {
+                        flags,
+                        extra_bytes: size,
+                        extra_data: r.read_bytes(size as usize)?,
+                    }
                 }
-            }
+                None => parse_item_object_data(r)?,
+            }),
+            OBJ_TYPE_MISC if is_exit_grid_pid(pid) => ObjectData::Misc(parse_misc_object_data(r)?),
             _ => {
-                // Scenery, walls, etc.: read the flags field
-                // Without proto files we can't determine the exact subtype,
-                // but all non-critter types write at least the flags field.
-                let scenery_flags = r.read_i32()?;
-                ObjectData::Scenery(SceneryObjectData {
-                    flags: scenery_flags,
-                })
+                // Without proto files we can't determine the scenery subtype;
+                // unless the caller knows better, assume no subtype data.
+                let flags = r.read_i32()?;
+                let extra_data = r.read_bytes(known_size.unwrap_or(0) as usize)?;
+                ObjectData::Scenery(SceneryObjectData { flags, extra_data })
             }
         };
 
@@ -155,7 +569,7 @@ impl GameObject {
         let mut inventory = Vec::with_capacity(normalized_inventory_length as usize);
         for _ in 0..normalized_inventory_length {
             let quantity = r.read_i32()?;
-            let object = GameObject::parse(r)?;
+            let object = GameObject::parse_impl(r, sizes)?;
             inventory.push(InventoryItem { quantity, object });
         }
 
@@ -185,6 +599,95 @@ impl GameObject {
         })
     }
 
+    /// Build a new, unplaced item object from its prototype, the same way
+    /// the engine initializes an object it creates from a PID.
+    pub fn new_item(id: i32, proto: &ItemPrototype) -> io::Result<Self> {
+        if obj_type_from_pid(proto.pid) != OBJ_TYPE_ITEM {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("pid=0x{:08x} is not an item prototype", proto.pid),
+            ));
+        }
+        let extra_bytes = item_extra_bytes_for_type(proto.item_type);
+        if proto.extra_data.len() != extra_bytes as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "item prototype pid=0x{:08x} has {} extra bytes, expected {extra_bytes} for item type {}",
+                    proto.pid,
+                    proto.extra_data.len(),
+                    proto.item_type
+                ),
+            ));
+        }
+
+        Ok(Self {
+            id,
+            tile: -1,
+            x: 0,
+            y: 0,
+            sx: 0,
+            sy: 0,
+            frame: 0,
+            rotation: 0,
+            fid: proto.fid,
+            flags: proto.flags,
+            elevation: 0,
+            pid: proto.pid,
+            cid: -1,
+            light_distance: proto.light_distance,
+            light_intensity: proto.light_intensity,
+            outline: 0,
+            sid: -1,
+            script_index: -1,
+            inventory_length: 0,
+            inventory_capacity: 0,
+            object_data: ObjectData::Item(ItemObjectData {
+                flags: 0,
+                extra_bytes,
+                extra_data: proto.extra_data.clone(),
+            }),
+            inventory: Vec::new(),
+        })
+    }
+
+    /// Highest object id used by this object or anything nested in its inventory.
+    ///
+    /// This only sees the objects reachable from `self`. Objects on the maps
+    /// live in the slot's map saves, so an id derived from this can still
+    /// clash with one of them.
+    pub fn max_object_id(&self) -> i32 {
+        self.inventory
+            .iter()
+            .map(|item| item.object.max_object_id())
+            .fold(self.id, i32::max)
+    }
+
+    /// Add the ids of this object and everything nested in its inventory.
+    pub fn collect_object_ids(&self, ids: &mut BTreeSet<i32>) {
+        ids.insert(self.id);
+        for item in &self.inventory {
+            item.object.collect_object_ids(ids);
+        }
+    }
+
+    fn renumber_colliding_ids(
+        &mut self,
+        used_ids: &BTreeSet<i32>,
+        next_id: &mut i32,
+    ) -> io::Result<()> {
+        if used_ids.contains(&self.id) {
+            *next_id = next_id.checked_add(1).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "no free object id available")
+            })?;
+            self.id = *next_id;
+        }
+        for item in &mut self.inventory {
+            item.object.renumber_colliding_ids(used_ids, next_id)?;
+        }
+        Ok(())
+    }
+
     pub fn emit_to_vec(&self, out: &mut Vec<u8>) -> io::Result<()> {
         out.extend_from_slice(&self.id.to_be_bytes());
         out.extend_from_slice(&self.tile.to_be_bytes());
@@ -245,14 +748,15 @@ impl GameObject {
             }
             ObjectData::Scenery(data) => {
                 out.extend_from_slice(&data.flags.to_be_bytes());
+                out.extend_from_slice(&data.extra_data);
             }
             ObjectData::Misc(data) => {
+                out.extend_from_slice(&data.flags.to_be_bytes());
                 out.extend_from_slice(&data.map.to_be_bytes());
                 out.extend_from_slice(&data.tile.to_be_bytes());
                 out.extend_from_slice(&data.elevation.to_be_bytes());
                 out.extend_from_slice(&data.rotation.to_be_bytes());
             }
-            ObjectData::Other => {}
         }
 
         for item in &self.inventory {
@@ -380,6 +884,7 @@ fn parse_misc_object_data<R: Read + Seek>(
     r: &mut BigEndianReader<R>,
 ) -> io::Result<MiscObjectData> {
     Ok(MiscObjectData {
+        flags: r.read_i32()?,
         map: r.read_i32()?,
         tile: r.read_i32()?,
         elevation: r.read_i32()?,
@@ -389,9 +894,14 @@ fn parse_misc_object_data<R: Read + Seek>(
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeSet;
     use std::io::Cursor;
 
-    use super::GameObject;
+    use super::{
+        GameObject, ITEM_TYPE_AMMO, ITEM_TYPE_ARMOR, ITEM_TYPE_MISC, ITEM_TYPE_WEAPON,
+        InventoryItem, ItemExtraData, ItemPrototype, OBJECT_FLAG_WORN, ObjectData,
+        insert_inventory_item, set_weapon_ammo, take_inventory_item,
+    };
     use crate::reader::BigEndianReader;
 
     #[test]
@@ -420,4 +930,144 @@ mod tests {
         assert_eq!(parsed.inventory_length, -1);
         assert!(parsed.inventory.is_empty());
     }
+
+    #[test]
+    fn item_extra_data_decodes_by_item_type() {
+        let weapon_bytes = [0, 0, 0, 30, 0, 0, 0, 0x1D];
+        let weapon = ItemExtraData::decode(ITEM_TYPE_WEAPON, &weapon_bytes);
+        assert_eq!(
+            weapon,
+            Some(ItemExtraData::Weapon {
+                ammo_loaded: 30,
+                ammo_pid: 0x1D,
+            })
+        );
+        assert_eq!(weapon.unwrap().to_bytes(), weapon_bytes);
+        assert_eq!(
+            ItemExtraData::decode(ITEM_TYPE_AMMO, &[0, 0, 0, 24]),
+            Some(ItemExtraData::Ammo { quantity: 24 })
+        );
+        assert_eq!(
+            ItemExtraData::decode(ITEM_TYPE_WEAPON, &[0, 0, 0, 24]),
+            None
+        );
+        assert_eq!(ItemExtraData::decode(ITEM_TYPE_ARMOR, &[]), None);
+    }
+
+    #[test]
+    fn new_item_uses_prototype_fields_and_extra_data_size() {
+        let proto = ItemPrototype {
+            pid: 0x0000_0009,
+            item_type: ITEM_TYPE_WEAPON,
+            fid: 0x17,
+            flags: 0x2000_0008,
+            light_distance: 0,
+            light_intensity: 0,
+            extra_data: vec![0, 0, 0, 30, 0, 0, 0, 0x1D],
+        };
+        let object = GameObject::new_item(42, &proto).expect("weapon should build");
+        assert_eq!(object.id, 42);
+        assert_eq!(object.tile, -1);
+        assert_eq!(object.fid, 0x17);
+        assert_eq!(object.flags, 0x2000_0008);
+        match &object.object_data {
+            ObjectData::Item(data) => assert_eq!(data.extra_bytes, 8),
+            other => panic!("expected item data, got {other:?}"),
+        }
+        // 72 base + 12 inventory header + 4 item flags + 8 weapon bytes.
+        assert_eq!(object.emit_bytes().expect("emit").len(), 96);
+
+        let mismatched = ItemPrototype {
+            item_type: ITEM_TYPE_ARMOR,
+            ..proto.clone()
+        };
+        assert!(GameObject::new_item(1, &mismatched).is_err());
+
+        let not_an_item = ItemPrototype {
+            pid: 0x0100_0001,
+            ..proto
+        };
+        assert!(GameObject::new_item(1, &not_an_item).is_err());
+    }
+
+    #[test]
+    fn weapon_ammo_edits_check_every_stack_first() {
+        let weapon = ItemPrototype {
+            pid: 0x0000_0009,
+            item_type: ITEM_TYPE_WEAPON,
+            fid: 0x17,
+            flags: 0,
+            light_distance: 0,
+            light_intensity: 0,
+            extra_data: vec![0, 0, 0, 30, 0, 0, 0, 0x1D],
+        };
+        // A stack whose subtype data does not fit a weapon.
+        let odd = ItemPrototype {
+            item_type: ITEM_TYPE_MISC,
+            extra_data: vec![0, 0, 0, 0],
+            ..weapon.clone()
+        };
+        let mut inventory = vec![
+            InventoryItem {
+                quantity: 1,
+                object: GameObject::new_item(1, &weapon).expect("weapon should build"),
+            },
+            InventoryItem {
+                quantity: 1,
+                object: GameObject::new_item(2, &odd).expect("item should build"),
+            },
+        ];
+
+        assert!(set_weapon_ammo(&mut inventory, weapon.pid, 0x1D, 5).is_err());
+        match &inventory[0].object.object_data {
+            ObjectData::Item(data) => assert_eq!(data.extra_data, weapon.extra_data),
+            other => panic!("expected item data, got {other:?}"),
+        }
+
+        inventory.pop();
+        set_weapon_ammo(&mut inventory, weapon.pid, 0x1D, 5).expect("weapon should load");
+        match &inventory[0].object.object_data {
+            ObjectData::Item(data) => assert_eq!(data.extra_data, [0, 0, 0, 5, 0, 0, 0, 0x1D]),
+            other => panic!("expected item data, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn split_stacks_merge_or_take_fresh_ids() {
+        let proto = ItemPrototype {
+            pid: 0x0000_0028,
+            item_type: ITEM_TYPE_MISC,
+            fid: 0x30,
+            flags: 0,
+            light_distance: 0,
+            light_intensity: 0,
+            extra_data: vec![0, 0, 0, 0],
+        };
+        let stack = |id, quantity| InventoryItem {
+            quantity,
+            object: GameObject::new_item(id, &proto).expect("item should build"),
+        };
+
+        let mut source = vec![stack(7, 5)];
+        let taken = take_inventory_item(&mut source, proto.pid, Some(2), false).expect("split");
+        assert_eq!((taken.quantity, source[0].quantity), (2, 3));
+        assert!(take_inventory_item(&mut source, proto.pid, Some(4), false).is_err());
+
+        // Same id already used at the destination: the new stack is renumbered.
+        let mut dest = Vec::new();
+        let used_ids = BTreeSet::from([7, 20]);
+        insert_inventory_item(&mut dest, taken, &used_ids).expect("insert");
+        assert_eq!(dest[0].object.id, 21);
+
+        insert_inventory_item(&mut dest, stack(30, 4), &used_ids).expect("merge");
+        assert_eq!(dest.len(), 1);
+        assert_eq!(dest[0].quantity, 6);
+
+        let mut worn = vec![stack(8, 1)];
+        worn[0].object.flags |= OBJECT_FLAG_WORN;
+        assert!(take_inventory_item(&mut worn, proto.pid, None, false).is_err());
+        let unequipped = take_inventory_item(&mut worn, proto.pid, None, true).expect("take");
+        assert_eq!(unequipped.object.flags & OBJECT_FLAG_WORN, 0);
+        assert!(worn.is_empty());
+    }
 }
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+/// How a perk enters the perk array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PerkKind {
+    /// Offered on the perk selection screen.
+    Selectable,
+    /// Granted by scripts, items or implants; never offered to the player.
+    Hidden,
+    /// Tracks a drug addiction.
+    Addiction,
+}
+
+/// Stat changes the engine applies while a perk is in effect, from its perk
+/// table: `modifier` is added to the bonus of `stat`, and `special` to the
+/// SPECIAL bonuses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PerkEffect {
+    pub perk: usize,
+    pub stat: usize,
+    pub modifier: i32,
+    pub special: [i32; 7],
+}
+
+/// Selection rules for one perk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PerkInfo {
+    pub max_rank: i32,
+    pub min_level: i32,
+    /// One entry per SPECIAL stat: a positive value is a minimum, a negative
+    /// value the maximum allowed (`-9` means at most 9), zero no requirement.
+    pub special: [i32; 7],
+    /// Skill index and minimum skill total.
+    pub skills: &'static [(usize, i32)],
+    /// When set, meeting any one of `skills` is enough.
+    pub any_skill: bool,
+    pub kind: PerkKind,
+}
+
+impl PerkInfo {
+    pub const fn selectable(max_rank: i32, min_level: i32, special: [i32; 7]) -> Self {
+        Self {
+            max_rank,
+            min_level,
+            special,
+            skills: &[],
+            any_skill: false,
+            kind: PerkKind::Selectable,
+        }
+    }
+
+    pub const fn hidden() -> Self {
+        Self {
+            max_rank: 1,
+            min_level: 1,
+            special: [0; 7],
+            skills: &[],
+            any_skill: false,
+            kind: PerkKind::Hidden,
+        }
+    }
+
+    pub const fn addiction() -> Self {
+        Self {
+            kind: PerkKind::Addiction,
+            ..Self::hidden()
+        }
+    }
+
+    pub const fn with_skills(mut self, skills: &'static [(usize, i32)]) -> Self {
+        self.skills = skills;
+        self
+    }
+
+    pub const fn with_any_skill(mut self, skills: &'static [(usize, i32)]) -> Self {
+        self.skills = skills;
+        self.any_skill = true;
+        self
+    }
+
+    /// Check the SPECIAL requirement for one stat total.
+    pub fn special_allows(&self, stat_index: usize, total: i32) -> bool {
+        match self.special.get(stat_index).copied().unwrap_or(0) {
+            0 => true,
+            min if min > 0 => total >= min,
+            max => total <= -max,
+        }
+    }
+
+    /// Check the skill requirement against skill totals indexed by skill.
+    pub fn skills_allow(&self, skill_totals: &[i32]) -> bool {
+        let meets =
+            |&(skill, min): &(usize, i32)| skill_totals.get(skill).copied().unwrap_or(0) >= min;
+        if self.skills.is_empty() {
+            true
+        } else if self.any_skill {
+            self.skills.iter().any(meets)
+        } else {
+            self.skills.iter().all(meets)
+        }
+    }
+}
@@ -0,0 +1,498 @@
+//! Extended save data written by the sfall engine patch next to `SAVE.DAT`.
+//!
+//! - `sfallgv.sav`: script global variables, followed by sfall's object id
+//!   counter, the added-years counter and the fake perk and trait lists.
+//! - `sfallfs.sav`: files scripts created through sfall's `fs_*` functions.
+//! - `sfalldb.sav`: script arrays marked to be saved with the game.
+//!
+//! sfall writes its structures straight from memory, so every integer is
+//! little-endian, unlike the rest of the save.
+
+use std::fmt;
+use std::io::{self, Cursor, Read};
+
+pub const SFALL_GLOBALS_FILE_NAME: &str = "sfallgv.sav";
+pub const SFALL_FILE_SYSTEM_FILE_NAME: &str = "sfallfs.sav";
+pub const SFALL_ARRAYS_FILE_NAME: &str = "sfalldb.sav";
+
+/// Named globals use exactly this many ASCII characters as their key.
+pub const SFALL_GLOBAL_NAME_LEN: usize = 8;
+pub const FAKE_PERK_NAME_LEN: usize = 64;
+pub const FAKE_PERK_DESCRIPTION_LEN: usize = 1024;
+
+const GLOBAL_RECORD_LEN: usize = 16;
+const FAKE_PERK_RECORD_LEN: usize = 8 + FAKE_PERK_NAME_LEN + FAKE_PERK_DESCRIPTION_LEN;
+const ADDED_YEARS_SHIFT: u32 = 16;
+
+const ARRAY_VALUE_NONE: u32 = 0;
+const ARRAY_VALUE_INT: u32 = 1;
+const ARRAY_VALUE_FLOAT: u32 = 2;
+const ARRAY_VALUE_STR: u32 = 3;
+
+/// The key of an sfall global: an eight-character name, or the number passed
+/// to `set_sfall_global`. Both are stored as one 64-bit value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SfallGlobalKey {
+    Named(String),
+    Numbered(u64),
+}
+
+impl SfallGlobalKey {
+    /// Build a named key, checking that `name` is eight printable ASCII
+    /// characters as sfall requires.
+    pub fn named(name: &str) -> io::Result<Self> {
+        if name.len() != SFALL_GLOBAL_NAME_LEN || !name.bytes().all(is_name_byte) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "sfall global names must be {SFALL_GLOBAL_NAME_LEN} printable ASCII characters, got {name:?}"
+                ),
+            ));
+        }
+        Ok(Self::Named(name.to_string()))
+    }
+
+    fn from_raw(raw: [u8; 8]) -> Self {
+        if raw.iter().copied().all(is_name_byte) {
+            Self::Named(raw.iter().map(|&b| b as char).collect())
+        } else {
+            Self::Numbered(u64::from_le_bytes(raw))
+        }
+    }
+
+    fn to_raw(&self) -> [u8; 8] {
+        match self {
+            Self::Named(name) => {
+                let mut raw = [0u8; 8];
+                for (slot, byte) in raw.iter_mut().zip(name.bytes()) {
+                    *slot = byte;
+                }
+                raw
+            }
+            Self::Numbered(number) => number.to_le_bytes(),
+        }
+    }
+}
+
+impl fmt::Display for SfallGlobalKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Named(name) => f.write_str(name),
+            Self::Numbered(number) => write!(f, "{number}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SfallGlobalVar {
+    pub key: SfallGlobalKey,
+    pub value: i32,
+}
+
+/// A perk or trait added by a script with `set_fake_perk` or `set_fake_trait`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakePerk {
+    /// Current rank; for selectable perks, the rank the player would reach.
+    pub level: i32,
+    /// Skilldex artwork shown in the character screen.
+    pub image: i32,
+    pub name: String,
+    pub description: String,
+}
+
+/// The contents of `sfallgv.sav`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SfallGlobals {
+    pub vars: Vec<SfallGlobalVar>,
+    /// Next id sfall hands out to objects that scripts ask to track.
+    pub unique_id: u32,
+    /// Years added to the game date after the calendar wrapped.
+    pub added_years: u16,
+    pub fake_traits: Vec<FakePerk>,
+    pub fake_perks: Vec<FakePerk>,
+    /// Fake perks offered on the perk selection screen.
+    pub fake_selectable_perks: Vec<FakePerk>,
+    /// Anything sfall writes after the fake perks (drug and world-map
+    /// fixes, depending on the version), kept as-is.
+    pub trailing: Vec<u8>,
+}
+
+impl SfallGlobals {
+    pub fn parse(bytes: &[u8]) -> io::Result<Self> {
+        let mut r = Cursor::new(bytes);
+        let count = read_count(&mut r, GLOBAL_RECORD_LEN, "global variable")?;
+        let mut vars = Vec::with_capacity(count);
+        for _ in 0..count {
+            let key = SfallGlobalKey::from_raw(read_array(&mut r)?);
+            let value = read_i32(&mut r)?;
+            let _unused = read_u32(&mut r)?;
+            vars.push(SfallGlobalVar { key, value });
+        }
+        let unique_id = read_u32(&mut r)?;
+        let added_years = (read_u32(&mut r)? >> ADDED_YEARS_SHIFT) as u16;
+        let fake_traits = read_fake_perks(&mut r)?;
+        let fake_perks = read_fake_perks(&mut r)?;
+        let fake_selectable_perks = read_fake_perks(&mut r)?;
+        let mut trailing = Vec::new();
+        r.read_to_end(&mut trailing)?;
+
+        Ok(Self {
+            vars,
+            unique_id,
+            added_years,
+            fake_traits,
+            fake_perks,
+            fake_selectable_perks,
+            trailing,
+        })
+    }
+
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        write_count(&mut out, self.vars.len())?;
+        for var in &self.vars {
+            out.extend_from_slice(&var.key.to_raw());
+            out.extend_from_slice(&var.value.to_le_bytes());
+            out.extend_from_slice(&0u32.to_le_bytes());
+        }
+        out.extend_from_slice(&self.unique_id.to_le_bytes());
+        out.extend_from_slice(&(u32::from(self.added_years) << ADDED_YEARS_SHIFT).to_le_bytes());
+        for perks in [
+            &self.fake_traits,
+            &self.fake_perks,
+            &self.fake_selectable_perks,
+        ] {
+            write_fake_perks(&mut out, perks)?;
+        }
+        out.extend_from_slice(&self.trailing);
+        Ok(out)
+    }
+
+    pub fn get(&self, key: &SfallGlobalKey) -> Option<i32> {
+        self.vars
+            .iter()
+            .find(|var| &var.key == key)
+            .map(|var| var.value)
+    }
+
+    /// Set a global, adding it when the save does not have it yet.
+    pub fn set(&mut self, key: SfallGlobalKey, value: i32) {
+        match self.vars.iter_mut().find(|var| var.key == key) {
+            Some(var) => var.value = value,
+            None => self.vars.push(SfallGlobalVar { key, value }),
+        }
+    }
+
+    /// Remove a global. Returns whether it was present.
+    pub fn remove(&mut self, key: &SfallGlobalKey) -> bool {
+        let before = self.vars.len();
+        self.vars.retain(|var| &var.key != key);
+        self.vars.len() != before
+    }
+}
+
+/// One file in sfall's virtual file system.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SfallFile {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+/// The contents of `sfallfs.sav`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SfallFileSystem {
+    pub files: Vec<SfallFile>,
+}
+
+impl SfallFileSystem {
+    pub fn parse(bytes: &[u8]) -> io::Result<Self> {
+        let mut r = Cursor::new(bytes);
+        let count = read_count(&mut r, 8, "file")?;
+        let mut files = Vec::with_capacity(count);
+        for _ in 0..count {
+            let name_len = read_count(&mut r, 1, "file name byte")?;
+            let name = String::from_utf8_lossy(&read_bytes(&mut r, name_len)?).into_owned();
+            let data_len = read_count(&mut r, 1, "file data byte")?;
+            let data = read_bytes(&mut r, data_len)?;
+            files.push(SfallFile { name, data });
+        }
+        expect_end(&r, "sfall file system")?;
+        Ok(Self { files })
+    }
+
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        write_count(&mut out, self.files.len())?;
+        for file in &self.files {
+            write_count(&mut out, file.name.len())?;
+            out.extend_from_slice(file.name.as_bytes());
+            write_count(&mut out, file.data.len())?;
+            out.extend_from_slice(&file.data);
+        }
+        Ok(out)
+    }
+}
+
+/// A key or element of an sfall array.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SfallArrayValue {
+    None,
+    Int(i32),
+    Float(f32),
+    Str(String),
+}
+
+impl fmt::Display for SfallArrayValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => f.write_str("none"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::Str(value) => write!(f, "{value:?}"),
+        }
+    }
+}
+
+/// A saved array. Associative arrays interleave keys and values in `elements`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SfallArray {
+    /// The name given to `save_array`.
+    pub key: SfallArrayValue,
+    pub flags: u32,
+    pub elements: Vec<SfallArrayValue>,
+}
+
+/// The contents of `sfalldb.sav`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SfallArrays {
+    /// Arrays in the format older sfall versions wrote, kept as-is. Current
+    /// versions write none.
+    pub legacy: Vec<u8>,
+    pub arrays: Vec<SfallArray>,
+    /// Bytes after the last array, kept as-is.
+    pub trailing: Vec<u8>,
+}
+
+impl SfallArrays {
+    pub fn parse(bytes: &[u8]) -> io::Result<Self> {
+        let mut r = Cursor::new(bytes);
+        let legacy_count = read_u32(&mut r)?;
+        if legacy_count != 0 {
+            // Pre-3.4 saves; nothing follows that this parser understands.
+            return Ok(Self {
+                legacy: bytes.to_vec(),
+                arrays: Vec::new(),
+                trailing: Vec::new(),
+            });
+        }
+
+        let count = read_count(&mut r, 16, "array")?;
+        let mut arrays = Vec::with_capacity(count);
+        for _ in 0..count {
+            let key = read_array_value(&mut r)?;
+            let flags = read_u32(&mut r)?;
+            let len = read_count(&mut r, 8, "array element")?;
+            let elements = (0..len)
+                .map(|_| read_array_value(&mut r))
+                .collect::<io::Result<_>>()?;
+            arrays.push(SfallArray {
+                key,
+                flags,
+                elements,
+            });
+        }
+        let mut trailing = Vec::new();
+        r.read_to_end(&mut trailing)?;
+
+        Ok(Self {
+            legacy: Vec::new(),
+            arrays,
+            trailing,
+        })
+    }
+
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        if !self.legacy.is_empty() {
+            if !self.arrays.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "cannot add arrays to a legacy sfall array database",
+                ));
+            }
+            return Ok(self.legacy.clone());
+        }
+
+        let mut out = Vec::new();
+        out.extend_from_slice(&0u32.to_le_bytes());
+        write_count(&mut out, self.arrays.len())?;
+        for array in &self.arrays {
+            write_array_value(&mut out, &array.key)?;
+            out.extend_from_slice(&array.flags.to_le_bytes());
+            write_count(&mut out, array.elements.len())?;
+            for element in &array.elements {
+                write_array_value(&mut out, element)?;
+            }
+        }
+        out.extend_from_slice(&self.trailing);
+        Ok(out)
+    }
+
+    pub fn get(&self, key: &SfallArrayValue) -> Option<&SfallArray> {
+        self.arrays.iter().find(|array| &array.key == key)
+    }
+}
+
+fn is_name_byte(byte: u8) -> bool {
+    byte.is_ascii_graphic()
+}
+
+fn read_fake_perks(r: &mut Cursor<&[u8]>) -> io::Result<Vec<FakePerk>> {
+    let count = read_count(r, FAKE_PERK_RECORD_LEN, "fake perk")?;
+    (0..count)
+        .map(|_| {
+            Ok(FakePerk {
+                level: read_i32(r)?,
+                image: read_i32(r)?,
+                name: read_c_string(r, FAKE_PERK_NAME_LEN)?,
+                description: read_c_string(r, FAKE_PERK_DESCRIPTION_LEN)?,
+            })
+        })
+        .collect()
+}
+
+fn write_fake_perks(out: &mut Vec<u8>, perks: &[FakePerk]) -> io::Result<()> {
+    write_count(out, perks.len())?;
+    for perk in perks {
+        out.extend_from_slice(&perk.level.to_le_bytes());
+        out.extend_from_slice(&perk.image.to_le_bytes());
+        write_c_string(out, &perk.name, FAKE_PERK_NAME_LEN, "fake perk name")?;
+        write_c_string(
+            out,
+            &perk.description,
+            FAKE_PERK_DESCRIPTION_LEN,
+            "fake perk description",
+        )?;
+    }
+    Ok(())
+}
+
+fn read_array_value(r: &mut Cursor<&[u8]>) -> io::Result<SfallArrayValue> {
+    let kind = read_u32(r)?;
+    match kind {
+        ARRAY_VALUE_STR => {
+            let len = read_count(r, 1, "string byte")?;
+            let mut raw = read_bytes(r, len)?;
+            if raw.last() == Some(&0) {
+                raw.pop();
+            }
+            Ok(SfallArrayValue::Str(
+                String::from_utf8_lossy(&raw).into_owned(),
+            ))
+        }
+        _ => {
+            let raw = read_array::<4>(r)?;
+            match kind {
+                ARRAY_VALUE_NONE => Ok(SfallArrayValue::None),
+                ARRAY_VALUE_INT => Ok(SfallArrayValue::Int(i32::from_le_bytes(raw))),
+                ARRAY_VALUE_FLOAT => Ok(SfallArrayValue::Float(f32::from_le_bytes(raw))),
+                _ => Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unknown sfall array value type {kind}"),
+                )),
+            }
+        }
+    }
+}
+
+fn write_array_value(out: &mut Vec<u8>, value: &SfallArrayValue) -> io::Result<()> {
+    let (kind, raw) = match value {
+        SfallArrayValue::None => (ARRAY_VALUE_NONE, [0; 4]),
+        SfallArrayValue::Int(value) => (ARRAY_VALUE_INT, value.to_le_bytes()),
+        SfallArrayValue::Float(value) => (ARRAY_VALUE_FLOAT, value.to_le_bytes()),
+        SfallArrayValue::Str(value) => {
+            out.extend_from_slice(&ARRAY_VALUE_STR.to_le_bytes());
+            // The stored length counts the terminating NUL.
+            write_count(out, value.len() + 1)?;
+            out.extend_from_slice(value.as_bytes());
+            out.push(0);
+            return Ok(());
+        }
+    };
+    out.extend_from_slice(&kind.to_le_bytes());
+    out.extend_from_slice(&raw);
+    Ok(())
+}
+
+/// Read a count, rejecting values the rest of the file could not hold.
+fn read_count(r: &mut Cursor<&[u8]>, min_item_len: usize, what: &str) -> io::Result<usize> {
+    let count = read_u32(r)? as usize;
+    let remaining = r.get_ref().len() - r.position() as usize;
+    if count.saturating_mul(min_item_len) > remaining {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("sfall {what} count {count} exceeds the {remaining} bytes left"),
+        ));
+    }
+    Ok(count)
+}
+
+fn write_count(out: &mut Vec<u8>, count: usize) -> io::Result<()> {
+    let count = u32::try_from(count).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("count {count} does not fit sfall's 32-bit field"),
+        )
+    })?;
+    out.extend_from_slice(&count.to_le_bytes());
+    Ok(())
+}
+
+fn read_c_string(r: &mut Cursor<&[u8]>, len: usize) -> io::Result<String> {
+    let raw = read_bytes(r, len)?;
+    let end = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+    Ok(String::from_utf8_lossy(&raw[..end]).into_owned())
+}
+
+fn write_c_string(out: &mut Vec<u8>, value: &str, len: usize, what: &str) -> io::Result<()> {
+    if value.len() >= len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{what} is {} bytes; at most {} fit", value.len(), len - 1),
+        ));
+    }
+    let start = out.len();
+    out.extend_from_slice(value.as_bytes());
+    out.resize(start + len, 0);
+    Ok(())
+}
+
+fn expect_end(r: &Cursor<&[u8]>, what: &str) -> io::Result<()> {
+    let extra = r.get_ref().len() - r.position() as usize;
+    if extra != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{extra} unexpected bytes at the end of the {what}"),
+        ));
+    }
+    Ok(())
+}
+
+fn read_u32(r: &mut Cursor<&[u8]>) -> io::Result<u32> {
+    read_array(r).map(u32::from_le_bytes)
+}
+
+fn read_i32(r: &mut Cursor<&[u8]>) -> io::Result<i32> {
+    read_array(r).map(i32::from_le_bytes)
+}
+
+fn read_array<const N: usize>(r: &mut Cursor<&[u8]>) -> io::Result<[u8; N]> {
+    let mut buf = [0u8; N];
+    r.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_bytes(r: &mut Cursor<&[u8]>, n: usize) -> io::Result<Vec<u8>> {
+    let mut buf = vec![0u8; n];
+    r.read_exact(&mut buf)?;
+    Ok(buf)
+}
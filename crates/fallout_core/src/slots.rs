@@ -0,0 +1,300 @@
+//! Browse the save slots of a game's `SAVEGAME` directory.
+//!
+//! Each slot is a `SLOTnn` directory holding a `SAVE.DAT`; the load menu
+//! remembers its cursor in `slotdat.ini` next to them. Slot details come from
+//! the fixed-size `SAVE.DAT` header, so listing does not decode the rest of
+//! the save. The character's level sits far past the header and costs a full
+//! parse per slot, so [`list_slots_with_levels`] reads it only on request.
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, Cursor};
+use std::path::{Path, PathBuf};
+
+use crate::core_api::{DateParts, Game};
+use crate::reader::BigEndianReader;
+use crate::{fallout1, fallout2};
+
+pub const SLOT_DIR_PREFIX: &str = "SLOT";
+pub const SLOT_INDEX_FILE_NAME: &str = "slotdat.ini";
+const SAVE_DAT_FILE_NAME: &str = "SAVE.DAT";
+
+/// What the load menu shows for one slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotSummary {
+    /// The `nn` of `SLOTnn`; slot 1 is the top of the first page.
+    pub slot: u32,
+    pub path: PathBuf,
+    pub game: Game,
+    pub character_name: String,
+    pub description: String,
+    /// Real-world date the game was saved.
+    pub file_date: DateParts,
+    /// Real-world time of day the game was saved, as the game packs it.
+    pub file_time: i32,
+    pub game_date: DateParts,
+    pub game_time: u32,
+    pub map_id: i16,
+    pub map_filename: String,
+    pub elevation: i16,
+    /// The player's level, from [`list_slots_with_levels`]. `None` from
+    /// [`list_slots`], or when the header reads but the rest of the save does
+    /// not.
+    pub level: Option<i32>,
+}
+
+/// Where the load menu's cursor was left, from `slotdat.ini`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadMenuPosition {
+    /// Highlighted row on the current page.
+    pub list_num: i32,
+    pub page_offset: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotListing {
+    /// Slots with a readable `SAVE.DAT`, ordered by slot number.
+    pub slots: Vec<SlotSummary>,
+    /// Slots whose `SAVE.DAT` could not be read, with the reason.
+    pub unreadable: Vec<(PathBuf, String)>,
+    /// `None` when `slotdat.ini` is missing or unreadable; the game then
+    /// starts at the top of the first page.
+    pub position: Option<LoadMenuPosition>,
+}
+
+/// List the save slots under `save_root`, a game's `SAVEGAME` directory,
+/// from their headers alone. Slot directories without a `SAVE.DAT` are empty
+/// and skipped.
+pub fn list_slots<P: AsRef<Path>>(save_root: P) -> io::Result<SlotListing> {
+    list(save_root.as_ref(), false)
+}
+
+/// Like [`list_slots`], but also parses each save to fill in the level.
+pub fn list_slots_with_levels<P: AsRef<Path>>(save_root: P) -> io::Result<SlotListing> {
+    list(save_root.as_ref(), true)
+}
+
+fn list(save_root: &Path, read_levels: bool) -> io::Result<SlotListing> {
+    let mut slot_dirs = Vec::new();
+    for entry in fs::read_dir(save_root)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        if let Some(slot) = slot_number(&entry.file_name().to_string_lossy()) {
+            slot_dirs.push((slot, entry.path()));
+        }
+    }
+    slot_dirs.sort();
+
+    let mut slots = Vec::new();
+    let mut unreadable = Vec::new();
+    for (slot, dir) in slot_dirs {
+        let Some(save_path) = find_file(&dir, SAVE_DAT_FILE_NAME)? else {
+            continue;
+        };
+        match read_slot_summary(slot, &dir, &save_path, read_levels) {
+            Ok(summary) => slots.push(summary),
+            Err(e) => unreadable.push((dir, e.to_string())),
+        }
+    }
+
+    // A damaged index only loses the cursor, not the listing.
+    let position = find_file(save_root, SLOT_INDEX_FILE_NAME)?
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|ini| parse_load_menu_position(&ini).ok());
+
+    Ok(SlotListing {
+        slots,
+        unreadable,
+        position,
+    })
+}
+
+/// Parse the `[POSITION]` section of `slotdat.ini`.
+pub fn parse_load_menu_position(ini: &str) -> io::Result<LoadMenuPosition> {
+    let mut list_num = None;
+    let mut page_offset = None;
+    for line in ini.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().parse::<i32>().ok();
+        match key.trim() {
+            key if key.eq_ignore_ascii_case("ListNum") => list_num = value,
+            key if key.eq_ignore_ascii_case("PageOffset") => page_offset = value,
+            _ => {}
+        }
+    }
+
+    match (list_num, page_offset) {
+        (Some(list_num), Some(page_offset)) => Ok(LoadMenuPosition {
+            list_num,
+            page_offset,
+        }),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{SLOT_INDEX_FILE_NAME} is missing ListNum or PageOffset"),
+        )),
+    }
+}
+
+fn read_slot_summary(
+    slot: u32,
+    dir: &Path,
+    save_path: &Path,
+    read_levels: bool,
+) -> io::Result<SlotSummary> {
+    let mut r = BigEndianReader::new(BufReader::new(File::open(save_path)?));
+    // Both games share the header layout and differ only in the version.
+    let (game, header) = match fallout2::header::SaveHeader::parse(&mut r) {
+        Ok(header) => (Game::Fallout2, HeaderFields::from(header)),
+        Err(fallout2_err) => {
+            r.seek_to(0)?;
+            match fallout1::header::SaveHeader::parse(&mut r) {
+                Ok(header) => (Game::Fallout1, HeaderFields::from(header)),
+                Err(_) => return Err(fallout2_err),
+            }
+        }
+    };
+
+    Ok(SlotSummary {
+        slot,
+        path: dir.to_path_buf(),
+        game,
+        character_name: header.character_name,
+        description: header.description,
+        file_date: header.file_date,
+        file_time: header.file_time,
+        game_date: header.game_date,
+        game_time: header.game_time,
+        map_id: header.map_id,
+        map_filename: header.map_filename,
+        elevation: header.elevation,
+        level: read_levels.then(|| read_level(game, save_path)).flatten(),
+    })
+}
+
+/// The level sits in the PC stats section, after variable-length data, so
+/// finding it takes a parse of the whole save.
+fn read_level(game: Game, save_path: &Path) -> Option<i32> {
+    let bytes = fs::read(save_path).ok()?;
+    match game {
+        Game::Fallout1 => fallout1::SaveGame::parse(Cursor::new(bytes))
+            .ok()
+            .map(|save| save.pc_stats.level),
+        Game::Fallout2 => fallout2::SaveGame::parse(Cursor::new(bytes))
+            .ok()
+            .map(|save| save.pc_stats.level),
+    }
+}
+
+struct HeaderFields {
+    character_name: String,
+    description: String,
+    file_date: DateParts,
+    file_time: i32,
+    game_date: DateParts,
+    game_time: u32,
+    map_id: i16,
+    map_filename: String,
+    elevation: i16,
+}
+
+impl From<fallout1::header::SaveHeader> for HeaderFields {
+    fn from(header: fallout1::header::SaveHeader) -> Self {
+        Self {
+            character_name: header.character_name,
+            description: header.description,
+            file_date: DateParts {
+                day: header.file_day,
+                month: header.file_month,
+                year: header.file_year,
+            },
+            file_time: header.file_time,
+            game_date: DateParts {
+                day: header.game_day,
+                month: header.game_month,
+                year: header.game_year,
+            },
+            game_time: header.game_time,
+            map_id: header.map,
+            map_filename: header.map_filename,
+            elevation: header.elevation,
+        }
+    }
+}
+
+impl From<fallout2::header::SaveHeader> for HeaderFields {
+    fn from(header: fallout2::header::SaveHeader) -> Self {
+        Self {
+            character_name: header.character_name,
+            description: header.description,
+            file_date: DateParts {
+                day: header.file_day,
+                month: header.file_month,
+                year: header.file_year,
+            },
+            file_time: header.file_time,
+            game_date: DateParts {
+                day: header.game_day,
+                month: header.game_month,
+                year: header.game_year,
+            },
+            game_time: header.game_time,
+            map_id: header.map,
+            map_filename: header.map_filename,
+            elevation: header.elevation,
+        }
+    }
+}
+
+fn slot_number(dir_name: &str) -> Option<u32> {
+    let prefix = dir_name.get(..SLOT_DIR_PREFIX.len())?;
+    if !prefix.eq_ignore_ascii_case(SLOT_DIR_PREFIX) {
+        return None;
+    }
+    let digits = &dir_name[SLOT_DIR_PREFIX.len()..];
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Find `name` directly inside `dir`, ignoring ASCII case as the game does.
+fn find_file(dir: &Path, name: &str) -> io::Result<Option<PathBuf>> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() && entry.file_name().eq_ignore_ascii_case(name) {
+            return Ok(Some(entry.path()));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LoadMenuPosition, parse_load_menu_position, slot_number};
+
+    #[test]
+    fn slot_numbers_come_from_directory_names() {
+        assert_eq!(slot_number("SLOT01"), Some(1));
+        assert_eq!(slot_number("slot10"), Some(10));
+        assert_eq!(slot_number("SLOT"), None);
+        assert_eq!(slot_number("SLOT1a"), None);
+        assert_eq!(slot_number("MAPS"), None);
+    }
+
+    #[test]
+    fn load_menu_position_parses_ini() {
+        let position = parse_load_menu_position("[POSITION]\r\nListNum=3\r\nPageOffset=10\r\n")
+            .expect("valid ini");
+        assert_eq!(
+            position,
+            LoadMenuPosition {
+                list_num: 3,
+                page_offset: 10,
+            }
+        );
+        assert!(parse_load_menu_position("[POSITION]\n").is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+
+use fallout_core::automap::{
+    AUTOMAP_MAP_COUNT_FALLOUT1, AUTOMAP_MAP_COUNT_FALLOUT2, AUTOMAP_TILE_EMPTY, AUTOMAP_TILE_WALL,
+    Automap, AutomapEntry,
+};
+use fallout_core::map_save::MapSave;
+use fallout_core::object::{OBJ_TYPE_WALL, OBJECT_FLAG_SEEN, obj_type_from_fid};
+use flate2::read::GzDecoder;
+
+fn workspace_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+fn fallout1_slot(slot: u32) -> PathBuf {
+    workspace_root().join(format!("tests/fallout1_examples/SAVEGAME/SLOT{slot:02}"))
+}
+
+fn fallout2_slot(slot: u32) -> PathBuf {
+    workspace_root().join(format!("tests/fallout2_examples/SLOT{slot:02}"))
+}
+
+#[test]
+fn fallout1_automaps_round_trip_byte_for_byte() {
+    for slot in 1..=7 {
+        let path = fallout1_slot(slot).join("AUTOMAP.SAV");
+        let bytes = fs::read(&path).expect("failed to read automap");
+        let automap = Automap::parse(&bytes, AUTOMAP_MAP_COUNT_FALLOUT1)
+            .unwrap_or_else(|e| panic!("failed to parse {path:?}: {e}"));
+        assert!(!automap.compressed);
+        assert_eq!(automap.map_count(), AUTOMAP_MAP_COUNT_FALLOUT1);
+        assert!(
+            automap.to_bytes().expect("emit automap") == bytes,
+            "{path:?}"
+        );
+    }
+}
+
+#[test]
+fn fallout2_automaps_round_trip_through_gzip() {
+    for slot in 1..=2 {
+        let path = fallout2_slot(slot).join("AUTOMAP.SAV");
+        let bytes = fs::read(&path).expect("failed to read automap");
+        let mut payload = Vec::new();
+        GzDecoder::new(bytes.as_slice())
+            .read_to_end(&mut payload)
+            .expect("automap should be gzipped");
+
+        let automap = Automap::parse(&bytes, AUTOMAP_MAP_COUNT_FALLOUT2).expect("parse automap");
+        assert!(automap.compressed);
+        assert_eq!(automap.to_uncompressed_bytes(), payload);
+        let reparsed = Automap::parse(
+            &automap.to_bytes().expect("emit"),
+            AUTOMAP_MAP_COUNT_FALLOUT2,
+        )
+        .expect("reparse automap");
+        assert_eq!(reparsed.to_uncompressed_bytes(), payload);
+    }
+}
+
+#[test]
+fn automap_entries_record_seen_walls_and_scenery() {
+    let bytes = fs::read(fallout1_slot(1).join("AUTOMAP.SAV")).expect("read automap");
+    let automap = Automap::parse(&bytes, AUTOMAP_MAP_COUNT_FALLOUT1).expect("parse automap");
+    assert_eq!(automap.entry(0, 0), Some(&AutomapEntry::Disabled));
+    assert_eq!(automap.entry(3, 1), Some(&AutomapEntry::Empty));
+
+    // Map 35 is V13ENT; every seen wall there is on the automap, drawn as a
+    // wall unless scenery on the same hex was recorded after it.
+    let tiles = automap.tiles(35, 0).expect("V13ENT should be explored");
+    assert_eq!(tiles.explored_count(), 1030);
+    let map = MapSave::parse(&fs::read(fallout1_slot(1).join("V13ENT.SAV")).expect("read map"))
+        .expect("parse map");
+    let seen_walls: Vec<i32> = map.objects[0]
+        .iter()
+        .filter(|o| obj_type_from_fid(o.fid) == OBJ_TYPE_WALL && o.flags & OBJECT_FLAG_SEEN != 0)
+        .map(|o| o.tile)
+        .collect();
+    assert!(!seen_walls.is_empty());
+    assert!(
+        seen_walls
+            .iter()
+            .all(|&tile| tiles.tile(tile) != AUTOMAP_TILE_EMPTY)
+    );
+    let walls = seen_walls
+        .iter()
+        .filter(|&&tile| tiles.tile(tile) == AUTOMAP_TILE_WALL)
+        .count();
+    assert!(walls > seen_walls.len() * 9 / 10);
+}
+
+#[test]
+fn automap_reveal_all_and_clear_edit_one_map() {
+    let bytes = fs::read(fallout1_slot(1).join("AUTOMAP.SAV")).expect("read automap");
+    let mut automap = Automap::parse(&bytes, AUTOMAP_MAP_COUNT_FALLOUT1).expect("parse automap");
+    let map = MapSave::parse(&fs::read(fallout1_slot(1).join("V13ENT.SAV")).expect("read map"))
+        .expect("parse map");
+    let before = automap.tiles(35, 0).expect("explored").explored_count();
+
+    assert!(automap.reveal_all(36, &map).is_err());
+    automap.reveal_all(35, &map).expect("reveal V13ENT");
+    let revealed = automap.tiles(35, 0).expect("explored").explored_count();
+    assert!(revealed > before);
+
+    let reparsed = Automap::parse(
+        &automap.to_bytes().expect("emit"),
+        AUTOMAP_MAP_COUNT_FALLOUT1,
+    )
+    .expect("reparse revealed automap");
+    assert_eq!(reparsed.tiles(35, 0), automap.tiles(35, 0));
+    assert_eq!(reparsed.tiles(6, 2), automap.tiles(6, 2));
+
+    automap.clear(35).expect("clear V13ENT");
+    assert_eq!(automap.entry(35, 0), Some(&AutomapEntry::Empty));
+    let cleared = Automap::parse(
+        &automap.to_bytes().expect("emit"),
+        AUTOMAP_MAP_COUNT_FALLOUT1,
+    )
+    .expect("reparse cleared automap");
+    assert!(cleared.tiles(35, 0).is_none());
+    assert_eq!(cleared.tiles(6, 2), automap.tiles(6, 2));
+    assert!(cleared.to_uncompressed_bytes().len() < bytes.len());
+    assert!(automap.clear(AUTOMAP_MAP_COUNT_FALLOUT1).is_err());
+}
@@ -1,10 +1,20 @@
+use std::collections::BTreeSet;
 use std::fs::{self, File};
 use std::io::BufReader;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use fallout_core::core_api::{CharacterExport, CoreErrorCode, Engine, Game, TraitCatalog};
+use fallout_core::automap::automap_tile_for;
+use fallout_core::core_api::{
+    CharacterExport, CharacterRule, CoreErrorCode, Engine, EquipmentSlot, Game, GlobalVarCatalog,
+    ItemCatalog, MapInventoryHolderKind, Palette, PerkEntry, QueueEventData, THUMBNAIL_HEIGHT,
+    THUMBNAIL_WIDTH, TraitCatalog,
+};
 use fallout_core::gender::Gender;
+use fallout_core::layout::{FileLayout, SectionId};
+use fallout_core::object::{OBJECT_FLAG_SEEN, ObjectData};
+use fallout_core::perk::PerkKind;
+use fallout_core::sfall::SfallGlobalKey;
 use fallout_core::{fallout1, fallout2};
 
 const GAME_TIME_TICKS_PER_YEAR: u32 = 315_360_000;
@@ -326,7 +336,7 @@ fn session_query_methods_match_fallout2_save_data() {
         assert_eq!(skill.tag_bonus, save.skill_tag_bonus(skill.index));
         assert_eq!(skill.bonus, skill.total - skill.raw);
     }
-    assert_eq!(tagged_indices, vec![0, 4, 5]);
+    assert_eq!(tagged_indices, vec![4, 0, 5]);
 
     let perks = session.active_perks();
     let expected_perks = save.perks.iter().filter(|&&rank| rank > 0).count();
@@ -351,8 +361,8 @@ fn session_query_methods_match_fallout2_save_data() {
     let kills = session.nonzero_kill_counts();
     let expected_kills = save.kill_counts.iter().filter(|&&count| count > 0).count();
     assert_eq!(kills.len(), expected_kills);
-    assert!(kills.iter().any(|k| k.index == 7 && k.count == 2));
-    assert!(kills.iter().any(|k| k.index == 18 && k.count == 2));
+    assert!(kills.iter().any(|k| k.index == 6 && k.count == 2));
+    assert!(kills.iter().any(|k| k.index == 17 && k.count == 2));
 
     assert_eq!(session.map_files(), save.map_files);
 }
@@ -593,6 +603,53 @@ fn session_can_edit_name_and_description_fallout2() {
     assert_eq!(reparsed.snapshot().description, "Edited Save");
 }
 
+#[test]
+fn session_replaces_thumbnail_for_both_games() {
+    let engine = Engine::new();
+    for (game, path) in [
+        (Game::Fallout1, fallout1_save_path(1)),
+        (Game::Fallout2, fallout2_save_path(1)),
+    ] {
+        let bytes = fs::read(&path).expect("failed to read fixture");
+        let mut session = engine
+            .open_bytes(&bytes, Some(game))
+            .expect("failed to open save");
+
+        // 2x2 image: left column mid grey, right column light grey, against
+        // a grey-ramp palette.
+        let palette = Palette::builtin();
+        let rgba = [[100, 100, 100, 255], [240, 240, 240, 255]]
+            .repeat(2)
+            .concat();
+        session
+            .set_thumbnail(&rgba, 2, 2, &palette)
+            .expect("failed to set thumbnail");
+        let indices = session.thumbnail_indices();
+        assert_eq!(indices.len(), THUMBNAIL_WIDTH * THUMBNAIL_HEIGHT);
+        assert_eq!(indices[0], 100);
+        // 240 falls in the colour-cycling range, so the nearest still grey wins.
+        assert_eq!(indices[THUMBNAIL_WIDTH - 1], 228);
+
+        let modified = session
+            .to_bytes_modified()
+            .expect("failed to emit modified bytes");
+        assert_eq!(modified.len(), bytes.len());
+        let reparsed = engine
+            .open_bytes(&modified, Some(game))
+            .expect("failed to parse modified bytes");
+        assert_eq!(reparsed.thumbnail_indices(), session.thumbnail_indices());
+        assert_eq!(
+            reparsed.snapshot().character_name,
+            session.snapshot().character_name
+        );
+
+        let err = session
+            .set_thumbnail(&rgba, 3, 2, &palette)
+            .expect_err("mismatched dimensions should be rejected");
+        assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+    }
+}
+
 #[test]
 fn session_can_edit_skill_raw_values_fallout1() {
     let engine = Engine::new();
@@ -820,6 +877,1917 @@ fn session_can_edit_traits_perks_and_inventory_fallout2() {
     );
 }
 
+fn write_item_proto(dir: &std::path::Path, file_name: &str, fields: &[(usize, i32)], len: usize) {
+    let mut bytes = vec![0u8; len];
+    for &(offset, value) in fields {
+        bytes[offset..offset + 4].copy_from_slice(&value.to_be_bytes());
+    }
+    fs::write(dir.join(file_name), bytes).expect("failed to write item proto");
+}
+
+#[test]
+fn session_can_create_new_inventory_items_from_prototypes_fallout2() {
+    const WEAPON_PID: i32 = 0x0000_0009;
+    const AMMO_PID: i32 = 0x0000_001D;
+
+    let install_dir = temp_test_dir("item_prototypes");
+    let items_dir = install_dir.join("data").join("proto").join("items");
+    let text_dir = install_dir
+        .join("data")
+        .join("text")
+        .join("english")
+        .join("game");
+    fs::create_dir_all(&items_dir).expect("failed to create proto dir");
+    fs::create_dir_all(&text_dir).expect("failed to create text dir");
+    fs::write(items_dir.join("items.lst"), "00000009.pro\n0000001d.pro\n")
+        .expect("failed to write items.lst");
+    fs::write(
+        text_dir.join("pro_item.msg"),
+        "{900}{}{10mm SMG}\n{2900}{}{10mm JHP}\n",
+    )
+    .expect("failed to write pro_item.msg");
+    // pid, message id, fid, flags, type, weight, then weapon ammo pid/capacity.
+    write_item_proto(
+        &items_dir,
+        "00000009.pro",
+        &[
+            (0x00, WEAPON_PID),
+            (0x04, 900),
+            (0x08, 0x0000_0017),
+            (0x14, 0x2000_0008),
+            (0x20, 3),
+            (0x2C, 5),
+            (0x71, AMMO_PID),
+            (0x75, 30),
+        ],
+        0x7A,
+    );
+    // Ammo quantity per box lives at 0x3D.
+    write_item_proto(
+        &items_dir,
+        "0000001d.pro",
+        &[
+            (0x00, AMMO_PID),
+            (0x04, 2900),
+            (0x08, 0x0000_0004),
+            (0x14, 0x0000_0008),
+            (0x20, 4),
+            (0x2C, 1),
+            (0x3D, 24),
+        ],
+        0x51,
+    );
+    let catalog =
+        ItemCatalog::load_from_install_dir(&install_dir).expect("failed to load item catalog");
+    let weapon_proto = catalog
+        .prototype(WEAPON_PID)
+        .expect("weapon prototype should load");
+    assert_eq!(weapon_proto.fid, 0x17);
+    assert_eq!(weapon_proto.extra_data, [0, 0, 0, 30, 0, 0, 0, 0x1D]);
+
+    let engine = Engine::new();
+    let bytes = fs::read(fallout2_save_path(1)).expect("failed to read Fallout 2 fixture");
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout2))
+        .expect("failed to open Fallout 2 save");
+    assert!(
+        session
+            .inventory()
+            .iter()
+            .all(|item| item.pid != WEAPON_PID)
+    );
+
+    let err = session
+        .add_inventory_item(WEAPON_PID, 1)
+        .expect_err("adding a new pid without prototype data should fail");
+    assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+
+    session
+        .add_inventory_item_with_catalog(WEAPON_PID, 1, &catalog, 0)
+        .expect("failed to create weapon from prototype");
+    session
+        .add_inventory_item_with_catalog(AMMO_PID, 2, &catalog, 0)
+        .expect("failed to create ammo from prototype");
+    session
+        .add_inventory_item_with_catalog(AMMO_PID, 3, &catalog, 0)
+        .expect("failed to stack created ammo");
+
+    assert_eq!(catalog.weapon_ammo_capacity(WEAPON_PID), Some(30));
+    assert_eq!(catalog.weapon_ammo_capacity(AMMO_PID), None);
+    let err = session
+        .set_weapon_ammo_with_catalog(WEAPON_PID, AMMO_PID, 31, &catalog)
+        .expect_err("overfilling the magazine should fail");
+    assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+    session
+        .set_weapon_ammo_with_catalog(WEAPON_PID, AMMO_PID, 30, &catalog)
+        .expect("a full magazine should load");
+
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified Fallout 2 bytes");
+    let reparsed = engine
+        .open_bytes(&modified, Some(Game::Fallout2))
+        .expect("failed to parse modified Fallout 2 bytes");
+    let inventory = reparsed.inventory();
+    assert_eq!(inventory.len(), session.inventory().len());
+    assert!(
+        inventory
+            .iter()
+            .any(|item| item.pid == WEAPON_PID && item.quantity == 1)
+    );
+    assert!(
+        inventory
+            .iter()
+            .any(|item| item.pid == AMMO_PID && item.quantity == 5)
+    );
+
+    let doc = fallout2::Document::parse_with_layout(std::io::Cursor::new(&modified))
+        .expect("modified bytes should parse as a document");
+    let ids: BTreeSet<i32> = doc
+        .save
+        .player_object
+        .inventory
+        .iter()
+        .map(|item| item.object.id)
+        .collect();
+    assert_eq!(ids.len(), doc.save.player_object.inventory.len());
+    let weapon = doc
+        .save
+        .player_object
+        .inventory
+        .iter()
+        .find(|item| item.object.pid == WEAPON_PID)
+        .expect("weapon should be in inventory");
+    assert_eq!(weapon.object.fid, 0x17);
+    assert_eq!(weapon.object.flags, 0x2000_0008);
+    match &weapon.object.object_data {
+        ObjectData::Item(data) => {
+            assert_eq!(data.extra_bytes, 8);
+            assert_eq!(data.extra_data, [0, 0, 0, 30, 0, 0, 0, 0x1D]);
+        }
+        other => panic!("expected item data, got {other:?}"),
+    }
+
+    // Within a slot, new ids also clear every object on the map saves.
+    let slot_dir = fallout2_save_path(1)
+        .parent()
+        .expect("fixture should sit in a slot directory")
+        .to_path_buf();
+    let mut slot = engine
+        .open_slot(&slot_dir)
+        .expect("failed to open Fallout 2 slot");
+    let map_max = slot
+        .max_map_object_id()
+        .expect("failed to read map object ids");
+    slot.add_inventory_item_with_catalog(WEAPON_PID, 1, &catalog)
+        .expect("failed to create weapon in slot");
+    let modified = slot
+        .session()
+        .to_bytes_modified()
+        .expect("failed to emit modified Fallout 2 bytes");
+    let doc = fallout2::Document::parse_with_layout(std::io::Cursor::new(&modified))
+        .expect("modified bytes should parse as a document");
+    let weapon = doc
+        .save
+        .player_object
+        .inventory
+        .iter()
+        .find(|item| item.object.pid == WEAPON_PID)
+        .expect("weapon should be in inventory");
+    assert!(weapon.object.id > map_max);
+
+    // An id floor above the player's ids decides the new id.
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout2))
+        .expect("failed to open Fallout 2 save");
+    session
+        .add_inventory_item_with_catalog(WEAPON_PID, 1, &catalog, 50_000)
+        .expect("failed to create weapon above the id floor");
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified Fallout 2 bytes");
+    let doc = fallout2::Document::parse_with_layout(std::io::Cursor::new(&modified))
+        .expect("modified bytes should parse as a document");
+    assert!(
+        doc.save
+            .player_object
+            .inventory
+            .iter()
+            .any(|item| item.object.pid == WEAPON_PID && item.object.id == 50_001)
+    );
+
+    let _ = fs::remove_dir_all(&install_dir);
+}
+
+fn section_bytes(bytes: &[u8], layout: &FileLayout, id: SectionId) -> Vec<u8> {
+    let section = layout
+        .sections
+        .iter()
+        .find(|section| section.id == id)
+        .unwrap_or_else(|| panic!("missing section {id:?}"));
+    bytes[section.range.start..section.range.end].to_vec()
+}
+
+#[test]
+fn session_can_edit_global_vars_and_keeps_duplicate_in_sync() {
+    let engine = Engine::new();
+    for (game, path) in [
+        (Game::Fallout1, fallout1_save_path(1)),
+        (Game::Fallout2, fallout2_save_path(1)),
+    ] {
+        let bytes = fs::read(&path).expect("failed to read fixture");
+        let mut session = engine
+            .open_bytes(&bytes, Some(game))
+            .expect("failed to open save");
+
+        let count = session.global_vars().len();
+        assert_eq!(count, session.snapshot().global_var_count);
+        let last = count - 1;
+        let original_last = session.global_var(last).expect("last global should exist");
+        assert_eq!(session.global_var(count), None);
+
+        session
+            .set_global_var(0, 1234)
+            .expect("failed to set first global");
+        session
+            .set_global_var(last, original_last.wrapping_add(7))
+            .expect("failed to set last global");
+        let err = session
+            .set_global_var(count, 1)
+            .expect_err("out of range global should fail");
+        assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+        assert_eq!(session.global_var(0), Some(1234));
+
+        let modified = session
+            .to_bytes_modified()
+            .expect("failed to emit modified bytes");
+        assert_eq!(modified.len(), bytes.len());
+        let reparsed = engine
+            .open_bytes(&modified, Some(game))
+            .expect("failed to parse modified bytes");
+        assert_eq!(reparsed.global_var(0), Some(1234));
+        assert_eq!(
+            reparsed.global_var(last),
+            Some(original_last.wrapping_add(7))
+        );
+
+        let layout = match game {
+            Game::Fallout1 => {
+                fallout1::Document::parse_with_layout(std::io::Cursor::new(&modified))
+                    .expect("modified Fallout 1 bytes should parse")
+                    .layout()
+                    .clone()
+            }
+            Game::Fallout2 => {
+                fallout2::Document::parse_with_layout(std::io::Cursor::new(&modified))
+                    .expect("modified Fallout 2 bytes should parse")
+                    .layout()
+                    .clone()
+            }
+        };
+        assert_eq!(
+            section_bytes(&modified, &layout, SectionId::Handler(2)),
+            section_bytes(&modified, &layout, SectionId::Handler(4))
+        );
+    }
+}
+
+#[test]
+fn session_can_remove_and_retime_fallout1_queue_events() {
+    let engine = Engine::new();
+    let bytes = fs::read(fallout1_save_path(3)).expect("failed to read SLOT03");
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout1))
+        .expect("failed to open SLOT03");
+
+    let events = session
+        .queue_events()
+        .expect("SLOT03 queue should decode")
+        .to_vec();
+    assert_eq!(events.len(), 5);
+    assert!(events.windows(2).all(|pair| pair[0].time <= pair[1].time));
+    let script_index = events
+        .iter()
+        .position(|event| {
+            event.data
+                == QueueEventData::Script {
+                    sid: 67108869,
+                    fixed_param: 1,
+                }
+        })
+        .expect("SLOT03 should have a pending script timer");
+    assert_eq!(events[script_index].time, 373775606);
+    assert_eq!(events[script_index].object_id, 1088);
+    assert_eq!(
+        session.export_character().queue_events.as_ref(),
+        Some(&events)
+    );
+
+    let mut export = session.export_character();
+    export.queue_events.as_mut().expect("decoded queue")[script_index].time += 1;
+    let err = session
+        .apply_character(&export)
+        .expect_err("queue event edits via export should fail");
+    assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+
+    let removed = session
+        .remove_queue_event(script_index)
+        .expect("failed to remove script event");
+    assert_eq!(removed, events[script_index]);
+    let err = session
+        .remove_queue_event(4)
+        .expect_err("out of range queue event should fail");
+    assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+
+    let first = session.queue_events().expect("decoded queue")[0].clone();
+    session
+        .set_queue_event_time(0, u32::MAX)
+        .expect("failed to retime first event");
+    let retimed = &session.queue_events().expect("decoded queue")[3];
+    assert_eq!(retimed.time, u32::MAX);
+    assert_eq!(retimed.data, first.data);
+
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified bytes");
+    // Script events carry a 12-byte header plus sid and fixed_param.
+    assert_eq!(modified.len(), bytes.len() - 20);
+    let reparsed = engine
+        .open_bytes(&modified, Some(Game::Fallout1))
+        .expect("failed to parse modified bytes");
+    assert_eq!(reparsed.queue_events(), session.queue_events());
+    assert_eq!(
+        reparsed.snapshot().selected_traits,
+        session.snapshot().selected_traits
+    );
+}
+
+#[test]
+fn fallout1_undecoded_queue_is_reported_instead_of_empty() {
+    const TICKS_PER_DAY: u32 = 864_000;
+
+    let bytes = fs::read(fallout1_save_path(3)).expect("failed to read SLOT03");
+    let doc = fallout1::Document::parse_with_layout(std::io::Cursor::new(&bytes))
+        .expect("fixture should parse");
+    let queue = doc
+        .layout()
+        .sections
+        .iter()
+        .find(|section| section.id == SectionId::Handler(15))
+        .expect("missing handler 15")
+        .range;
+    let mut corrupt = bytes.clone();
+    corrupt[queue.start..queue.start + 4].copy_from_slice(&(-1i32).to_be_bytes());
+
+    let engine = Engine::new();
+    let mut session = engine
+        .open_bytes(&corrupt, Some(Game::Fallout1))
+        .expect("a bad queue should not stop the save from opening");
+    assert!(session.queue_events().is_err());
+    assert_eq!(session.export_character().queue_events, None);
+    assert!(
+        session
+            .addictions()
+            .iter()
+            .all(|addiction| addiction.withdrawal_events.is_none())
+    );
+    assert!(session.remove_queue_event(0).is_err());
+    assert!(session.set_queue_event_time(0, 0).is_err());
+    let game_time = session.snapshot().game_time;
+    assert!(
+        session.advance_game_time(TICKS_PER_DAY).is_err(),
+        "the clock should not move without the queue"
+    );
+    assert_eq!(session.snapshot().game_time, game_time);
+    assert_eq!(
+        session
+            .to_bytes_modified()
+            .expect("failed to emit unmodified bytes"),
+        corrupt
+    );
+}
+
+#[test]
+fn fallout2_queue_events_decode_and_edit() {
+    let bytes = fs::read(fallout2_save_path(2)).expect("failed to read SLOT02");
+    let engine = Engine::new();
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout2))
+        .expect("failed to open SLOT02");
+
+    let events = session
+        .queue_events()
+        .expect("SLOT02 queue should decode")
+        .to_vec();
+    assert_eq!(events.len(), 5);
+    assert!(events.windows(2).all(|pair| pair[0].time <= pair[1].time));
+    let sound_index = events
+        .iter()
+        .position(|event| event.data == QueueEventData::SoundEffect)
+        .expect("SLOT02 should have a pending sound effect");
+    assert!(
+        events
+            .iter()
+            .any(|event| event.data == QueueEventData::MapUpdate && event.object_id == -2)
+    );
+    assert_eq!(
+        session
+            .to_bytes_modified()
+            .expect("failed to emit unmodified bytes"),
+        bytes
+    );
+
+    let removed = session
+        .remove_queue_event(sound_index)
+        .expect("failed to remove sound effect event");
+    assert_eq!(removed, events[sound_index]);
+    let err = session
+        .remove_queue_event(4)
+        .expect_err("out of range queue event should fail");
+    assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+    session
+        .set_queue_event_time(0, u32::MAX)
+        .expect("failed to retime first event");
+    assert_eq!(
+        session.queue_events().expect("decoded queue")[3].time,
+        u32::MAX
+    );
+
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified bytes");
+    // Sound effect events are only the 12-byte header.
+    assert_eq!(modified.len(), bytes.len() - 12);
+    let reparsed = engine
+        .open_bytes(&modified, Some(Game::Fallout2))
+        .expect("failed to parse modified bytes");
+    assert_eq!(reparsed.queue_events(), session.queue_events());
+    assert_eq!(reparsed.snapshot(), session.snapshot());
+}
+
+#[test]
+fn session_equips_items_and_moves_armor_bonuses() {
+    const HARDENED_POWER_ARMOR_PID: i32 = 232;
+    const LEATHER_JACKET_PID: i32 = 74;
+    const STIMPAK_PID: i32 = 40;
+    const STAT_AC: usize = 9;
+    const STAT_DT_NORMAL: usize = 17;
+    const STAT_DR_NORMAL: usize = 24;
+    const STAT_STRENGTH: usize = 0;
+    const STAT_RADIATION_RESISTANCE: usize = 31;
+    const PERK_POWERED_ARMOR: i32 = 62;
+
+    let install_dir = temp_test_dir("armor_prototypes");
+    let items_dir = install_dir.join("proto").join("items");
+    let text_dir = install_dir.join("text").join("english").join("game");
+    fs::create_dir_all(&items_dir).expect("failed to create proto dir");
+    fs::create_dir_all(&text_dir).expect("failed to create text dir");
+    let mut items_lst = vec!["dummy.pro"; HARDENED_POWER_ARMOR_PID as usize];
+    items_lst[(LEATHER_JACKET_PID - 1) as usize] = "0000004a.pro";
+    items_lst[(HARDENED_POWER_ARMOR_PID - 1) as usize] = "000000e8.pro";
+    fs::write(items_dir.join("items.lst"), items_lst.join("\n"))
+        .expect("failed to write items.lst");
+    fs::write(
+        text_dir.join("pro_item.msg"),
+        "{7400}{}{Leather Jacket}\n{23200}{}{Hardened Power Armor}\n",
+    )
+    .expect("failed to write pro_item.msg");
+    // pid, message id, type (armor), weight, then AC, DR[0], DT[0] and perk.
+    for (file_name, pid, ac, dr, dt, perk) in [
+        ("0000004a.pro", LEATHER_JACKET_PID, 8, 20, 0, -1),
+        (
+            "000000e8.pro",
+            HARDENED_POWER_ARMOR_PID,
+            25,
+            50,
+            15,
+            PERK_POWERED_ARMOR,
+        ),
+    ] {
+        write_item_proto(
+            &items_dir,
+            file_name,
+            &[
+                (0x00, pid),
+                (0x04, pid * 100),
+                (0x20, 0),
+                (0x2C, 5),
+                (0x39, ac),
+                (0x3D, dr),
+                (0x59, dt),
+                (0x75, perk),
+            ],
+            0x81,
+        );
+    }
+    let catalog =
+        ItemCatalog::load_from_install_dir(&install_dir).expect("failed to load item catalog");
+    assert_eq!(
+        catalog
+            .armor(HARDENED_POWER_ARMOR_PID)
+            .map(|armor| armor.damage_threshold[0]),
+        Some(15)
+    );
+
+    let engine = Engine::new();
+    let bytes = fs::read(fallout1_save_path(1)).expect("failed to read Fallout 1 fixture");
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout1))
+        .expect("failed to open Fallout 1 save");
+    let equipped = session.equipped();
+    assert_eq!(equipped.armor, Some(HARDENED_POWER_ARMOR_PID));
+    assert_eq!(equipped.left_hand, Some(233));
+    assert_eq!(equipped.right_hand, Some(75));
+    let bonus = |session: &fallout_core::core_api::Session, index: usize| session.stat(index).bonus;
+    let (ac, dt, dr) = (
+        bonus(&session, STAT_AC),
+        bonus(&session, STAT_DT_NORMAL),
+        bonus(&session, STAT_DR_NORMAL),
+    );
+    let (strength, rad_resistance) = (
+        bonus(&session, STAT_STRENGTH),
+        bonus(&session, STAT_RADIATION_RESISTANCE),
+    );
+
+    let err = session
+        .equip_item(EquipmentSlot::Armor, LEATHER_JACKET_PID)
+        .expect_err("armor changes without prototypes should fail");
+    assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+
+    session
+        .add_inventory_item_with_catalog(LEATHER_JACKET_PID, 1, &catalog, 0)
+        .expect("failed to add leather jacket");
+    session
+        .equip_item_with_catalog(EquipmentSlot::Armor, LEATHER_JACKET_PID, &catalog)
+        .expect("failed to wear leather jacket");
+
+    let stimpaks_before: i32 = session
+        .inventory()
+        .iter()
+        .filter(|item| item.pid == STIMPAK_PID)
+        .map(|item| item.quantity)
+        .sum();
+    session
+        .equip_item(EquipmentSlot::RightHand, STIMPAK_PID)
+        .expect("failed to hold a stimpak");
+
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified Fallout 1 bytes");
+    let mut reparsed = engine
+        .open_bytes(&modified, Some(Game::Fallout1))
+        .expect("failed to parse modified Fallout 1 bytes");
+    let equipped = reparsed.equipped();
+    assert_eq!(equipped.armor, Some(LEATHER_JACKET_PID));
+    assert_eq!(equipped.left_hand, Some(233));
+    assert_eq!(equipped.right_hand, Some(STIMPAK_PID));
+    assert_eq!(bonus(&reparsed, STAT_AC), ac - 25 + 8);
+    assert_eq!(bonus(&reparsed, STAT_DT_NORMAL), dt - 15);
+    assert_eq!(bonus(&reparsed, STAT_DR_NORMAL), dr - 50 + 20);
+    // Taking off power armor also drops its perk's strength and rad resistance.
+    assert_eq!(bonus(&reparsed, STAT_STRENGTH), strength - 3);
+    assert_eq!(
+        bonus(&reparsed, STAT_RADIATION_RESISTANCE),
+        rad_resistance - 30
+    );
+    let stimpaks_after: i32 = reparsed
+        .inventory()
+        .iter()
+        .filter(|item| item.pid == STIMPAK_PID)
+        .map(|item| item.quantity)
+        .sum();
+    assert_eq!(stimpaks_after, stimpaks_before);
+
+    assert_eq!(
+        reparsed
+            .unequip_with_catalog(EquipmentSlot::Armor, &catalog)
+            .expect("failed to take off armor"),
+        Some(LEATHER_JACKET_PID)
+    );
+    assert_eq!(bonus(&reparsed, STAT_AC), ac - 25);
+    assert_eq!(
+        reparsed
+            .unequip_with_catalog(EquipmentSlot::Armor, &catalog)
+            .expect("unequip"),
+        None
+    );
+    assert!(reparsed.unequip(EquipmentSlot::Armor).is_err());
+    assert_eq!(
+        reparsed.unequip(EquipmentSlot::RightHand).expect("unequip"),
+        Some(STIMPAK_PID)
+    );
+    assert_eq!(reparsed.equipped().right_hand, None);
+
+    let err = reparsed
+        .equip_item(EquipmentSlot::LeftHand, 0x0000_7FFF)
+        .expect_err("equipping an item the player lacks should fail");
+    assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+
+    let _ = fs::remove_dir_all(&install_dir);
+}
+
+#[test]
+fn session_decodes_and_edits_weapon_ammo_fallout1() {
+    const WEAPON_PID: i32 = 233;
+    const AMMO_PID: i32 = 0x0000_001D;
+    const STIMPAK_PID: i32 = 40;
+    const FLARE_PID: i32 = 75;
+
+    let engine = Engine::new();
+    let bytes = fs::read(fallout1_save_path(1)).expect("failed to read Fallout 1 fixture");
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout1))
+        .expect("failed to open Fallout 1 save");
+
+    let resolved = session.inventory_resolved_builtin();
+    let weapon = resolved
+        .iter()
+        .find(|item| item.pid == WEAPON_PID)
+        .expect("fixture should carry the wielded weapon");
+    assert_eq!(weapon.ammo_pid, Some(39));
+    assert_eq!(weapon.ammo_loaded, Some(10));
+    let stimpak = resolved
+        .iter()
+        .find(|item| item.pid == STIMPAK_PID)
+        .expect("fixture should carry stimpaks");
+    assert_eq!(stimpak.ammo_pid, None);
+    assert_eq!(stimpak.charges, None);
+
+    session
+        .set_weapon_ammo(WEAPON_PID, AMMO_PID, 12)
+        .expect("failed to load weapon");
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified Fallout 1 bytes");
+    let mut reparsed = engine
+        .open_bytes(&modified, Some(Game::Fallout1))
+        .expect("failed to parse modified Fallout 1 bytes");
+    let weapon = reparsed
+        .inventory_resolved_builtin()
+        .into_iter()
+        .find(|item| item.pid == WEAPON_PID)
+        .expect("weapon should still be carried");
+    assert_eq!(weapon.ammo_pid, Some(AMMO_PID));
+    assert_eq!(weapon.ammo_loaded, Some(12));
+    assert_eq!(reparsed.equipped().left_hand, Some(WEAPON_PID));
+
+    reparsed
+        .set_weapon_ammo(WEAPON_PID, -1, 0)
+        .expect("failed to empty weapon");
+    for (pid, ammo_pid, rounds) in [
+        (WEAPON_PID, AMMO_PID, -1),
+        (WEAPON_PID, -1, 5),
+        (STIMPAK_PID, AMMO_PID, 1),
+        (FLARE_PID, AMMO_PID, 1),
+        (0x0000_7FFF, AMMO_PID, 1),
+    ] {
+        let err = reparsed
+            .set_weapon_ammo(pid, ammo_pid, rounds)
+            .expect_err("invalid ammo edit should fail");
+        assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+    }
+}
+
+#[test]
+fn session_can_edit_radiation_and_poison_fallout1() {
+    let engine = Engine::new();
+    let bytes = fs::read(fallout1_save_path(1)).expect("failed to read Fallout 1 fixture");
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout1))
+        .expect("failed to open Fallout 1 save");
+    let hp = session.current_hp();
+    assert!(session.radiation().is_some());
+    assert!(session.poison().is_some());
+
+    session.set_radiation(450).expect("failed to set radiation");
+    session.set_poison(0).expect("failed to set poison");
+    let err = session
+        .set_radiation(-1)
+        .expect_err("negative radiation should fail");
+    assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+    assert!(session.set_poison(-5).is_err());
+
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified Fallout 1 bytes");
+    let reparsed = engine
+        .open_bytes(&modified, Some(Game::Fallout1))
+        .expect("failed to parse modified Fallout 1 bytes");
+    assert_eq!(reparsed.radiation(), Some(450));
+    assert_eq!(reparsed.poison(), Some(0));
+    assert_eq!(reparsed.current_hp(), hp);
+    assert_eq!(reparsed.export_character().radiation, Some(450));
+}
+
+#[test]
+fn session_preferences_drive_skill_difficulty_fallout2() {
+    const SKILL_SMALL_GUNS: usize = 0;
+    const SKILL_FIRST_AID: usize = 6;
+
+    let engine = Engine::new();
+    let bytes = fs::read(fallout2_save_path(1)).expect("failed to read Fallout 2 fixture");
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout2))
+        .expect("failed to open Fallout 2 save");
+    let total =
+        |session: &fallout_core::core_api::Session, index: usize| session.skills()[index].total;
+    let preferences = session.preferences().cloned().expect("preferences");
+    assert_eq!(preferences.game_difficulty, 1);
+    let first_aid = total(&session, SKILL_FIRST_AID);
+    let small_guns = total(&session, SKILL_SMALL_GUNS);
+
+    session.set_game_difficulty(0).expect("failed to set easy");
+    assert_eq!(total(&session, SKILL_FIRST_AID), first_aid + 20);
+    session.set_game_difficulty(2).expect("failed to set hard");
+    assert_eq!(total(&session, SKILL_FIRST_AID), first_aid - 10);
+    assert_eq!(total(&session, SKILL_SMALL_GUNS), small_guns);
+    assert!(session.set_game_difficulty(3).is_err());
+
+    let mut edited = session.preferences().cloned().expect("preferences");
+    edited.violence_level = 0;
+    edited.combat_speed = 100;
+    edited.brightness = 1.5;
+    session
+        .set_preferences(&edited)
+        .expect("failed to set preferences");
+    edited.master_volume = -1;
+    let err = session
+        .set_preferences(&edited)
+        .expect_err("negative volume should fail");
+    assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified Fallout 2 bytes");
+    assert_eq!(modified.len(), bytes.len());
+    let reparsed = engine
+        .open_bytes(&modified, Some(Game::Fallout2))
+        .expect("failed to parse modified Fallout 2 bytes");
+    let reparsed_preferences = reparsed.preferences().expect("preferences");
+    assert_eq!(reparsed_preferences.game_difficulty, 2);
+    assert_eq!(reparsed_preferences.violence_level, 0);
+    assert_eq!(reparsed_preferences.combat_speed, 100);
+    assert_eq!(reparsed_preferences.brightness, 1.5);
+    assert_eq!(
+        reparsed_preferences.text_base_delay,
+        preferences.text_base_delay
+    );
+    assert_eq!(total(&reparsed, SKILL_FIRST_AID), first_aid - 10);
+
+    let f1_bytes = fs::read(fallout1_save_path(1)).expect("failed to read Fallout 1 fixture");
+    let mut f1_session = engine
+        .open_bytes(&f1_bytes, Some(Game::Fallout1))
+        .expect("failed to open Fallout 1 save");
+    assert!(f1_session.preferences().is_none());
+    assert!(f1_session.set_game_difficulty(0).is_err());
+}
+
+#[test]
+fn session_level_up_grants_skill_points_hp_and_perks() {
+    const PERK_EDUCATED: usize = 18;
+    const PERK_LIFEGIVER: usize = 28;
+
+    let engine = Engine::new();
+    let bytes = fs::read(fallout1_save_path(1)).expect("failed to read Fallout 1 fixture");
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout1))
+        .expect("failed to open Fallout 1 save");
+    // Level 13, INT 8, END 3, Gifted.
+    let max_hp = session.max_hp();
+    let hp = session.current_hp().expect("player hp");
+    assert!(session.level_up_to(13).is_err());
+    assert!(session.level_up_to(22).is_err());
+
+    let report = session.level_up_to(15).expect("failed to level up");
+    assert_eq!(report.from_level, 13);
+    assert_eq!(report.to_level, 15);
+    assert_eq!(report.experience, 105_000);
+    assert_eq!(report.skill_points, 2 * (5 + 2 * 8 - 5));
+    assert_eq!(report.hit_points, 2 * (2 + 3 / 2));
+    assert_eq!(report.perk_levels, vec![15]);
+    assert!(!report.free_perk);
+
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified Fallout 1 bytes");
+    let reparsed = engine
+        .open_bytes(&modified, Some(Game::Fallout1))
+        .expect("failed to parse modified Fallout 1 bytes");
+    let snapshot = reparsed.snapshot();
+    assert_eq!(snapshot.level, 15);
+    assert_eq!(snapshot.experience, 105_000);
+    assert_eq!(snapshot.unspent_skill_points, report.skill_points);
+    assert_eq!(reparsed.max_hp(), max_hp + report.hit_points);
+    assert_eq!(reparsed.current_hp(), Some(hp + report.hit_points));
+
+    let bytes = fs::read(fallout2_save_path(1)).expect("failed to read Fallout 2 fixture");
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout2))
+        .expect("failed to open Fallout 2 save");
+    // Level 1, INT 4, END 9, Gifted.
+    session
+        .set_perk_rank(PERK_EDUCATED, 1)
+        .expect("failed to add Educated");
+    session
+        .set_perk_rank(PERK_LIFEGIVER, 2)
+        .expect("failed to add Lifegiver");
+    let report = session.level_up_to(4).expect("failed to level up");
+    assert_eq!(report.experience, 6_000);
+    assert_eq!(report.skill_points, 3 * (5 + 2 * 4 + 2 - 5));
+    assert_eq!(report.hit_points, 3 * (2 + 9 / 2 + 8));
+    assert_eq!(report.perk_levels, vec![3]);
+    assert!(report.free_perk);
+    assert_eq!(session.snapshot().level, 4);
+    assert_eq!(session.next_level_xp(), 10_000);
+
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified Fallout 2 bytes");
+    let reparsed = fallout2::SaveGame::parse(std::io::Cursor::new(modified))
+        .expect("failed to parse modified Fallout 2 bytes");
+    let editor = reparsed
+        .character_editor
+        .expect("handler 18 should still decode");
+    assert_eq!(editor.last_level, 4);
+    assert_eq!(editor.free_perk, 1);
+}
+
+#[test]
+fn session_recalculates_derived_stats_from_special() {
+    let engine = Engine::new();
+    let fixtures = [
+        (Game::Fallout1, fallout1_save_path(1)),
+        (Game::Fallout1, fallout1_save_path(5)),
+        (Game::Fallout2, fallout2_save_path(1)),
+        (Game::Fallout2, fallout2_save_path(2)),
+    ];
+    for (game, path) in fixtures {
+        let bytes = fs::read(&path).expect("failed to read fixture");
+        let mut session = engine
+            .open_bytes(&bytes, Some(game))
+            .expect("failed to open fixture");
+        session
+            .recalculate_derived_stats()
+            .expect("failed to recalculate derived stats");
+        assert_eq!(
+            session
+                .to_bytes_modified()
+                .expect("failed to emit modified bytes"),
+            bytes,
+            "stored derived stats of {} should already match",
+            path.display()
+        );
+    }
+
+    // Strength 5 with a +3 bonus, +1 from Gifted.
+    let bytes = fs::read(fallout1_save_path(1)).expect("failed to read Fallout 1 fixture");
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout1))
+        .expect("failed to open Fallout 1 save");
+    let carry_weight = session.stat(12).base;
+    session.set_base_stat(0, 3).expect("failed to set strength");
+    assert_eq!(session.stat(12).base, carry_weight);
+    session
+        .set_base_stat_with_recalc(0, 3)
+        .expect("failed to set strength");
+    assert_eq!(session.stat(7).base, 4 + 2 * 4 + 15);
+    assert_eq!(session.stat(11).base, 2);
+    assert_eq!(session.stat(12).base, 200);
+
+    // Endurance 9 and Agility 7, both +1 from Gifted.
+    let bytes = fs::read(fallout2_save_path(1)).expect("failed to read Fallout 2 fixture");
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout2))
+        .expect("failed to open Fallout 2 save");
+    session
+        .set_base_stat_with_recalc(2, 3)
+        .expect("failed to set endurance");
+    session
+        .set_base_stat_with_recalc(5, 4)
+        .expect("failed to set agility");
+
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified Fallout 2 bytes");
+    let reparsed = engine
+        .open_bytes(&modified, Some(Game::Fallout2))
+        .expect("failed to parse modified Fallout 2 bytes");
+    let base = |index: usize| reparsed.stat(index).base;
+    assert_eq!(base(7), 9 + 2 * 4 + 15);
+    assert_eq!(base(8), 7);
+    assert_eq!(base(9), 5);
+    assert_eq!(base(14), 1);
+    assert_eq!(base(31), 8);
+    assert_eq!(base(32), 20);
+    assert_eq!(base(12), 250);
+}
+
+#[test]
+fn session_validate_character_reports_rule_violations() {
+    let engine = Engine::new();
+    let bytes = fs::read(fallout1_save_path(1)).expect("failed to read Fallout 1 fixture");
+    let session = engine
+        .open_bytes(&bytes, Some(Game::Fallout1))
+        .expect("failed to open Fallout 1 save");
+    // Four tagged skills without Tag!, and nine perk ranks at level 13.
+    let rules: Vec<_> = session
+        .validate_character()
+        .iter()
+        .map(|violation| (violation.rule, violation.index))
+        .collect();
+    assert_eq!(
+        rules,
+        vec![
+            (CharacterRule::TaggedSkills, None),
+            (CharacterRule::Perks, None)
+        ]
+    );
+
+    let bytes = fs::read(fallout2_save_path(1)).expect("failed to read Fallout 2 fixture");
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout2))
+        .expect("failed to open Fallout 2 save");
+    assert!(session.validate_character().is_empty());
+
+    // Awareness needs level 3 and has a single rank.
+    session
+        .set_perk_rank(0, 2)
+        .expect("failed to set perk rank");
+    session.set_base_stat(5, 10).expect("failed to set agility");
+    session
+        .set_skill_points(50)
+        .expect("failed to set skill points");
+    let violations = session.validate_character();
+    let rules: Vec<_> = violations
+        .iter()
+        .map(|violation| (violation.rule, violation.index))
+        .collect();
+    assert_eq!(
+        rules,
+        vec![
+            (CharacterRule::Special, Some(5)),
+            (CharacterRule::Perks, Some(0)),
+            (CharacterRule::Perks, Some(0)),
+            (CharacterRule::Perks, None),
+            (CharacterRule::SkillPoints, None),
+        ]
+    );
+    assert!(violations[0].message.contains("11"));
+}
+
+#[test]
+fn session_validate_character_accepts_stat_bonuses_and_book_skills() {
+    const PERK_PRESENCE: usize = 10;
+    const PRESENCE_CHARISMA: i32 = 6;
+    const STAT_CHARISMA: usize = 3;
+    const SKILL_SCIENCE: usize = 12;
+
+    // Presence needs level 3 and CH 6. The character reaches level 3, takes
+    // it, and reads a book that raises Science without spending skill points.
+    let edit = |bytes: &[u8]| {
+        let mut session = Engine::new()
+            .open_bytes(bytes, Some(Game::Fallout2))
+            .expect("failed to open Fallout 2 save");
+        session.set_level(3).expect("failed to set level");
+        session
+            .set_experience(3000)
+            .expect("failed to set experience");
+        session
+            .set_skill_points(0)
+            .expect("failed to set skill points");
+        session
+            .set_perk_rank(PERK_PRESENCE, 1)
+            .expect("failed to set perk rank");
+        let raw = session.skills()[SKILL_SCIENCE].raw;
+        session
+            .set_skill_base_value(SKILL_SCIENCE, raw + 40)
+            .expect("failed to raise skill");
+        session
+    };
+
+    let bytes = fs::read(fallout2_save_path(1)).expect("failed to read Fallout 2 fixture");
+    let rules: Vec<_> = edit(&bytes)
+        .validate_character()
+        .iter()
+        .map(|violation| (violation.rule, violation.index))
+        .collect();
+    assert_eq!(rules, vec![(CharacterRule::Perks, Some(PERK_PRESENCE))]);
+
+    // A bonus from worn items or drugs makes up the difference, as it does
+    // in the game's own check.
+    let mut doc = fallout2::Document::parse_with_layout(std::io::Cursor::new(&bytes))
+        .expect("fixture should parse");
+    let missing = PRESENCE_CHARISMA - doc.save.special_total(STAT_CHARISMA);
+    assert!(missing > 0);
+    let bonus = doc.save.critter_data.bonus_stats[STAT_CHARISMA];
+    doc.set_bonus_stat(STAT_CHARISMA, bonus + missing)
+        .expect("failed to set bonus stat");
+    let boosted = doc
+        .to_bytes_modified()
+        .expect("failed to emit boosted bytes");
+    assert_eq!(edit(&boosted).validate_character(), Vec::new());
+}
+
+#[test]
+fn session_lists_eligible_perks_and_enforces_max_rank() {
+    const PERK_AWARENESS: usize = 0;
+    const PERK_PRESENCE: usize = 10;
+    const PERK_ACTION_BOY: usize = 26;
+    const PERK_NUKA_COLA_ADDICTION: usize = 53;
+    const PERK_MAGNETIC_PERSONALITY: usize = 98;
+
+    let engine = Engine::new();
+    let bytes = fs::read(fallout2_save_path(1)).expect("failed to read Fallout 2 fixture");
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout2))
+        .expect("failed to open Fallout 2 save");
+    assert!(session.eligible_perks().is_empty());
+    assert_eq!(
+        session
+            .perk_info(PERK_NUKA_COLA_ADDICTION)
+            .expect("perk info")
+            .kind,
+        PerkKind::Addiction
+    );
+
+    // Level 3 with PE 6 and CH 4 after Gifted.
+    session.level_up_to(3).expect("failed to level up");
+    let eligible: Vec<usize> = session
+        .eligible_perks()
+        .iter()
+        .map(|perk| perk.index)
+        .collect();
+    assert!(eligible.contains(&PERK_AWARENESS));
+    assert!(eligible.contains(&PERK_MAGNETIC_PERSONALITY));
+    assert!(!eligible.contains(&PERK_PRESENCE));
+
+    let err = session
+        .set_perk_rank_within_max(PERK_AWARENESS, 2)
+        .expect_err("Awareness has a single rank");
+    assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+    session
+        .set_perk_rank_within_max(PERK_AWARENESS, 1)
+        .expect("failed to set perk rank");
+    assert!(
+        !session
+            .eligible_perks()
+            .iter()
+            .any(|perk| perk.index == PERK_AWARENESS)
+    );
+
+    let bytes = fs::read(fallout1_save_path(1)).expect("failed to read Fallout 1 fixture");
+    let session = engine
+        .open_bytes(&bytes, Some(Game::Fallout1))
+        .expect("failed to open Fallout 1 save");
+    assert!(session.eligible_perks().contains(&PerkEntry {
+        index: PERK_ACTION_BOY,
+        name: "Action Boy".to_string(),
+        rank: 1,
+    }));
+}
+
+#[test]
+fn session_cures_addictions_and_their_queue_events() {
+    const PERK_AWARENESS: usize = 0;
+    const PERK_BUFFOUT_ADDICTION: usize = 54;
+    const PERK_MENTATS_ADDICTION: usize = 55;
+    const PID_MENTATS: i32 = 53;
+    const STAT_PERCEPTION: usize = 1;
+    const STAT_INTELLIGENCE: usize = 4;
+
+    // Mentats raise PE and IN by 2 right away and take it back later.
+    let install_dir = temp_test_dir("drug_prototypes");
+    let items_dir = install_dir.join("data").join("proto").join("items");
+    let text_dir = install_dir
+        .join("data")
+        .join("text")
+        .join("english")
+        .join("game");
+    fs::create_dir_all(&items_dir).expect("failed to create proto dir");
+    fs::create_dir_all(&text_dir).expect("failed to create text dir");
+    fs::write(items_dir.join("items.lst"), "00000035.pro\n").expect("failed to write items.lst");
+    fs::write(text_dir.join("pro_item.msg"), "{5300}{}{Mentats}\n")
+        .expect("failed to write pro_item.msg");
+    // pid, message id, type (drug), weight, then stats and stage amounts.
+    write_item_proto(
+        &items_dir,
+        "00000035.pro",
+        &[
+            (0x00, PID_MENTATS),
+            (0x04, 5300),
+            (0x20, 2),
+            (0x2C, 1),
+            (0x39, STAT_PERCEPTION as i32),
+            (0x3D, STAT_INTELLIGENCE as i32),
+            (0x41, -1),
+            (0x45, 2),
+            (0x49, 2),
+            (0x55, -2),
+            (0x59, -2),
+        ],
+        0x7D,
+    );
+    let catalog =
+        ItemCatalog::load_from_install_dir(&install_dir).expect("failed to load item catalog");
+
+    // Take Mentats: the boost is on, its comedown and a withdrawal are queued.
+    let bytes = fs::read(fallout1_save_path(1)).expect("failed to read Fallout 1 fixture");
+    let mut doc = fallout1::Document::parse_with_layout(std::io::Cursor::new(&bytes))
+        .expect("fixture should parse");
+    for stat in [STAT_PERCEPTION, STAT_INTELLIGENCE] {
+        let bonus = doc.save.critter_data.bonus_stats[stat];
+        doc.set_bonus_stat(stat, bonus + 2)
+            .expect("failed to set bonus stat");
+    }
+    let queue = doc
+        .layout()
+        .sections
+        .iter()
+        .find(|section| section.id == SectionId::Handler(15))
+        .expect("missing handler 15")
+        .range;
+    let mut events = doc.save.queue_events.clone().expect("decoded queue");
+    let player_id = doc.save.player_object.id;
+    events.push(fallout_core::event_queue::QueueEvent {
+        time: u32::MAX - 1,
+        object_id: player_id,
+        data: QueueEventData::Drug {
+            stats: [STAT_PERCEPTION as i32, STAT_INTELLIGENCE as i32, -1],
+            modifiers: [-2, -2, 0],
+        },
+    });
+    events.push(fallout_core::event_queue::QueueEvent {
+        time: u32::MAX,
+        object_id: player_id,
+        data: QueueEventData::Withdrawal {
+            start: 1,
+            item_pid: PID_MENTATS,
+            perk: PERK_MENTATS_ADDICTION as i32,
+        },
+    });
+    let mut blob = Vec::new();
+    fallout_core::event_queue::emit_event_queue(&events, &mut blob);
+    let boosted = doc
+        .to_bytes_modified()
+        .expect("failed to emit boosted bytes");
+    let mut bytes_with_events = boosted[..queue.start].to_vec();
+    bytes_with_events.extend_from_slice(&blob);
+    bytes_with_events.extend_from_slice(&boosted[queue.end..]);
+
+    let engine = Engine::new();
+    let mut session = engine
+        .open_bytes(&bytes_with_events, Some(Game::Fallout1))
+        .expect("failed to open Fallout 1 save");
+    session
+        .set_perk_rank(PERK_BUFFOUT_ADDICTION, 1)
+        .expect("failed to set addiction");
+    let addictions = session.addictions();
+    assert_eq!(
+        addictions
+            .iter()
+            .map(|a| (a.index, a.active, a.withdrawal_events))
+            .collect::<Vec<_>>(),
+        vec![
+            (PERK_BUFFOUT_ADDICTION, true, Some(0)),
+            (PERK_MENTATS_ADDICTION, false, Some(1))
+        ]
+    );
+    assert_eq!(session.export_character().addictions, addictions);
+
+    assert!(session.cure_addiction(PERK_AWARENESS, &catalog).is_err());
+    assert_eq!(
+        session.cure_addiction(PERK_BUFFOUT_ADDICTION, &catalog),
+        Ok(0)
+    );
+    assert_eq!(
+        session.cure_addiction(PERK_MENTATS_ADDICTION, &catalog),
+        Ok(2)
+    );
+    assert!(session.addictions().is_empty());
+
+    // The comedown was applied, so the save is back to before the dose.
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified bytes");
+    assert_eq!(modified, bytes);
+
+    // Fallout 2 keeps the same events in handler 24.
+    let bytes = fs::read(fallout2_save_path(1)).expect("failed to read Fallout 2 fixture");
+    let mut doc = fallout2::Document::parse_with_layout(std::io::Cursor::new(&bytes))
+        .expect("fixture should parse");
+    for stat in [STAT_PERCEPTION, STAT_INTELLIGENCE] {
+        let bonus = doc.save.critter_data.bonus_stats[stat];
+        doc.set_bonus_stat(stat, bonus + 2)
+            .expect("failed to set bonus stat");
+    }
+    let queue = doc
+        .layout()
+        .sections
+        .iter()
+        .find(|section| section.id == SectionId::Handler(24))
+        .expect("missing handler 24")
+        .range;
+    let mut events = doc.save.queue_events.clone().expect("decoded queue");
+    let player_id = doc.save.player_object.id;
+    events.push(fallout_core::event_queue::QueueEvent {
+        time: u32::MAX - 1,
+        object_id: player_id,
+        data: QueueEventData::Drug {
+            stats: [STAT_PERCEPTION as i32, STAT_INTELLIGENCE as i32, -1],
+            modifiers: [-2, -2, 0],
+        },
+    });
+    events.push(fallout_core::event_queue::QueueEvent {
+        time: u32::MAX,
+        object_id: player_id,
+        data: QueueEventData::Withdrawal {
+            start: 1,
+            item_pid: PID_MENTATS,
+            perk: PERK_MENTATS_ADDICTION as i32,
+        },
+    });
+    let mut blob = Vec::new();
+    fallout_core::event_queue::emit_event_queue(&events, &mut blob);
+    let boosted = doc
+        .to_bytes_modified()
+        .expect("failed to emit boosted bytes");
+    let mut bytes_with_events = boosted[..queue.start].to_vec();
+    bytes_with_events.extend_from_slice(&blob);
+    bytes_with_events.extend_from_slice(&boosted[queue.end..]);
+
+    let mut session = engine
+        .open_bytes(&bytes_with_events, Some(Game::Fallout2))
+        .expect("failed to open Fallout 2 save");
+    assert_eq!(
+        session
+            .addictions()
+            .iter()
+            .map(|a| (a.index, a.active, a.withdrawal_events))
+            .collect::<Vec<_>>(),
+        vec![(PERK_MENTATS_ADDICTION, false, Some(1))]
+    );
+    assert_eq!(
+        session.cure_addiction(PERK_MENTATS_ADDICTION, &catalog),
+        Ok(2)
+    );
+    assert!(session.addictions().is_empty());
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified bytes");
+    assert_eq!(modified, bytes);
+}
+
+#[test]
+fn session_can_edit_tagged_skills_fallout2() {
+    const PERK_TAG: usize = 51;
+
+    let engine = Engine::new();
+    let bytes = fs::read(fallout2_save_path(1)).expect("failed to read Fallout 2 fixture");
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout2))
+        .expect("failed to open Fallout 2 save");
+    assert_eq!(session.tagged_skill_indices(), [4, 0, 5]);
+    let skill = |session: &fallout_core::core_api::Session, index: usize| {
+        session
+            .skills()
+            .into_iter()
+            .find(|entry| entry.index == index)
+            .expect("skill should exist")
+    };
+    let untagged_total = skill(&session, 8).total;
+    session
+        .set_tagged_skills(&[3, 0, 5])
+        .expect("failed to tag skill 3");
+    let regular_tag_bonus = skill(&session, 3).tag_bonus;
+
+    session
+        .set_tagged_skills(&[8, 0, 5])
+        .expect("failed to retag skills");
+    assert_eq!(session.tagged_skill_indices(), [8, 0, 5]);
+    assert_eq!(skill(&session, 4).tag_bonus, 0);
+    assert!(skill(&session, 8).tag_bonus >= 20);
+    assert_eq!(
+        skill(&session, 8).total,
+        untagged_total + skill(&session, 8).tag_bonus
+    );
+
+    for invalid in [&[0, 1, 2, 3][..], &[0, 0], &[18]] {
+        let err = session
+            .set_tagged_skills(invalid)
+            .expect_err("invalid tags should be rejected");
+        assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+    }
+
+    // The Tag! perk allows a fourth tag, which does not get the flat +20.
+    let mut export = session.export_character();
+    export.perks.push(PerkEntry {
+        index: PERK_TAG,
+        name: "Tag!".to_string(),
+        rank: 1,
+    });
+    export.tagged_skills = vec![8, 0, 5, 3];
+    session
+        .apply_character(&export)
+        .expect("failed to apply tags with the Tag! perk");
+    assert_eq!(session.export_character().tagged_skills, [8, 0, 5, 3]);
+    assert_eq!(skill(&session, 3).tag_bonus, regular_tag_bonus - 20);
+
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified Fallout 2 bytes");
+    let reparsed = engine
+        .open_bytes(&modified, Some(Game::Fallout2))
+        .expect("failed to parse modified Fallout 2 bytes");
+    assert_eq!(reparsed.tagged_skill_indices(), [8, 0, 5, 3]);
+    assert_eq!(reparsed.skills(), session.skills());
+}
+
+#[test]
+fn session_can_edit_kill_counts_fallout1() {
+    const KILL_TYPE_CHILD: usize = 2;
+    const KILL_TYPE_RAT: usize = 7;
+
+    let engine = Engine::new();
+    let bytes = fs::read(fallout1_save_path(1)).expect("failed to read Fallout 1 fixture");
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout1))
+        .expect("failed to open Fallout 1 save");
+    let kill_count = |session: &fallout_core::core_api::Session, index: usize| {
+        session.all_kill_counts()[index].count
+    };
+    assert_eq!(kill_count(&session, KILL_TYPE_RAT), 128);
+
+    session
+        .set_kill_count(KILL_TYPE_CHILD, 3)
+        .expect("failed to set kill count");
+    assert_eq!(kill_count(&session, KILL_TYPE_CHILD), 3);
+    let kill_type_count = session.all_kill_counts().len();
+    assert!(session.set_kill_count(kill_type_count, 1).is_err());
+    assert!(session.set_kill_count(KILL_TYPE_RAT, -1).is_err());
+
+    let mut export = session.export_character();
+    export
+        .kill_counts
+        .retain(|entry| entry.index != KILL_TYPE_CHILD);
+    export
+        .kill_counts
+        .iter_mut()
+        .find(|entry| entry.index == KILL_TYPE_RAT)
+        .expect("rat kills should be exported")
+        .count = 5;
+    session
+        .apply_character(&export)
+        .expect("failed to apply kill counts");
+
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified Fallout 1 bytes");
+    let reparsed = engine
+        .open_bytes(&modified, Some(Game::Fallout1))
+        .expect("failed to parse modified Fallout 1 bytes");
+    assert_eq!(kill_count(&reparsed, KILL_TYPE_CHILD), 0);
+    assert_eq!(kill_count(&reparsed, KILL_TYPE_RAT), 5);
+    assert_eq!(reparsed.nonzero_kill_counts(), export.kill_counts);
+}
+
+#[test]
+fn session_game_time_edits_keep_date_and_queue_in_step_fallout1() {
+    const TICKS_PER_DAY: u32 = 864_000;
+
+    let engine = Engine::new();
+    for slot in 1..=7 {
+        let bytes = fs::read(fallout1_save_path(slot)).expect("failed to read Fallout 1 fixture");
+        let mut session = engine
+            .open_bytes(&bytes, Some(Game::Fallout1))
+            .expect("failed to open Fallout 1 save");
+        let game_time = session.snapshot().game_time;
+        if session.queue_events().is_err() {
+            // Pending events could not be moved with the clock.
+            assert!(session.set_game_time(game_time).is_err());
+            continue;
+        }
+        session
+            .set_game_time(game_time)
+            .expect("failed to set game time");
+        assert_eq!(
+            session
+                .to_bytes_modified()
+                .expect("failed to emit Fallout 1 bytes"),
+            bytes,
+            "slot {slot} date should match the engine calendar"
+        );
+    }
+
+    let bytes = fs::read(fallout1_save_path(1)).expect("failed to read Fallout 1 fixture");
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout1))
+        .expect("failed to open Fallout 1 save");
+    let game_time = session.snapshot().game_time;
+    let event_times: Vec<u32> = session
+        .queue_events()
+        .expect("decoded queue")
+        .iter()
+        .map(|e| e.time)
+        .collect();
+    assert!(!event_times.is_empty());
+    let age = session.age();
+
+    session
+        .advance_game_time(30 * TICKS_PER_DAY)
+        .expect("failed to advance game time");
+    assert_eq!(session.snapshot().game_time, game_time + 30 * TICKS_PER_DAY);
+    let date = session.snapshot().game_date;
+    assert_eq!((date.year, date.month, date.day), (2163, 3, 12));
+    assert!(
+        session.advance_game_time(u32::MAX).is_err(),
+        "overflowing the clock should fail"
+    );
+
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified Fallout 1 bytes");
+    let reparsed = engine
+        .open_bytes(&modified, Some(Game::Fallout1))
+        .expect("failed to parse modified Fallout 1 bytes");
+    assert_eq!(reparsed.snapshot().game_date, date);
+    assert_eq!(
+        reparsed.snapshot().game_time,
+        game_time + 30 * TICKS_PER_DAY
+    );
+    let shifted: Vec<u32> = reparsed
+        .queue_events()
+        .expect("decoded queue")
+        .iter()
+        .map(|e| e.time)
+        .collect();
+    let expected: Vec<u32> = event_times
+        .iter()
+        .map(|time| time + 30 * TICKS_PER_DAY)
+        .collect();
+    assert_eq!(shifted, expected);
+    assert_eq!(reparsed.age(), age);
+}
+
+#[test]
+fn session_can_reset_game_time_fallout2() {
+    let engine = Engine::new();
+    let bytes = fs::read(fallout2_save_path(1)).expect("failed to read Fallout 2 fixture");
+    let mut session = engine
+        .open_bytes(&bytes, Some(Game::Fallout2))
+        .expect("failed to open Fallout 2 save");
+    let delta = 200 * 864_000 - i64::from(session.snapshot().game_time);
+    let expected: Vec<u32> = session
+        .queue_events()
+        .expect("SLOT01 queue should decode")
+        .iter()
+        .map(|event| (i64::from(event.time) + delta).clamp(0, i64::from(u32::MAX)) as u32)
+        .collect();
+
+    session
+        .set_game_time(200 * 864_000)
+        .expect("failed to set game time");
+    let modified = session
+        .to_bytes_modified()
+        .expect("failed to emit modified Fallout 2 bytes");
+    let reparsed = engine
+        .open_bytes(&modified, Some(Game::Fallout2))
+        .expect("failed to parse modified Fallout 2 bytes");
+    let date = reparsed.snapshot().game_date;
+    assert_eq!((date.year, date.month, date.day), (2242, 2, 10));
+    assert_eq!(reparsed.snapshot().game_time, 200 * 864_000);
+    let shifted: Vec<u32> = reparsed
+        .queue_events()
+        .expect("decoded queue")
+        .iter()
+        .map(|event| event.time)
+        .collect();
+    assert_eq!(shifted, expected);
+}
+
+#[test]
+fn slot_session_teleports_between_saved_maps_fallout2() {
+    let engine = Engine::new();
+    let slot_dir = workspace_root().join("tests/fallout2_examples/SLOT01");
+    let mut slot = engine
+        .open_slot(&slot_dir)
+        .expect("failed to open SLOT01 directory");
+    assert_eq!(slot.session().snapshot().map_id, 3);
+    assert_eq!(
+        slot.map_save_name_for_id(126).expect("map lookup"),
+        Some("ARTEMPLE.SAV".to_string())
+    );
+
+    let session = slot.session_mut();
+    session
+        .teleport(0, 20_100)
+        .expect("failed to move within the current map");
+    assert_eq!(session.player_tile(), 20_100);
+    assert_eq!(session.snapshot().map_id, 3);
+    assert!(session.teleport(3, 20_100).is_err());
+    assert!(session.teleport(0, 40_000).is_err());
+    let artemple = slot.map_save("ARTEMPLE.SAV").expect("ARTEMPLE map save");
+    let session = slot.session_mut();
+    assert!(
+        session
+            .teleport_to_map_save("NOWHERE.SAV", &artemple.header, 0, 20_100)
+            .is_err()
+    );
+    let other_map = session
+        .map_files()
+        .into_iter()
+        .find(|name| !name.eq_ignore_ascii_case("ARTEMPLE.SAV"))
+        .expect("SLOT01 saves more than one map");
+    let err = session
+        .teleport_to_map_save(&other_map, &artemple.header, 0, 20_100)
+        .expect_err("a map save name and another map's header should not mix");
+    assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+
+    // ARTEMPLE only has its first elevation.
+    assert!(slot.teleport(126, 1, 15_000).is_err());
+    assert!(slot.teleport(999, 0, 15_000).is_err());
+    slot.teleport(126, 0, 15_000)
+        .expect("failed to teleport to ARTEMPLE");
+
+    let modified = slot
+        .session()
+        .to_bytes_modified()
+        .expect("failed to emit modified Fallout 2 bytes");
+    let reparsed = engine
+        .open_bytes(&modified, Some(Game::Fallout2))
+        .expect("failed to parse modified Fallout 2 bytes");
+    let snapshot = reparsed.snapshot();
+    assert_eq!(snapshot.map_id, 126);
+    assert_eq!(snapshot.map_filename, "ARTEMPLE.sav");
+    assert_eq!(snapshot.elevation, 0);
+    assert_eq!(reparsed.player_tile(), 15_000);
+}
+
+#[test]
+fn slot_session_loads_all_slot_files_fallout2() {
+    let slot_dir = workspace_root().join("tests/fallout2_examples/SLOT01");
+    let slot = Engine::new()
+        .open_slot(&slot_dir)
+        .expect("failed to open SLOT01 directory");
+
+    assert_eq!(slot.session().game(), Game::Fallout2);
+    assert_eq!(slot.visited_maps(), ["ARCAVES.SAV", "ARTEMPLE.SAV"]);
+    assert_eq!(slot.map_save_names(), ["ARCAVES.SAV", "ARTEMPLE.SAV"]);
+    assert!(slot.map_save_bytes("artemple.sav").is_some());
+    assert!(slot.automap_bytes().is_some());
+    assert_eq!(
+        slot.sfall_file_names(),
+        ["sfalldb.sav", "sfallfs.sav", "sfallgv.sav"]
+    );
+    assert!(!slot.proto_paths().is_empty());
+    assert!(
+        slot.proto_paths()
+            .iter()
+            .all(|path| path.starts_with("proto"))
+    );
+}
+
+#[test]
+fn slot_session_writes_edited_slot_to_new_directory() {
+    let slot_dir = workspace_root().join("tests/fallout1_examples/SAVEGAME/SLOT01");
+    let engine = Engine::new();
+    let mut slot = engine
+        .open_slot(&slot_dir)
+        .expect("failed to open SLOT01 directory");
+    assert_eq!(slot.session().game(), Game::Fallout1);
+    assert_eq!(slot.map_save_names().len(), 34);
+    assert!(slot.sfall_file_names().is_empty());
+    slot.session_mut()
+        .set_global_var(0, 4242)
+        .expect("failed to set global");
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before unix epoch")
+        .as_nanos();
+    let out_dir = std::env::temp_dir().join(format!(
+        "fallout_se_slot_write_{}_{}",
+        std::process::id(),
+        nanos
+    ));
+    slot.write_to_dir(&out_dir).expect("failed to write slot");
+
+    let err = slot
+        .write_to_dir(&out_dir)
+        .expect_err("existing output directory should be refused");
+    assert_eq!(err.code, CoreErrorCode::Io);
+
+    let reopened = engine
+        .open_slot(&out_dir)
+        .expect("failed to reopen written slot");
+    assert_eq!(reopened.session().global_var(0), Some(4242));
+    for name in slot.map_save_names() {
+        assert_eq!(
+            reopened.map_save_bytes(&name),
+            fs::read(slot_dir.join(&name)).ok().as_deref(),
+            "{name} should be copied unchanged"
+        );
+    }
+    assert_eq!(
+        reopened.automap_bytes(),
+        fs::read(slot_dir.join("AUTOMAP.SAV")).ok().as_deref()
+    );
+
+    let _ = fs::remove_dir_all(&out_dir);
+}
+
+#[test]
+fn slot_session_moves_items_between_map_containers_and_player() {
+    let slot_dir = workspace_root().join("tests/fallout2_examples/SLOT01");
+    let engine = Engine::new();
+    let mut slot = engine
+        .open_slot(&slot_dir)
+        .expect("failed to open SLOT01 directory");
+
+    let holders = slot
+        .map_inventories("ARCAVES.SAV")
+        .expect("failed to list map inventories");
+    assert!(
+        holders
+            .iter()
+            .any(|holder| holder.kind == MapInventoryHolderKind::Critter)
+    );
+    let container = holders
+        .iter()
+        .find(|holder| {
+            holder.kind == MapInventoryHolderKind::Container && !holder.inventory.is_empty()
+        })
+        .expect("ARCAVES.SAV should have a non-empty container")
+        .clone();
+    let moved = container.inventory[0];
+    let player_qty = |slot: &fallout_core::core_api::SlotSession| -> i32 {
+        slot.session()
+            .inventory()
+            .iter()
+            .filter(|item| item.pid == moved.pid)
+            .map(|item| item.quantity)
+            .sum()
+    };
+    let before = player_qty(&slot);
+
+    slot.move_item_to_player("ARCAVES.SAV", container.object_id, moved.pid, None)
+        .expect("failed to move item to player");
+    assert_eq!(player_qty(&slot), before + moved.quantity);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before unix epoch")
+        .as_nanos();
+    let out_dir = std::env::temp_dir().join(format!(
+        "fallout_se_slot_move_{}_{}",
+        std::process::id(),
+        nanos
+    ));
+    slot.write_to_dir(&out_dir).expect("failed to write slot");
+    let mut reopened = engine
+        .open_slot(&out_dir)
+        .expect("failed to reopen written slot");
+    assert_eq!(player_qty(&reopened), before + moved.quantity);
+    let emptied = reopened
+        .map_inventories("ARCAVES.SAV")
+        .expect("failed to list map inventories")
+        .into_iter()
+        .find(|holder| holder.object_id == container.object_id)
+        .expect("container should still be on the map");
+    assert_eq!(
+        emptied.inventory.len() + 1,
+        container.inventory.len(),
+        "moved stack should be gone from the container"
+    );
+    assert_eq!(
+        reopened.map_save_bytes("ARTEMPLE.SAV"),
+        fs::read(slot_dir.join("ARTEMPLE.SAV")).ok().as_deref()
+    );
+
+    reopened
+        .move_item_from_player("ARCAVES.SAV", container.object_id, moved.pid, Some(1))
+        .expect("failed to move item back");
+    assert_eq!(player_qty(&reopened), before + moved.quantity - 1);
+    let refilled = reopened
+        .map_inventories("ARCAVES.SAV")
+        .expect("failed to list map inventories")
+        .into_iter()
+        .find(|holder| holder.object_id == container.object_id)
+        .expect("container should still be on the map");
+    assert_eq!(refilled.inventory[0].pid, moved.pid);
+    assert_eq!(refilled.inventory[0].quantity, 1);
+
+    let err = reopened
+        .move_item_from_player("ARCAVES.SAV", -12345, moved.pid, None)
+        .expect_err("unknown holder should be rejected");
+    assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+    assert_eq!(player_qty(&reopened), before + moved.quantity - 1);
+
+    let _ = fs::remove_dir_all(&out_dir);
+}
+
+#[test]
+fn slot_session_moves_items_between_fallout1_map_holders() {
+    let slot_dir = workspace_root().join("tests/fallout1_examples/SAVEGAME/SLOT01");
+    let mut slot = Engine::new()
+        .open_slot(&slot_dir)
+        .expect("failed to open SLOT01 directory");
+
+    let holders = slot
+        .map_inventories("HUBOLDTN.SAV")
+        .expect("failed to list map inventories");
+    let containers: Vec<_> = holders
+        .iter()
+        .filter(|holder| {
+            holder.kind == MapInventoryHolderKind::Container && !holder.inventory.is_empty()
+        })
+        .collect();
+    assert!(containers.len() >= 2);
+    let (from, to) = (containers[0].clone(), containers[1].clone());
+    let moved = from.inventory[0];
+
+    slot.move_map_item(
+        "HUBOLDTN.SAV",
+        from.object_id,
+        to.object_id,
+        moved.pid,
+        None,
+    )
+    .expect("failed to move item between containers");
+
+    let map = slot.map_save("HUBOLDTN.SAV").expect("failed to parse map");
+    let to_object = map.object_by_id(to.object_id).expect("target container");
+    let carried: i32 = to_object
+        .inventory
+        .iter()
+        .filter(|item| item.object.pid == moved.pid)
+        .map(|item| item.quantity)
+        .sum();
+    let carried_before: i32 = to
+        .inventory
+        .iter()
+        .filter(|item| item.pid == moved.pid)
+        .map(|item| item.quantity)
+        .sum();
+    assert_eq!(carried, carried_before + moved.quantity);
+    assert_eq!(
+        map.object_by_id(from.object_id)
+            .expect("source container")
+            .inventory
+            .len()
+            + 1,
+        from.inventory.len()
+    );
+}
+
+#[test]
+fn slot_session_edits_sfall_globals() {
+    let slot_dir = workspace_root().join("tests/fallout2_examples/SLOT01");
+    let engine = Engine::new();
+    let mut slot = engine
+        .open_slot(&slot_dir)
+        .expect("failed to open SLOT01 directory");
+
+    let looter = SfallGlobalKey::named("p_looter").expect("valid name");
+    let globals = slot
+        .sfall_globals()
+        .expect("failed to parse sfallgv.sav")
+        .expect("slot was saved with sfall");
+    assert_eq!(globals.get(&looter), Some(0));
+    assert!(
+        slot.sfall_file_system()
+            .expect("failed to parse sfallfs.sav")
+            .is_some()
+    );
+    assert!(
+        slot.sfall_arrays()
+            .expect("failed to parse sfalldb.sav")
+            .is_some()
+    );
+
+    slot.set_sfall_global(looter.clone(), 5)
+        .expect("failed to set sfall global");
+    let edited = slot
+        .sfall_globals()
+        .expect("failed to parse edited sfallgv.sav")
+        .expect("still present");
+    assert_eq!(edited.get(&looter), Some(5));
+    assert_eq!(slot.sfall_file_names().len(), 3);
+
+    let fallout1 = engine
+        .open_slot(workspace_root().join("tests/fallout1_examples/SAVEGAME/SLOT01"))
+        .expect("failed to open Fallout 1 slot");
+    assert!(fallout1.sfall_globals().expect("no parse error").is_none());
+}
+
+#[test]
+fn slot_session_reveals_and_clears_automap() {
+    let slot_dir = workspace_root().join("tests/fallout2_examples/SLOT01");
+    let engine = Engine::new();
+    let mut slot = engine
+        .open_slot(&slot_dir)
+        .expect("failed to open SLOT01 directory");
+
+    // Map 126 is ARTEMPLE, the map the player stands on.
+    let automap = slot.automap().expect("failed to parse automap");
+    let explored = automap
+        .tiles(126, 0)
+        .expect("ARTEMPLE should be explored")
+        .explored_count();
+    assert_eq!(
+        slot.session().automap_size() as usize,
+        automap.to_uncompressed_bytes().len()
+    );
+
+    slot.reveal_automap(126).expect("failed to reveal ARTEMPLE");
+    let revealed = slot.automap().expect("failed to parse revealed automap");
+    assert!(
+        revealed
+            .tiles(126, 0)
+            .expect("still explored")
+            .explored_count()
+            > explored
+    );
+    assert_eq!(revealed.tiles(3, 0), automap.tiles(3, 0));
+    assert_eq!(
+        slot.session().automap_size() as usize,
+        revealed.to_uncompressed_bytes().len()
+    );
+    let map = slot.map_save("ARTEMPLE.SAV").expect("failed to parse map");
+    assert!(
+        map.objects[0]
+            .iter()
+            .filter(|object| automap_tile_for(object).is_some())
+            .all(|object| object.flags & OBJECT_FLAG_SEEN != 0)
+    );
+
+    let err = slot
+        .reveal_automap(150)
+        .expect_err("maps without a save should be rejected");
+    assert_eq!(err.code, CoreErrorCode::UnsupportedOperation);
+
+    slot.clear_automap(3).expect("failed to clear ARCAVES");
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before unix epoch")
+        .as_nanos();
+    let out_dir = std::env::temp_dir().join(format!(
+        "fallout_se_slot_automap_{}_{}",
+        std::process::id(),
+        nanos
+    ));
+    slot.write_to_dir(&out_dir).expect("failed to write slot");
+    let reopened = engine
+        .open_slot(&out_dir)
+        .expect("failed to reopen written slot");
+    let automap = reopened.automap().expect("failed to parse written automap");
+    assert!(automap.tiles(3, 0).is_none());
+    assert_eq!(automap.tiles(126, 0), revealed.tiles(126, 0));
+    assert_eq!(
+        reopened.session().automap_size() as usize,
+        automap.to_uncompressed_bytes().len()
+    );
+    let caves = reopened
+        .map_save("ARCAVES.SAV")
+        .expect("failed to parse map");
+    assert!(
+        caves
+            .all_objects()
+            .all(|(_, object)| object.flags & OBJECT_FLAG_SEEN == 0
+                || automap_tile_for(object).is_none())
+    );
+
+    let _ = fs::remove_dir_all(&out_dir);
+}
+
+#[test]
+fn global_var_catalog_names_resolve_from_install_dir() {
+    let install_dir = temp_test_dir("global_var_catalog");
+    let data_dir = install_dir.join("data");
+    fs::create_dir_all(&data_dir).expect("failed to create data dir");
+    fs::write(
+        data_dir.join("VAULT13.GAM"),
+        "GAME_GLOBAL_VARS:\nGVAR_PLAYER_REPUTATION :=0; // (0)\nGVAR_CHILDREN_KILLED :=0; // (1)\n",
+    )
+    .expect("failed to write vault13.gam");
+    let catalog = GlobalVarCatalog::load_from_install_dir(&install_dir)
+        .expect("failed to load global var catalog");
+    assert_eq!(catalog.len(), 2);
+    assert_eq!(catalog.index_of("gvar_children_killed"), Some(1));
+
+    let bytes = fs::read(fallout2_save_path(1)).expect("failed to read Fallout 2 fixture");
+    let session = Engine::new()
+        .open_bytes(&bytes, Some(Game::Fallout2))
+        .expect("failed to open Fallout 2 save");
+    let resolved = session.global_vars_resolved(Some(&catalog));
+    assert_eq!(resolved.len(), session.global_vars().len());
+    assert_eq!(resolved[0].name.as_deref(), Some("GVAR_PLAYER_REPUTATION"));
+    assert_eq!(resolved[1].name.as_deref(), Some("GVAR_CHILDREN_KILLED"));
+    assert_eq!(resolved[2].name, None);
+    assert_eq!(resolved[2].value, session.global_vars()[2]);
+
+    let _ = fs::remove_dir_all(&install_dir);
+}
+
 #[test]
 fn session_can_export_character_model() {
     let engine = Engine::new();
@@ -881,6 +2849,8 @@ fn session_can_apply_character_export_and_emit_modified_bytes_fallout2() {
     export.name = "ChosenOneX".to_string();
     export.description = "Edited Save".to_string();
     export.hp = Some(30);
+    export.radiation = Some(120);
+    export.poison = Some(7);
     let new_skill_raw = export
         .skills
         .iter()
@@ -922,6 +2892,8 @@ fn session_can_apply_character_export_and_emit_modified_bytes_fallout2() {
     assert_eq!(session.snapshot().character_name, "ChosenOneX");
     assert_eq!(session.snapshot().description, "Edited Save");
     assert_eq!(session.current_hp(), Some(30));
+    assert_eq!(session.radiation(), Some(120));
+    assert_eq!(session.poison(), Some(7));
     assert_eq!(session.age(), new_age_total);
     assert_eq!(
         session
@@ -950,6 +2922,8 @@ fn session_can_apply_character_export_and_emit_modified_bytes_fallout2() {
     assert_eq!(reparsed.snapshot().character_name, "ChosenOneX");
     assert_eq!(reparsed.snapshot().description, "Edited Save");
     assert_eq!(reparsed.current_hp(), Some(30));
+    assert_eq!(reparsed.radiation(), Some(120));
+    assert_eq!(reparsed.poison(), Some(7));
     assert_eq!(reparsed.age(), new_age_total);
     assert_eq!(
         reparsed
@@ -4,6 +4,7 @@ use std::path::PathBuf;
 
 use fallout_core::fallout1::Document as Fallout1Document;
 use fallout_core::fallout2::Document as Fallout2Document;
+use fallout_core::layout::{ByteRange, SectionId};
 
 fn workspace_root() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..")
@@ -60,6 +61,78 @@ fn fallout2_document_roundtrip_slot01() {
     assert_eq!(emitted, bytes);
 }
 
+fn fallout2_section_range(doc: &Fallout2Document, id: SectionId) -> Option<ByteRange> {
+    doc.layout()
+        .sections
+        .iter()
+        .find(|section| section.id == id)
+        .map(|section| section.range)
+}
+
+#[test]
+fn fallout2_document_splits_handlers_after_preferences() {
+    for slot in [1, 2] {
+        let bytes = fs::read(fallout2_save_path(slot)).expect("failed to read Fallout 2 fixture");
+        let doc = Fallout2Document::parse_with_layout(Cursor::new(bytes.as_slice()))
+            .expect("failed to parse Fallout 2 document");
+
+        let h17 = fallout2_section_range(&doc, SectionId::Handler(17)).expect("handler 17");
+        assert_eq!(h17.len(), 80);
+        let expected_lens = [
+            (18, Some(5)),
+            (19, None),
+            (20, Some(0)),
+            (21, Some(17)),
+            (22, Some(216)),
+            (23, None),
+        ];
+        let mut previous_end = h17.end;
+        for (handler, expected_len) in expected_lens {
+            let range = fallout2_section_range(&doc, SectionId::Handler(handler))
+                .unwrap_or_else(|| panic!("slot {slot} should have handler {handler}"));
+            assert_eq!(range.start, previous_end);
+            if let Some(len) = expected_len {
+                assert_eq!(range.len(), len, "slot {slot} handler {handler}");
+            }
+            previous_end = range.end;
+        }
+        let queue = fallout2_section_range(&doc, SectionId::Handler(24))
+            .unwrap_or_else(|| panic!("slot {slot} should have handler 24"));
+        assert_eq!(queue.start, previous_end);
+        let tail = fallout2_section_range(&doc, SectionId::Tail).expect("tail after the queue");
+        assert_eq!(tail.start, queue.end);
+
+        let emitted = doc
+            .to_bytes_unmodified()
+            .expect("failed to emit Fallout 2 unmodified bytes");
+        assert_eq!(emitted, bytes);
+    }
+}
+
+#[test]
+fn fallout2_document_keeps_undecodable_handlers_in_tail() {
+    let mut bytes = fs::read(fallout2_save_path(1)).expect("failed to read Fallout 2 fixture");
+    let doc = Fallout2Document::parse_with_layout(Cursor::new(bytes.as_slice()))
+        .expect("failed to parse Fallout 2 document");
+    let h19 = fallout2_section_range(&doc, SectionId::Handler(19)).expect("handler 19");
+
+    // Corrupt the world map "met Frank" flag, which must be 0 or 1.
+    bytes[h19.start..h19.start + 4].copy_from_slice(&7i32.to_be_bytes());
+    let doc = Fallout2Document::parse_with_layout(Cursor::new(bytes.as_slice()))
+        .expect("corrupt tail handlers should not fail the parse");
+    assert!(doc.save.world_map.is_none());
+    assert!(doc.save.queue_events.is_none());
+    assert!(fallout2_section_range(&doc, SectionId::Handler(18)).is_none());
+    let h17 = fallout2_section_range(&doc, SectionId::Handler(17)).expect("handler 17");
+    let tail = fallout2_section_range(&doc, SectionId::Tail).expect("tail");
+    assert_eq!(tail.start, h17.end);
+
+    let emitted = doc
+        .to_bytes_unmodified()
+        .expect("failed to emit Fallout 2 unmodified bytes");
+    assert_eq!(emitted, bytes);
+}
+
 #[test]
 fn fallout1_document_rejects_invalid_signature() {
     let invalid = vec![0u8; 64];
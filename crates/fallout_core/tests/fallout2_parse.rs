@@ -62,14 +62,17 @@ fn parse_slot01_core_sections() {
             .all(|&s| s == -1 || (0..18).contains(&s))
     );
 
-    assert_eq!(save.kill_counts[7], 2); // Rat
-    assert_eq!(save.kill_counts[18], 2); // Big Bad Boss
+    assert_eq!(save.tagged_skills[3], -1);
+
+    assert_eq!(save.kill_counts[6], 2); // Radscorpion
+    assert_eq!(save.kill_counts[17], 2); // Giant Ant
 
     assert_eq!(save.pc_stats.level, 1);
-    assert_eq!(save.pc_stats.experience, 1);
+    assert_eq!(save.pc_stats.experience, 170);
     assert_eq!(save.pc_stats.unspent_skill_points, 0);
     assert_eq!(save.pc_stats.reputation, 0);
-    assert_eq!(save.pc_stats.karma, 1);
+    assert_eq!(save.pc_stats.karma, 0);
+    assert_eq!(save.selected_traits, [6, 15]);
 
     assert!(save.party_member_count > 0);
     assert!(save.ai_packet_count <= save.party_member_count);
@@ -108,18 +111,58 @@ fn parse_slot02_core_sections() {
     assert_eq!(save.critter_data.base_stats[4], 7);
     assert_eq!(save.gender, Gender::Female);
 
-    assert!(save.tagged_skills.contains(&0));
-    assert!(save.tagged_skills.contains(&6));
-    assert!(save.tagged_skills.contains(&14));
-    assert!(save.tagged_skills.contains(&15));
+    assert_eq!(save.tagged_skills, [14, 15, 6, -1]);
 
     assert_eq!(save.pc_stats.level, 1);
-    assert_eq!(save.pc_stats.experience, 1);
+    assert_eq!(save.pc_stats.experience, 0);
     assert_eq!(save.pc_stats.unspent_skill_points, 0);
     assert_eq!(save.pc_stats.reputation, 0);
-    assert_eq!(save.pc_stats.karma, 1);
+    assert_eq!(save.pc_stats.karma, 0);
+    assert_eq!(save.selected_traits, [3, 13]);
 
     assert!(save.party_member_count > 0);
     assert!(save.ai_packet_count <= save.party_member_count);
     assert!(save.layout_detection_score > 0);
 }
+
+#[test]
+fn parse_slot01_handlers_after_preferences() {
+    let save = load_slot(1);
+
+    assert_eq!(save.preferences.game_difficulty, 1);
+    assert_eq!(save.game_difficulty(), 1);
+    assert_eq!(save.preferences.violence_level, 3);
+    assert_eq!(save.preferences.text_base_delay, 3.5);
+    assert_eq!(save.preferences.brightness, 1.0);
+
+    let editor = save
+        .character_editor
+        .as_ref()
+        .expect("handler 18 should parse");
+    assert_eq!(editor.last_level, 1);
+    assert_eq!(editor.free_perk, 0);
+
+    let world_map = save.world_map.as_ref().expect("handler 19 should parse");
+    assert!(!world_map.is_in_car);
+    assert_eq!(world_map.car_fuel, 80000);
+    assert_eq!(world_map.areas.len(), 61);
+    assert_eq!((world_map.areas[0].x, world_map.areas[0].y), (184, 133));
+    assert_eq!(world_map.tiles.len(), 20);
+    assert_eq!(world_map.horizontal_tile_count, 4);
+    assert_eq!(world_map.encounter_counters.len(), 373);
+
+    let movies_seen = save.movies_seen.expect("handler 21 should parse");
+    assert_eq!(movies_seen.iter().filter(|&&seen| seen).count(), 1);
+    assert!(movies_seen[2]);
+
+    let skill_use_times = save.skill_use_times.expect("handler 22 should parse");
+    assert!(skill_use_times.iter().flatten().all(|&time| time == 0));
+
+    let party = save
+        .party_members
+        .as_ref()
+        .expect("handler 23 should parse");
+    assert_eq!(party.member_count, 1);
+    assert!(party.member_object_ids.is_empty());
+    assert_eq!(party.level_up_info.len(), save.party_member_count - 1);
+}
@@ -0,0 +1,91 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use fallout_core::map_save::{MAP_VERSION_FALLOUT1, MAP_VERSION_FALLOUT2, MapSave};
+use fallout_core::object::{OBJ_TYPE_CRITTER, obj_type_from_pid};
+
+fn workspace_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+fn map_save_paths(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("Failed to read {:?}: {}", dir, e))
+        .map(|entry| entry.expect("dir entry").path())
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_ascii_uppercase())
+                .unwrap_or_default();
+            name.ends_with(".SAV") && name != "AUTOMAP.SAV" && !name.starts_with("SFALL")
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn load_map(path: &Path) -> (Vec<u8>, MapSave) {
+    let bytes = fs::read(path).unwrap_or_else(|e| panic!("Failed to read {:?}: {}", path, e));
+    let map =
+        MapSave::parse(&bytes).unwrap_or_else(|e| panic!("Failed to parse {:?}: {}", path, e));
+    (bytes, map)
+}
+
+#[test]
+fn fallout1_map_saves_round_trip_byte_for_byte() {
+    let mut checked = 0;
+    for slot in 1..=7 {
+        let dir =
+            workspace_root().join(format!("tests/fallout1_examples/SAVEGAME/SLOT{:02}", slot));
+        for path in map_save_paths(&dir) {
+            let (bytes, map) = load_map(&path);
+            assert!(!map.compressed, "{:?}", path);
+            assert_eq!(map.header.version, MAP_VERSION_FALLOUT1, "{:?}", path);
+            let emitted = map.to_bytes().expect("emit map");
+            assert!(emitted == bytes, "{:?} did not round-trip", path);
+            checked += 1;
+        }
+    }
+    assert!(checked >= 34);
+}
+
+#[test]
+fn fallout2_map_saves_round_trip_through_gzip() {
+    for slot in 1..=2 {
+        let dir = workspace_root().join(format!("tests/fallout2_examples/SLOT{:02}", slot));
+        for path in map_save_paths(&dir) {
+            let (_, map) = load_map(&path);
+            assert!(map.compressed, "{:?}", path);
+            assert_eq!(map.header.version, MAP_VERSION_FALLOUT2, "{:?}", path);
+
+            let payload = map.to_uncompressed_bytes().expect("emit payload");
+            let reparsed = MapSave::parse(&map.to_bytes().expect("emit gzip")).expect("reparse");
+            assert!(reparsed.compressed);
+            assert!(reparsed.to_uncompressed_bytes().expect("re-emit") == payload);
+        }
+    }
+}
+
+#[test]
+fn fallout2_map_save_exposes_header_objects_and_map_vars() {
+    let path = workspace_root().join("tests/fallout2_examples/SLOT01/ARTEMPLE.SAV");
+    let (_, mut map) = load_map(&path);
+
+    assert_eq!(map.name(), "ARTEMPLE.SAV");
+    assert!(map.all_objects().count() > 0);
+    assert!(map.critters().count() > 0);
+    assert!(
+        map.critters()
+            .all(|(_, object)| obj_type_from_pid(object.pid) == OBJ_TYPE_CRITTER)
+    );
+    for (elevation, object) in map.all_objects() {
+        assert_eq!(object.elevation as usize, elevation);
+    }
+
+    if !map.map_vars.is_empty() {
+        map.set_map_var(0, 1234).expect("set map var");
+        let reparsed = MapSave::parse(&map.to_bytes().expect("emit")).expect("reparse");
+        assert_eq!(reparsed.map_var(0), Some(1234));
+    }
+    assert!(map.set_map_var(map.map_vars.len(), 1).is_err());
+}
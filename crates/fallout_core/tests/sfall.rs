@@ -0,0 +1,105 @@
+use std::fs;
+use std::path::PathBuf;
+
+use fallout_core::sfall::{
+    FakePerk, SfallArray, SfallArrayValue, SfallArrays, SfallFile, SfallFileSystem, SfallGlobalKey,
+    SfallGlobals,
+};
+
+fn fallout2_slot_file(name: &str) -> Vec<u8> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../tests/fallout2_examples/SLOT01")
+        .join(name);
+    fs::read(&path).unwrap_or_else(|e| panic!("failed to read {path:?}: {e}"))
+}
+
+fn named(name: &str) -> SfallGlobalKey {
+    SfallGlobalKey::named(name).expect("valid global name")
+}
+
+#[test]
+fn sfall_globals_parse_and_round_trip() {
+    let bytes = fallout2_slot_file("sfallgv.sav");
+    let globals = SfallGlobals::parse(&bytes).expect("failed to parse sfallgv.sav");
+
+    let keys: Vec<String> = globals.vars.iter().map(|var| var.key.to_string()).collect();
+    assert_eq!(keys, ["p_looter", "p_corpse", "p_gitem0"]);
+    assert!(globals.vars.iter().all(|var| var.value == 0));
+    assert_eq!(globals.unique_id, 0x0FFF_FFFF);
+    assert_eq!(globals.added_years, 0);
+    assert!(globals.fake_traits.is_empty());
+    assert!(globals.fake_perks.is_empty());
+    assert!(globals.fake_selectable_perks.is_empty());
+    assert_eq!(globals.to_bytes().expect("emit globals"), bytes);
+}
+
+#[test]
+fn sfall_globals_edits_survive_a_round_trip() {
+    let mut globals =
+        SfallGlobals::parse(&fallout2_slot_file("sfallgv.sav")).expect("failed to parse");
+    globals.set(named("p_looter"), 7);
+    globals.set(SfallGlobalKey::Numbered(42), -3);
+    assert!(globals.remove(&named("p_gitem0")));
+    globals.added_years = 2;
+    globals.fake_perks.push(FakePerk {
+        level: 1,
+        image: 72,
+        name: "Lucky Find".to_string(),
+        description: "You notice things others miss.".to_string(),
+    });
+
+    let reparsed =
+        SfallGlobals::parse(&globals.to_bytes().expect("emit globals")).expect("failed to reparse");
+    assert_eq!(reparsed, globals);
+    assert_eq!(reparsed.get(&named("p_looter")), Some(7));
+    assert_eq!(reparsed.get(&SfallGlobalKey::Numbered(42)), Some(-3));
+    assert_eq!(reparsed.get(&named("p_gitem0")), None);
+
+    assert!(SfallGlobalKey::named("toolong99").is_err());
+    globals.fake_traits.push(FakePerk {
+        level: 1,
+        image: 0,
+        name: "x".repeat(64),
+        description: String::new(),
+    });
+    assert!(globals.to_bytes().is_err());
+}
+
+#[test]
+fn sfall_file_system_and_arrays_round_trip() {
+    let fs_bytes = fallout2_slot_file("sfallfs.sav");
+    let mut file_system = SfallFileSystem::parse(&fs_bytes).expect("failed to parse sfallfs.sav");
+    assert!(file_system.files.is_empty());
+    assert_eq!(file_system.to_bytes().expect("emit fs"), fs_bytes);
+
+    file_system.files.push(SfallFile {
+        name: "notes.txt".to_string(),
+        data: b"hello".to_vec(),
+    });
+    let reparsed = SfallFileSystem::parse(&file_system.to_bytes().expect("emit fs"))
+        .expect("failed to reparse fs");
+    assert_eq!(reparsed, file_system);
+
+    let db_bytes = fallout2_slot_file("sfalldb.sav");
+    let mut arrays = SfallArrays::parse(&db_bytes).expect("failed to parse sfalldb.sav");
+    assert!(arrays.arrays.is_empty());
+    assert_eq!(arrays.to_bytes().expect("emit arrays"), db_bytes);
+
+    arrays.arrays.push(SfallArray {
+        key: SfallArrayValue::Str("kills".to_string()),
+        flags: 1,
+        elements: vec![
+            SfallArrayValue::Str("rat".to_string()),
+            SfallArrayValue::Int(12),
+            SfallArrayValue::Str("gecko".to_string()),
+            SfallArrayValue::Float(1.5),
+        ],
+    });
+    let reparsed = SfallArrays::parse(&arrays.to_bytes().expect("emit arrays"))
+        .expect("failed to reparse arrays");
+    assert_eq!(reparsed, arrays);
+    let kills = reparsed
+        .get(&SfallArrayValue::Str("kills".to_string()))
+        .expect("saved array");
+    assert_eq!(kills.elements[1], SfallArrayValue::Int(12));
+}
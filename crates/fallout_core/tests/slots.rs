@@ -0,0 +1,90 @@
+use std::fs;
+use std::path::PathBuf;
+
+use fallout_core::core_api::{Engine, Game};
+use fallout_core::slots::{
+    LoadMenuPosition, SLOT_INDEX_FILE_NAME, SlotSummary, list_slots, list_slots_with_levels,
+};
+
+fn workspace_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+/// The listing must agree with what a full parse of the slot reports; the
+/// level only when it was asked for.
+fn assert_matches_full_parse(summary: &SlotSummary, with_level: bool) {
+    let bytes = fs::read(summary.path.join("SAVE.DAT")).expect("failed to read SAVE.DAT");
+    let session = Engine::new()
+        .open_bytes(&bytes, None)
+        .expect("failed to parse SAVE.DAT");
+    let snapshot = session.snapshot();
+    assert_eq!(summary.game, snapshot.game);
+    assert_eq!(summary.character_name, snapshot.character_name);
+    assert_eq!(summary.description, snapshot.description);
+    assert_eq!(summary.file_date, snapshot.file_date);
+    assert_eq!(summary.game_date, snapshot.game_date);
+    assert_eq!(summary.game_time, snapshot.game_time);
+    assert_eq!(summary.map_id, snapshot.map_id);
+    assert_eq!(summary.map_filename, snapshot.map_filename);
+    assert_eq!(summary.elevation, snapshot.elevation);
+    assert_eq!(summary.level, with_level.then_some(snapshot.level));
+}
+
+#[test]
+fn lists_fallout2_slots_with_load_menu_position() {
+    let listing = list_slots_with_levels(workspace_root().join("tests/fallout2_examples"))
+        .expect("failed to list slots");
+    assert!(listing.unreadable.is_empty());
+    assert_eq!(
+        listing.position,
+        Some(LoadMenuPosition {
+            list_num: 1,
+            page_offset: 0,
+        })
+    );
+
+    let numbers: Vec<u32> = listing.slots.iter().map(|slot| slot.slot).collect();
+    assert_eq!(numbers, [1, 2]);
+    assert!(listing.slots[0].path.ends_with("SLOT01"));
+    for slot in &listing.slots {
+        assert_eq!(slot.game, Game::Fallout2);
+        assert_matches_full_parse(slot, true);
+    }
+}
+
+#[test]
+fn lists_fallout1_slots_without_an_index_file() {
+    let listing = list_slots(workspace_root().join("tests/fallout1_examples/SAVEGAME"))
+        .expect("failed to list slots");
+    assert!(listing.unreadable.is_empty());
+    assert_eq!(listing.position, None);
+    assert_eq!(listing.slots.len(), 7);
+    for slot in &listing.slots {
+        assert_eq!(slot.game, Game::Fallout1);
+        assert_matches_full_parse(slot, false);
+    }
+}
+
+#[test]
+fn malformed_index_file_only_drops_the_position() {
+    let save_root =
+        std::env::temp_dir().join(format!("fallout_se_slots_bad_index_{}", std::process::id()));
+    let slot_dir = save_root.join("SLOT01");
+    fs::create_dir_all(&slot_dir).expect("failed to create slot dir");
+    fs::copy(
+        workspace_root().join("tests/fallout2_examples/SLOT01/SAVE.DAT"),
+        slot_dir.join("SAVE.DAT"),
+    )
+    .expect("failed to copy SAVE.DAT");
+    fs::write(
+        save_root.join(SLOT_INDEX_FILE_NAME),
+        "[POSITION]\nListNum=two\n",
+    )
+    .expect("failed to write index file");
+
+    let listing = list_slots(&save_root).expect("failed to list slots");
+    assert_eq!(listing.position, None);
+    assert_eq!(listing.slots.len(), 1);
+
+    let _ = fs::remove_dir_all(&save_root);
+}
@@ -1,11 +1,17 @@
 use std::fmt::Write as _;
 
+use fallout_core::automap::{AUTOMAP_TILE_EMPTY, AUTOMAP_TILE_SCENERY, AutomapTiles};
 use fallout_core::core_api::{
-    CharacterExport, Game as CoreGame, InventoryEntry, KillCountEntry, PerkEntry,
-    ResolvedInventoryEntry, Session, SkillEntry, StatEntry, TraitEntry,
+    AddictionEntry, CharacterExport, Game as CoreGame, InventoryEntry, KillCountEntry, Palette,
+    PerkEntry, QueueEvent, QueueEventData, ResolvedInventoryEntry, Session, SkillEntry, StatEntry,
+    THUMBNAIL_HEIGHT, THUMBNAIL_WIDTH, TraitEntry,
 };
 use serde_json::{Map as JsonMap, Value as JsonValue};
 
+mod png;
+
+pub use png::{RgbaImage, decode_png_rgba, encode_png_rgba};
+
 const THREE_COL_WIDTH_A: usize = 25;
 const THREE_COL_WIDTH_B: usize = 24;
 const THREE_COL_WIDTH_C: usize = 25;
@@ -17,6 +23,10 @@ const INVENTORY_COL_WIDTH_C: usize = 23;
 const INVENTORY_CAPS_PID: i32 = 41;
 const STAT_MAX_HP_INDEX: usize = 7;
 const STAT_AGE_INDEX: usize = 33;
+const AUTOMAP_GRID_WIDTH: usize = 200;
+const AUTOMAP_WALL_RGBA: [u8; 4] = [0, 255, 0, 255];
+const AUTOMAP_SCENERY_RGBA: [u8; 4] = [0, 128, 0, 255];
+const AUTOMAP_EMPTY_RGBA: [u8; 4] = [0, 0, 0, 255];
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum JsonStyle {
@@ -156,6 +166,34 @@ pub fn render_json_selected_from_export_with_inventory(
     }
 }
 
+/// The save's load-menu thumbnail as a PNG, coloured with `palette` or, as
+/// an approximation, the built-in grey stand-in.
+pub fn render_thumbnail_png(session: &Session, palette: Option<&Palette>) -> Vec<u8> {
+    encode_png_rgba(
+        &session.thumbnail_rgba_resolved(palette),
+        THUMBNAIL_WIDTH as u32,
+        THUMBNAIL_HEIGHT as u32,
+    )
+}
+
+/// One automap elevation as a 200x200 PNG, one pixel per hex with north up
+/// and east on the right: walls bright green, scenery dark green.
+pub fn render_automap_png(tiles: &AutomapTiles) -> Vec<u8> {
+    let mut rgba = Vec::with_capacity(AUTOMAP_GRID_WIDTH * AUTOMAP_GRID_WIDTH * 4);
+    for row in 0..AUTOMAP_GRID_WIDTH {
+        // Hex columns count up towards the west.
+        for column in (0..AUTOMAP_GRID_WIDTH).rev() {
+            let color = match tiles.tile((row * AUTOMAP_GRID_WIDTH + column) as i32) {
+                AUTOMAP_TILE_EMPTY => AUTOMAP_EMPTY_RGBA,
+                AUTOMAP_TILE_SCENERY => AUTOMAP_SCENERY_RGBA,
+                _ => AUTOMAP_WALL_RGBA,
+            };
+            rgba.extend_from_slice(&color);
+        }
+    }
+    encode_png_rgba(&rgba, AUTOMAP_GRID_WIDTH as u32, AUTOMAP_GRID_WIDTH as u32)
+}
+
 pub fn render_classic_sheet(session: &Session) -> String {
     render_classic_sheet_with_inventory(session, TextRenderOptions::default(), None, None)
 }
@@ -390,6 +428,20 @@ fn default_json(
             None => JsonValue::Null,
         },
     );
+    out.insert(
+        "radiation".to_string(),
+        match export.radiation {
+            Some(v) => JsonValue::from(v),
+            None => JsonValue::Null,
+        },
+    );
+    out.insert(
+        "poison".to_string(),
+        match export.poison {
+            Some(v) => JsonValue::from(v),
+            None => JsonValue::Null,
+        },
+    );
     out.insert("karma".to_string(), JsonValue::from(export.karma));
     out.insert("reputation".to_string(), JsonValue::from(export.reputation));
 
@@ -397,6 +449,10 @@ fn default_json(
     out.insert("stats".to_string(), stats_to_json(&export.stats));
     out.insert("traits".to_string(), traits_to_json(&export.traits));
     out.insert("perks".to_string(), perks_to_json(&export.perks));
+    out.insert(
+        "addictions".to_string(),
+        addictions_to_json(&export.addictions),
+    );
     out.insert("skills".to_string(), skills_to_json(&export.skills));
     out.insert(
         "tagged_skills".to_string(),
@@ -410,6 +466,13 @@ fn default_json(
         "inventory".to_string(),
         inventory_to_json(&export.inventory, inventory),
     );
+    out.insert(
+        "queue_events".to_string(),
+        export
+            .queue_events
+            .as_deref()
+            .map_or(JsonValue::Null, queue_events_to_json),
+    );
 
     out
 }
@@ -428,15 +491,13 @@ fn selected_stats_to_json(
     include_age: bool,
 ) -> JsonValue {
     let mut selected: Vec<&StatEntry> = Vec::new();
-    if include_max_hp {
-        if let Some(max_hp) = stats.iter().find(|stat| stat.index == STAT_MAX_HP_INDEX) {
-            selected.push(max_hp);
-        }
+    if include_max_hp
+        && let Some(max_hp) = stats.iter().find(|stat| stat.index == STAT_MAX_HP_INDEX)
+    {
+        selected.push(max_hp);
     }
-    if include_age {
-        if let Some(age) = stats.iter().find(|stat| stat.index == STAT_AGE_INDEX) {
-            selected.push(age);
-        }
+    if include_age && let Some(age) = stats.iter().find(|stat| stat.index == STAT_AGE_INDEX) {
+        selected.push(age);
     }
     JsonValue::Array(selected.into_iter().map(stat_entry_to_json).collect())
 }
@@ -486,6 +547,24 @@ fn perks_to_json(perks: &[PerkEntry]) -> JsonValue {
     )
 }
 
+fn addictions_to_json(addictions: &[AddictionEntry]) -> JsonValue {
+    JsonValue::Array(
+        addictions
+            .iter()
+            .map(|a: &AddictionEntry| {
+                let mut m = JsonMap::new();
+                m.insert("name".to_string(), JsonValue::String(a.name.clone()));
+                m.insert("active".to_string(), JsonValue::Bool(a.active));
+                m.insert(
+                    "withdrawal_events".to_string(),
+                    JsonValue::from(a.withdrawal_events),
+                );
+                JsonValue::Object(m)
+            })
+            .collect(),
+    )
+}
+
 fn kill_counts_to_json(kill_counts: &[KillCountEntry]) -> JsonValue {
     JsonValue::Array(
         kill_counts
@@ -500,6 +579,48 @@ fn kill_counts_to_json(kill_counts: &[KillCountEntry]) -> JsonValue {
     )
 }
 
+fn queue_events_to_json(events: &[QueueEvent]) -> JsonValue {
+    JsonValue::Array(
+        events
+            .iter()
+            .map(|event| {
+                let mut m = JsonMap::new();
+                m.insert("time".to_string(), JsonValue::from(event.time));
+                m.insert(
+                    "type".to_string(),
+                    JsonValue::String(event.data.name().to_string()),
+                );
+                m.insert("object_id".to_string(), JsonValue::from(event.object_id));
+                match &event.data {
+                    QueueEventData::Drug { stats, modifiers } => {
+                        m.insert("stats".to_string(), JsonValue::from(stats.to_vec()));
+                        m.insert("modifiers".to_string(), JsonValue::from(modifiers.to_vec()));
+                    }
+                    QueueEventData::Withdrawal {
+                        start,
+                        item_pid,
+                        perk,
+                    } => {
+                        m.insert("start".to_string(), JsonValue::from(*start));
+                        m.insert("item_pid".to_string(), JsonValue::from(*item_pid));
+                        m.insert("perk".to_string(), JsonValue::from(*perk));
+                    }
+                    QueueEventData::Script { sid, fixed_param } => {
+                        m.insert("sid".to_string(), JsonValue::from(*sid));
+                        m.insert("fixed_param".to_string(), JsonValue::from(*fixed_param));
+                    }
+                    QueueEventData::Radiation { level, is_healing } => {
+                        m.insert("level".to_string(), JsonValue::from(*level));
+                        m.insert("is_healing".to_string(), JsonValue::from(*is_healing));
+                    }
+                    _ => {}
+                }
+                JsonValue::Object(m)
+            })
+            .collect(),
+    )
+}
+
 fn inventory_to_json(
     inventory: &[InventoryEntry],
     resolved: Option<&[ResolvedInventoryEntry]>,
@@ -521,6 +642,18 @@ fn inventory_to_json(
                     if let Some(item_type) = item.item_type {
                         m.insert("item_type".to_string(), JsonValue::from(item_type));
                     }
+                    if let Some(ammo_pid) = item.ammo_pid {
+                        m.insert("ammo_pid".to_string(), JsonValue::from(ammo_pid));
+                    }
+                    if let Some(ammo_loaded) = item.ammo_loaded {
+                        m.insert("ammo_loaded".to_string(), JsonValue::from(ammo_loaded));
+                    }
+                    if let Some(charges) = item.charges {
+                        m.insert("charges".to_string(), JsonValue::from(charges));
+                    }
+                    if let Some(key_code) = item.key_code {
+                        m.insert("key_code".to_string(), JsonValue::from(key_code));
+                    }
                     JsonValue::Object(m)
                 })
                 .collect(),
@@ -651,30 +784,16 @@ fn render_classic_sheet_impl(
         },
     ];
 
-    struct RightCol {
-        idx: usize,
-        label: &'static str,
-    }
-    let right_cols: [Option<RightCol>; 7] = [
-        Some(RightCol {
-            idx: 13,
-            label: "Sequence",
-        }),
-        Some(RightCol {
-            idx: 14,
-            label: "Healing Rate",
-        }),
-        Some(RightCol {
-            idx: 15,
-            label: "Critical Chance",
-        }),
-        Some(RightCol {
-            idx: 12,
-            label: "Carry Weight",
-        }),
-        None,
-        None,
+    // Radiation and poison are not stats, so the right column is keyed by row
+    // rather than by stat index.
+    let right_labels: [Option<&str>; 7] = [
+        Some("Sequence"),
+        Some("Healing Rate"),
+        Some("Critical Chance"),
+        Some("Carry Weight"),
         None,
+        Some("Radiated"),
+        Some("Poisoned"),
     ];
 
     let current_hp = session.current_hp().unwrap_or(0);
@@ -710,19 +829,21 @@ fn render_classic_sheet_impl(
         line.push_str(": ");
         line.push_str(&mid_val);
 
-        if let Some(ref right) = right_cols[row] {
+        if let Some(right_label) = right_labels[row] {
             let right_val = match row {
-                0 => format!("{:02}", session.stat(right.idx).total),
-                1 => format!("{:02}", session.stat(right.idx).total),
-                2 => format!("{:03}%", session.stat(right.idx).total),
-                3 => format!("{} lbs.", session.stat(right.idx).total),
+                0 => format!("{:02}", session.stat(13).total),
+                1 => format!("{:02}", session.stat(14).total),
+                2 => format!("{:03}%", session.stat(15).total),
+                3 => format!("{} lbs.", session.stat(12).total),
+                5 => format!("{:03}", session.radiation().unwrap_or(0)),
+                6 => format!("{:03}", session.poison().unwrap_or(0)),
                 _ => unreachable!(),
             };
-            let right_start = 64 - right.label.len();
+            let right_start = 64 - right_label.len();
             while line.len() < right_start {
                 line.push(' ');
             }
-            line.push_str(right.label);
+            line.push_str(right_label);
             line.push_str(": ");
             line.push_str(&right_val);
         }
@@ -740,6 +861,7 @@ fn render_classic_sheet_impl(
         traits_buffer.as_slice()
     };
     let perks = session.active_perks();
+    let addictions = session.addictions();
     let skills = session.skills();
     let tagged_skill_indices = session.tagged_skill_indices();
     let kills = if options.verbose {
@@ -751,12 +873,16 @@ fn render_classic_sheet_impl(
 
     write_traits_perks_karma_grid(
         &mut out,
-        &traits,
+        traits,
         &perks,
         snapshot.karma,
         snapshot.reputation,
     );
     writeln!(&mut out).expect("writing to String cannot fail");
+    if !addictions.is_empty() {
+        write_addictions_section(&mut out, &addictions);
+        writeln!(&mut out).expect("writing to String cannot fail");
+    }
     write_skills_kills_grid(&mut out, &skills, &tagged_skill_indices, &kills);
     writeln!(&mut out).expect("writing to String cannot fail");
     write_inventory_section(
@@ -829,6 +955,18 @@ fn write_traits_perks_karma_grid(
     }
 }
 
+fn write_addictions_section(out: &mut String, addictions: &[AddictionEntry]) {
+    writeln!(out, " ::: Addictions :::").expect("writing to String cannot fail");
+    for entry in addictions {
+        if entry.active {
+            writeln!(out, " {}", entry.name).expect("writing to String cannot fail");
+        } else {
+            writeln!(out, " {} (withdrawal pending)", entry.name)
+                .expect("writing to String cannot fail");
+        }
+    }
+}
+
 fn write_skills_kills_grid(
     out: &mut String,
     skills: &[SkillEntry],
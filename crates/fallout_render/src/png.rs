@@ -0,0 +1,232 @@
+use std::io::{Read as _, Write as _};
+
+use flate2::Compression;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+const COLOR_TYPE_GREY: u8 = 0;
+const COLOR_TYPE_RGB: u8 = 2;
+const COLOR_TYPE_INDEXED: u8 = 3;
+const COLOR_TYPE_GREY_ALPHA: u8 = 4;
+const COLOR_TYPE_RGBA: u8 = 6;
+const BIT_DEPTH: u8 = 8;
+const FILTER_NONE: u8 = 0;
+const FILTER_SUB: u8 = 1;
+const FILTER_UP: u8 = 2;
+const FILTER_AVERAGE: u8 = 3;
+const FILTER_PAETH: u8 = 4;
+/// Largest image `decode_png_rgba` accepts, in pixels (4096x4096). Far more
+/// than a thumbnail needs, and it bounds what a hostile file can make us
+/// allocate.
+const MAX_DECODED_PIXELS: u64 = 4096 * 4096;
+
+/// An image decoded to 8-bit RGBA pixels, row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+/// Encode 8-bit RGBA pixels, row by row, as a PNG file.
+///
+/// # Panics
+///
+/// Panics if `rgba` is not `width * height * 4` bytes long.
+pub fn encode_png_rgba(rgba: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let stride = width as usize * 4;
+    assert_eq!(
+        rgba.len(),
+        stride * height as usize,
+        "RGBA buffer does not match {width}x{height}"
+    );
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&width.to_be_bytes());
+    ihdr.extend_from_slice(&height.to_be_bytes());
+    // Bit depth, colour type, then default compression, filter and interlace.
+    ihdr.extend_from_slice(&[BIT_DEPTH, COLOR_TYPE_RGBA, 0, 0, 0]);
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    for row in rgba.chunks_exact(stride.max(1)) {
+        encoder
+            .write_all(&[FILTER_NONE])
+            .and_then(|()| encoder.write_all(row))
+            .expect("writing to Vec cannot fail");
+    }
+    let idat = encoder.finish().expect("writing to Vec cannot fail");
+
+    let mut out = PNG_SIGNATURE.to_vec();
+    write_chunk(&mut out, b"IHDR", &ihdr);
+    write_chunk(&mut out, b"IDAT", &idat);
+    write_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+/// Decode a PNG file to RGBA pixels. Only 8-bit, non-interlaced images of up
+/// to 4096x4096 pixels are supported, which covers what image editors export
+/// by default.
+pub fn decode_png_rgba(bytes: &[u8]) -> Result<RgbaImage, String> {
+    let mut rest = bytes
+        .strip_prefix(PNG_SIGNATURE.as_slice())
+        .ok_or("not a PNG file")?;
+
+    let mut header = None;
+    let mut palette: &[u8] = &[];
+    let mut transparency: &[u8] = &[];
+    let mut idat = Vec::new();
+    while rest.len() >= 12 {
+        let len = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+        let kind = &rest[4..8];
+        // Length, type and data, then a 4-byte CRC.
+        let data_end = len.checked_add(8).ok_or("truncated PNG chunk")?;
+        let chunk_end = data_end.checked_add(4).ok_or("truncated PNG chunk")?;
+        let data = rest.get(8..data_end).ok_or("truncated PNG chunk")?;
+        match kind {
+            b"IHDR" => header = Some(data),
+            b"PLTE" => palette = data,
+            b"tRNS" => transparency = data,
+            b"IDAT" => idat.extend_from_slice(data),
+            b"IEND" => break,
+            _ => {}
+        }
+        rest = rest.get(chunk_end..).ok_or("truncated PNG chunk")?;
+    }
+
+    let header = header
+        .filter(|h| h.len() == 13)
+        .ok_or("missing PNG header")?;
+    let width = u32::from_be_bytes(header[0..4].try_into().unwrap());
+    let height = u32::from_be_bytes(header[4..8].try_into().unwrap());
+    let (bit_depth, color_type, interlace) = (header[8], header[9], header[12]);
+    if bit_depth != BIT_DEPTH || interlace != 0 {
+        return Err(format!(
+            "unsupported PNG: bit depth {bit_depth}, interlace {interlace} (need 8-bit, non-interlaced)"
+        ));
+    }
+    let channels = match color_type {
+        COLOR_TYPE_GREY | COLOR_TYPE_INDEXED => 1,
+        COLOR_TYPE_GREY_ALPHA => 2,
+        COLOR_TYPE_RGB => 3,
+        COLOR_TYPE_RGBA => 4,
+        other => return Err(format!("unsupported PNG colour type {other}")),
+    };
+
+    if width == 0 || height == 0 || u64::from(width) * u64::from(height) > MAX_DECODED_PIXELS {
+        return Err(format!(
+            "unsupported PNG size {width}x{height} (need 1 to {MAX_DECODED_PIXELS} pixels)"
+        ));
+    }
+    let too_large = || format!("PNG size {width}x{height} is too large");
+    let stride = (width as usize)
+        .checked_mul(channels)
+        .ok_or_else(too_large)?;
+    // Every row starts with its filter byte.
+    let raw_len = stride
+        .checked_add(1)
+        .and_then(|row| row.checked_mul(height as usize))
+        .ok_or_else(too_large)?;
+    let rgba_len = (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|pixels| pixels.checked_mul(4))
+        .ok_or_else(too_large)?;
+
+    // Inflate no more than the image needs, however much the data expands to.
+    let mut raw = Vec::new();
+    ZlibDecoder::new(idat.as_slice())
+        .take(raw_len as u64)
+        .read_to_end(&mut raw)
+        .map_err(|e| format!("invalid PNG image data: {e}"))?;
+    if raw.len() < raw_len {
+        return Err("truncated PNG image data".to_string());
+    }
+
+    let mut rgba = Vec::with_capacity(rgba_len);
+    let mut previous = vec![0u8; stride];
+    for line in raw.chunks_exact(stride + 1).take(height as usize) {
+        let mut row = line[1..].to_vec();
+        unfilter_row(line[0], &mut row, &previous, channels)?;
+        for pixel in row.chunks_exact(channels) {
+            let color = match color_type {
+                COLOR_TYPE_GREY => [pixel[0], pixel[0], pixel[0], u8::MAX],
+                COLOR_TYPE_GREY_ALPHA => [pixel[0], pixel[0], pixel[0], pixel[1]],
+                COLOR_TYPE_RGB => [pixel[0], pixel[1], pixel[2], u8::MAX],
+                COLOR_TYPE_RGBA => [pixel[0], pixel[1], pixel[2], pixel[3]],
+                _ => {
+                    let index = usize::from(pixel[0]);
+                    let rgb = palette
+                        .get(index * 3..index * 3 + 3)
+                        .ok_or("PNG palette index out of range")?;
+                    let alpha = transparency.get(index).copied().unwrap_or(u8::MAX);
+                    [rgb[0], rgb[1], rgb[2], alpha]
+                }
+            };
+            rgba.extend_from_slice(&color);
+        }
+        previous = row;
+    }
+
+    Ok(RgbaImage {
+        width,
+        height,
+        rgba,
+    })
+}
+
+fn unfilter_row(filter: u8, row: &mut [u8], previous: &[u8], bpp: usize) -> Result<(), String> {
+    for i in 0..row.len() {
+        let left = if i >= bpp { row[i - bpp] } else { 0 };
+        let up = previous[i];
+        let up_left = if i >= bpp { previous[i - bpp] } else { 0 };
+        let predictor = match filter {
+            FILTER_NONE => 0,
+            FILTER_SUB => left,
+            FILTER_UP => up,
+            FILTER_AVERAGE => ((u16::from(left) + u16::from(up)) / 2) as u8,
+            FILTER_PAETH => paeth(left, up, up_left),
+            other => return Err(format!("invalid PNG filter type {other}")),
+        };
+        row[i] = row[i].wrapping_add(predictor);
+    }
+    Ok(())
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = i16::from(a) + i16::from(b) - i16::from(c);
+    let (pa, pb, pc) = (
+        (p - i16::from(a)).abs(),
+        (p - i16::from(b)).abs(),
+        (p - i16::from(c)).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(kind.iter().chain(data));
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32<'a>(bytes: impl Iterator<Item = &'a u8>) -> u32 {
+    let mut crc = u32::MAX;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
@@ -1,11 +1,12 @@
 use std::path::PathBuf;
 
+use fallout_core::automap::{AUTOMAP_MAP_COUNT_FALLOUT2, AUTOMAP_TILE_WALL, Automap, AutomapTiles};
 use fallout_core::core_api::{Engine, ResolvedInventoryEntry, Session};
 use fallout_render::{
-    FieldSelection, JsonStyle, TextRenderOptions, render_classic_sheet,
-    render_classic_sheet_with_inventory, render_classic_sheet_with_options, render_json_full,
-    render_json_full_from_export, render_json_full_with_inventory, render_json_selected,
-    render_json_selected_from_export,
+    FieldSelection, JsonStyle, TextRenderOptions, decode_png_rgba, render_automap_png,
+    render_classic_sheet, render_classic_sheet_with_inventory, render_classic_sheet_with_options,
+    render_json_full, render_json_full_from_export, render_json_full_with_inventory,
+    render_json_selected, render_json_selected_from_export, render_thumbnail_png,
 };
 use serde_json::Value;
 
@@ -57,16 +58,20 @@ fn full_json_uses_canonical_top_level_order() {
             "elevation",
             "global_var_count",
             "hp",
+            "radiation",
+            "poison",
             "karma",
             "reputation",
             "special",
             "stats",
             "traits",
             "perks",
+            "addictions",
             "skills",
             "tagged_skills",
             "kill_counts",
             "inventory",
+            "queue_events",
         ]
     );
 }
@@ -197,6 +202,8 @@ fn classic_sheet_contains_expected_sections() {
     assert!(rendered.contains("::: Traits :::"));
     assert!(rendered.contains("::: Perks :::"));
     assert!(rendered.contains("::: Karma :::"));
+    assert!(rendered.contains("Radiated: 000"));
+    assert!(rendered.contains("Poisoned: 000"));
 
     let json = render_json_full(&session, JsonStyle::CanonicalV1);
     let json: Value = serde_json::from_str(
@@ -204,6 +211,8 @@ fn classic_sheet_contains_expected_sections() {
     )
     .expect("serialized json should parse");
     assert_eq!(json["game"], "Fallout1");
+    assert_eq!(json["radiation"], 0);
+    assert_eq!(json["poison"], 0);
 }
 
 #[test]
@@ -232,6 +241,24 @@ fn classic_sheet_includes_plain_text_detail_sections() {
     assert!(!rendered.contains("pid=FFFFFFFF"));
 }
 
+#[test]
+fn classic_sheet_lists_addictions_when_present() {
+    const PERK_BUFFOUT_ADDICTION: usize = 54;
+
+    let mut session = session_from_path(fallout1_save_path(1));
+    assert!(!render_classic_sheet(&session).contains("::: Addictions :::"));
+
+    session
+        .set_perk_rank(PERK_BUFFOUT_ADDICTION, 1)
+        .expect("failed to set addiction");
+    let rendered = render_classic_sheet(&session);
+    assert!(rendered.contains(" ::: Addictions :::\n Buffout Addiction\n"));
+
+    let json = render_json_full(&session, JsonStyle::CanonicalV1);
+    assert_eq!(json["addictions"][0]["name"], "Buffout Addiction");
+    assert_eq!(json["addictions"][0]["active"], true);
+}
+
 #[test]
 fn classic_sheet_verbose_includes_zero_kill_counts() {
     let session = session_from_path(fallout1_save_path(1));
@@ -257,6 +284,10 @@ fn json_inventory_can_include_resolved_item_metadata() {
             name: Some(format!("Item {}", item.pid)),
             base_weight: Some(1),
             item_type: Some(0),
+            ammo_pid: None,
+            ammo_loaded: None,
+            charges: None,
+            key_code: None,
         })
         .collect();
 
@@ -282,6 +313,10 @@ fn classic_sheet_can_include_resolved_inventory_and_total_weight() {
             name: Some(format!("Item {}", item.pid)),
             base_weight: Some(2),
             item_type: Some(0),
+            ammo_pid: None,
+            ammo_loaded: None,
+            charges: None,
+            key_code: None,
         })
         .collect();
 
@@ -337,3 +372,61 @@ fn full_json_skills_include_breakdown_fields() {
     let total = first["total"].as_i64().expect("total should be a number");
     assert_eq!(raw + bonus, total);
 }
+
+#[test]
+fn thumbnail_png_encodes_the_save_preview() {
+    let session = session_from_path(fallout1_save_path(1));
+    let png = render_thumbnail_png(&session, None);
+
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&png[12..16], b"IHDR");
+    assert_eq!(&png[16..20], &224u32.to_be_bytes());
+    assert_eq!(&png[20..24], &133u32.to_be_bytes());
+    assert_eq!(&png[png.len() - 12..], b"\0\0\0\0IEND\xaeB`\x82");
+
+    let rgba = session.thumbnail_rgba();
+    assert_eq!(rgba.len(), 224 * 133 * 4);
+    let index = session.thumbnail_indices()[0];
+    assert_eq!(rgba[..4], [index, index, index, 255]);
+
+    let decoded = decode_png_rgba(&png).expect("encoded thumbnail should decode");
+    assert_eq!((decoded.width, decoded.height), (224, 133));
+    assert_eq!(decoded.rgba, rgba);
+    assert!(decode_png_rgba(&png[..40]).is_err());
+    assert!(decode_png_rgba(b"GIF89a").is_err());
+
+    // Hostile sizes are rejected instead of overflowing or over-allocating.
+    let mut huge_chunk = png.clone();
+    huge_chunk[8..12].copy_from_slice(&u32::MAX.to_be_bytes());
+    assert!(decode_png_rgba(&huge_chunk).is_err());
+    let mut huge_image = png.clone();
+    huge_image[16..24].copy_from_slice(&[0xFF; 8]);
+    assert!(decode_png_rgba(&huge_image).is_err());
+    let mut taller = png;
+    taller[20..24].copy_from_slice(&134u32.to_be_bytes());
+    assert!(decode_png_rgba(&taller).is_err());
+}
+
+#[test]
+fn automap_png_draws_explored_hexes() {
+    let bytes = std::fs::read(workspace_root().join("tests/fallout2_examples/SLOT01/AUTOMAP.SAV"))
+        .expect("failed to read automap");
+    let automap =
+        Automap::parse(&bytes, AUTOMAP_MAP_COUNT_FALLOUT2).expect("failed to parse automap");
+    let tiles = automap.tiles(126, 0).expect("ARTEMPLE should be explored");
+
+    let image = decode_png_rgba(&render_automap_png(tiles)).expect("automap PNG should decode");
+    assert_eq!((image.width, image.height), (200, 200));
+    let lit = image
+        .rgba
+        .chunks_exact(4)
+        .filter(|pixel| pixel[1] != 0)
+        .count();
+    assert_eq!(lit, tiles.explored_count());
+
+    // Hex 0 is the north-east corner.
+    let mut corner = AutomapTiles::default();
+    corner.set_tile(0, AUTOMAP_TILE_WALL);
+    let image = decode_png_rgba(&render_automap_png(&corner)).expect("automap PNG should decode");
+    assert_eq!(image.rgba[199 * 4..200 * 4], [0, 255, 0, 255]);
+}
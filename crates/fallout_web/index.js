@@ -13,12 +13,15 @@ const elements = {
   downloadOutput: document.getElementById("download-output"),
   jsonToggle: document.getElementById("json-toggle"),
   output: document.getElementById("output"),
+  thumbnail: document.getElementById("thumbnail"),
+  thumbnailNote: document.getElementById("thumbnail-note"),
   status: document.getElementById("status"),
 };
 
 const state = {
   renderedText: "",
   outputFilename: "sheet.txt",
+  thumbnailUrl: null,
   ready: false,
   initError: null,
 };
@@ -28,7 +31,25 @@ function setStatus(message, kind = "info") {
   elements.status.dataset.kind = kind;
 }
 
+function setThumbnail(pngBytes) {
+  if (state.thumbnailUrl) {
+    URL.revokeObjectURL(state.thumbnailUrl);
+    state.thumbnailUrl = null;
+  }
+  if (!pngBytes) {
+    elements.thumbnail.hidden = true;
+    elements.thumbnailNote.hidden = true;
+    elements.thumbnail.removeAttribute("src");
+    return;
+  }
+  state.thumbnailUrl = URL.createObjectURL(new Blob([pngBytes], { type: "image/png" }));
+  elements.thumbnail.src = state.thumbnailUrl;
+  elements.thumbnail.hidden = false;
+  elements.thumbnailNote.hidden = false;
+}
+
 function resetOutput() {
+  setThumbnail(null);
   state.renderedText = "";
   state.outputFilename = "sheet.txt";
   elements.output.textContent = "";
@@ -109,6 +130,7 @@ async function renderFile(file) {
       normalizeFilename(file.name),
       wantsJsonOutput ? "json" : "txt",
     );
+    setThumbnail(wasmBindings.render_save_thumbnail_png(bytes, options));
     setStatus(`Rendered ${file.name}.`, "ok");
   } catch (error) {
     resetOutput();
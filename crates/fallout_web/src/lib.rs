@@ -1,7 +1,7 @@
 use fallout_core::core_api::{CharacterExport, Engine, Game as CoreGame, Session};
 use fallout_render::{
     JsonStyle, TextRenderOptions, render_classic_sheet_with_inventory,
-    render_json_full_with_inventory,
+    render_json_full_with_inventory, render_thumbnail_png,
 };
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
@@ -14,22 +14,13 @@ pub struct WebRenderOptions {
     pub metadata: Option<MetadataOptions>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 #[serde(default)]
 pub struct MetadataOptions {
     pub mode: String,
     pub payload: Option<serde_json::Value>,
 }
 
-impl Default for MetadataOptions {
-    fn default() -> Self {
-        Self {
-            mode: String::new(),
-            payload: None,
-        }
-    }
-}
-
 #[derive(Debug, Clone)]
 struct WebError {
     code: &'static str,
@@ -73,6 +64,15 @@ pub fn render_save_text(save_bytes: &[u8], options: JsValue) -> Result<String, J
     render_save_text_impl(save_bytes, &parsed_options).map_err(|err| err.to_js_value())
 }
 
+/// PNG bytes of the save's load-menu thumbnail. The game's palette is not
+/// available in the browser, so the colours are the built-in grey stand-in
+/// and only approximate the picture.
+#[wasm_bindgen]
+pub fn render_save_thumbnail_png(save_bytes: &[u8], options: JsValue) -> Result<Vec<u8>, JsValue> {
+    let parsed_options = parse_options(options).map_err(|err| err.to_js_value())?;
+    render_save_thumbnail_png_impl(save_bytes, &parsed_options).map_err(|err| err.to_js_value())
+}
+
 #[wasm_bindgen]
 pub fn export_save_json(save_bytes: &[u8], options: JsValue) -> Result<String, JsValue> {
     let parsed_options = parse_options(options).map_err(|err| err.to_js_value())?;
@@ -126,6 +126,14 @@ fn render_save_text_impl(
     ))
 }
 
+fn render_save_thumbnail_png_impl(
+    save_bytes: &[u8],
+    options: &WebRenderOptions,
+) -> Result<Vec<u8>, WebError> {
+    let session = open_session(save_bytes, options)?;
+    Ok(render_thumbnail_png(&session, None))
+}
+
 fn export_save_json_impl(
     save_bytes: &[u8],
     options: &WebRenderOptions,
@@ -264,6 +272,7 @@ mod tests {
     use super::{
         CharacterExport, CoreGame, WebRenderOptions, apply_json_to_save_impl,
         export_save_json_impl, parse_game_hint, render_save_text_impl,
+        render_save_thumbnail_png_impl,
     };
 
     #[test]
@@ -309,6 +318,14 @@ mod tests {
         assert!(rendered.contains("PERSONNEL RECORD"));
     }
 
+    #[test]
+    fn render_save_thumbnail_png_impl_emits_png() {
+        let bytes = fixture_bytes("tests/fallout2_examples/SLOT01/SAVE.DAT");
+        let png = render_save_thumbnail_png_impl(&bytes, &WebRenderOptions::default())
+            .expect("thumbnail should render");
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    }
+
     #[test]
     fn render_save_text_impl_rejects_empty_payload() {
         let err = render_save_text_impl(&[], &WebRenderOptions::default())
@@ -387,7 +404,7 @@ mod tests {
             serde_json::to_string_pretty(&edited).expect("edited export should serialize");
         let payload = apply_json_to_save_impl(&bytes, &edited_json, &WebRenderOptions::default())
             .expect("apply should succeed");
-        assert!(payload.updated_bytes.len() > 0);
+        assert!(!payload.updated_bytes.is_empty());
         assert!(payload.filename_hint.ends_with("_edited.SAVE.DAT"));
 
         let reparsed = Engine::new()